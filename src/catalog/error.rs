@@ -1,9 +1,12 @@
+use crate::data::types::Data;
 use derive_more::{Display, Error};
 
 #[derive(Error, Display, Debug)]
 pub enum CatalogError {
     #[display(fmt = "Table with name '{}' already exists", name)]
     TableExists { name: String },
+    #[display(fmt = "Table name '{}' is reserved for internal use", name)]
+    ReservedTableName { name: String },
     #[display(fmt = "Table with name '{}' does not exist", name)]
     TableDoesNotExist { name: String },
     #[display(
@@ -15,6 +18,28 @@ pub enum CatalogError {
     },
     #[display(fmt = "Column with name '{}' already exists", name)]
     ColumnExists { name: String },
+    /// Primary key `key` of column `column` already exists in table `table`.
+    #[display(
+        fmt = "duplicate key value violates primary key of table '{table}': key ({column})=({key}) already exists"
+    )]
+    UniqueViolation {
+        table: String,
+        column: String,
+        key: Data,
+    },
+    /// A row is proposed more than once by `INSERT ... ON CONFLICT DO UPDATE`.
+    #[display(
+        fmt = "ON CONFLICT DO UPDATE command cannot affect row a second time"
+    )]
+    RowAffectedTwice,
+    #[display(fmt = "Type with name '{}' already exists", name)]
+    TypeExists { name: String },
+    #[display(fmt = "Function with name '{}' already exists", name)]
+    FunctionExists { name: String },
+    #[display(fmt = "unrecognized configuration parameter '{}'", name)]
+    UnrecognizedSetting { name: String },
+    #[display(fmt = "invalid value for parameter '{}': '{}'", name, value)]
+    InvalidSettingValue { name: String, value: String },
 }
 
 pub type CatalogResult<T> = Result<T, CatalogError>;
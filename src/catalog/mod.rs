@@ -3,19 +3,31 @@
 pub mod column;
 pub mod error;
 pub mod schema;
+pub mod settings;
 pub mod vinyl_table;
+pub mod vinyl_type;
 
 use crate::{
     as_variant,
-    data::{tuple::Tuple, types::Data},
+    ctx::parse_statement,
+    data::{enum_type::EnumType, tuple::Tuple, types::Data},
     error::Result,
-    plan::create_table::create_table_to_name_schema,
+    function::{
+        aggregate::AggregateFunction, scalar::ScalarFunction, FunctionRegistry,
+    },
+    plan::{
+        create_table::create_table_to_name_schema,
+        create_type::create_type_to_enum_type,
+    },
+    sequence::Currvals,
     storage_engine::StorageEngine,
 };
 use error::{CatalogError, CatalogResult};
 use indexmap::map::{Entry, IndexMap};
 use schema::Schema;
+use settings::Settings;
 use sqlparser::{dialect::PostgreSqlDialect, parser::Parser};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
 
 /// A VinylDB table.
 #[derive(Debug, Clone)]
@@ -52,14 +64,34 @@ impl Table {
 }
 
 /// VinylDB catalog
+///
+/// The tables and types are shared by all the sessions, the functions and
+/// settings are per session.
 #[derive(Debug, Default)]
 pub struct Catalog {
-    tables: IndexMap<String, Table>,
+    tables: Arc<RwLock<IndexMap<String, Arc<Table>>>>,
+    /// User-defined types.
+    types: Arc<RwLock<IndexMap<String, Arc<EnumType>>>>,
+    /// Functions are not persisted, user-defined ones have to be registered
+    /// every time VinylDB starts.
+    functions: FunctionRegistry,
+    /// Settings of the current session.
+    settings: Settings,
 }
 
 impl Catalog {
     /// Create a catalog
     pub fn new(storage: &StorageEngine) -> Result<Self> {
+        // types are loaded first as they can be used by tables
+        let mut types = IndexMap::new();
+        let tree = storage.get_tree_of_table(vinyl_type::TABLE_NAME).unwrap();
+        for res_tuple in tree.iter().values() {
+            let tuple = Tuple::decode(res_tuple?, &vinyl_type::SCHEMA);
+            let sql = as_variant!(Data::String, tuple.get(1).unwrap());
+            let enum_type = create_type_to_enum_type(parse_statement(sql)?)?;
+            types.insert(enum_type.name().to_string(), Arc::new(enum_type));
+        }
+
         let tree = storage.get_tree_of_table(vinyl_table::TABLE_NAME).unwrap();
         let vinyl_table = Table::new(
             vinyl_table::TABLE_NAME.to_string(),
@@ -67,7 +99,7 @@ impl Catalog {
             0,
         );
         let mut tables = IndexMap::new();
-        tables.insert(vinyl_table::TABLE_NAME.into(), vinyl_table);
+        tables.insert(vinyl_table::TABLE_NAME.into(), Arc::new(vinyl_table));
         for res_tuple in tree.iter().values() {
             let tuple = Tuple::decode(res_tuple?, &vinyl_table::SCHEMA);
             let sql = as_variant!(Data::String, tuple.get(1).unwrap());
@@ -77,18 +109,47 @@ impl Catalog {
             // SAFETY:
             // The passed statement is  guaranteed to be a `Statement::CreateTable`
             let (name, schema) =
-                unsafe { create_table_to_name_schema(statement)? };
+                unsafe { create_table_to_name_schema(&types, statement)? };
             let table = Table::new(name.clone(), schema, 0);
 
-            tables.insert(name, table);
+            tables.insert(name, Arc::new(table));
         }
 
-        Ok(Self { tables })
+        let mut functions = FunctionRegistry::default();
+        functions.register_sequence_functions(
+            storage.sequences(),
+            &Arc::new(Currvals::default()),
+        );
+
+        Ok(Self {
+            tables: Arc::new(RwLock::new(tables)),
+            types: Arc::new(RwLock::new(types)),
+            functions,
+            settings: Settings::default(),
+        })
+    }
+
+    /// Return the catalog of another session, which shares the tables and
+    /// types with this one, and starts with the functions of this one, the
+    /// default settings, and no values of `currval()`.
+    pub fn new_session(&self, storage: &StorageEngine) -> Self {
+        let mut functions = self.functions.clone();
+        functions.register_sequence_functions(
+            storage.sequences(),
+            &Arc::new(Currvals::default()),
+        );
+
+        Self {
+            tables: Arc::clone(&self.tables),
+            types: Arc::clone(&self.types),
+            functions,
+            settings: Settings::default(),
+        }
     }
 
-    pub fn add_table(&mut self, table: Table) -> CatalogResult<()> {
-        match self.tables.entry(table.name.clone()) {
-            Entry::Vacant(v) => v.insert(table),
+    pub fn add_table(&self, table: Table) -> CatalogResult<()> {
+        match self.tables.write().unwrap().entry(table.name.clone()) {
+            Entry::Vacant(v) => v.insert(Arc::new(table)),
             Entry::Occupied(_) => {
                 return Err(CatalogError::TableExists { name: table.name })
             }
@@ -97,13 +158,22 @@ impl Catalog {
         Ok(())
     }
 
-    pub fn tables(&self) -> &IndexMap<String, Table> {
-        &self.tables
+    /// Remove table `name`, nothing happens if it does not exist.
+    pub fn remove_table(&self, name: &str) {
+        self.tables.write().unwrap().shift_remove(name);
+    }
+
+    /// Return all the tables, in the order they are created.
+    pub fn tables(&self) -> Vec<Arc<Table>> {
+        self.tables.read().unwrap().values().cloned().collect()
     }
 
-    pub fn get_table(&self, name: &str) -> CatalogResult<&Table> {
+    pub fn get_table(&self, name: &str) -> CatalogResult<Arc<Table>> {
         self.tables
+            .read()
+            .unwrap()
             .get(name)
+            .cloned()
             .ok_or_else(|| CatalogError::TableDoesNotExist {
                 name: name.to_string(),
             })
@@ -112,4 +182,54 @@ impl Catalog {
     pub fn contains_table(&self, name: &str) -> bool {
         self.get_table(name).is_ok()
     }
+
+    pub fn add_type(&self, enum_type: EnumType) -> CatalogResult<()> {
+        match self
+            .types
+            .write()
+            .unwrap()
+            .entry(enum_type.name().to_string())
+        {
+            Entry::Vacant(v) => v.insert(Arc::new(enum_type)),
+            Entry::Occupied(o) => {
+                return Err(CatalogError::TypeExists {
+                    name: o.key().clone(),
+                })
+            }
+        };
+
+        Ok(())
+    }
+
+    pub fn types(
+        &self,
+    ) -> RwLockReadGuard<'_, IndexMap<String, Arc<EnumType>>> {
+        self.types.read().unwrap()
+    }
+
+    pub fn functions(&self) -> &FunctionRegistry {
+        &self.functions
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+
+    pub fn register_aggregate_function(
+        &mut self,
+        func: AggregateFunction,
+    ) -> CatalogResult<()> {
+        self.functions.register_aggregate_function(func)
+    }
+
+    pub fn register_scalar_function(
+        &mut self,
+        func: ScalarFunction,
+    ) -> CatalogResult<()> {
+        self.functions.register_scalar_function(func)
+    }
 }
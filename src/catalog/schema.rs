@@ -1,11 +1,21 @@
 use super::error::{CatalogError, CatalogResult};
-use crate::data::types::DataType;
-use indexmap::{map::Entry, IndexMap};
+use crate::data::{collation::Collation, types::DataType};
+use indexmap::{map::Entry, IndexMap, IndexSet};
+use sqlparser::ast::Expr as SqlExpr;
 
 /// Describes the metadata of an ordered sequence of relative types.
 #[derive(Debug, Clone)]
 pub struct Schema {
     columns: IndexMap<String, DataType>,
+    /// Collations of string columns, the ones not in it are
+    /// [`Collation::Binary`].
+    collations: IndexMap<String, Collation>,
+    /// `DEFAULT` expressions of columns, they are converted and evaluated
+    /// every time they are used, as they could be `now()`.
+    defaults: IndexMap<String, SqlExpr>,
+    /// Identity columns that are `GENERATED ALWAYS`, values can not be
+    /// inserted into them.
+    generated_always: IndexSet<String>,
 }
 
 impl Schema {
@@ -13,6 +23,9 @@ impl Schema {
     pub fn new(fields: impl IntoIterator<Item = (String, DataType)>) -> Self {
         Schema {
             columns: fields.into_iter().collect(),
+            collations: IndexMap::new(),
+            defaults: IndexMap::new(),
+            generated_always: IndexSet::new(),
         }
     }
 
@@ -32,15 +45,54 @@ impl Schema {
             }
         }
 
-        Ok(Self { columns: ret })
+        Ok(Self {
+            columns: ret,
+            collations: IndexMap::new(),
+            defaults: IndexMap::new(),
+            generated_always: IndexSet::new(),
+        })
     }
 
     pub fn empty() -> Self {
         Self {
             columns: IndexMap::new(),
+            collations: IndexMap::new(),
+            defaults: IndexMap::new(),
+            generated_always: IndexSet::new(),
         }
     }
 
+    /// Set the collations of columns, the binary ones can be omitted.
+    pub fn with_collations(
+        mut self,
+        collations: impl IntoIterator<Item = (String, Collation)>,
+    ) -> Self {
+        self.collations.extend(
+            collations
+                .into_iter()
+                .filter(|(_, collation)| *collation != Collation::Binary),
+        );
+        self
+    }
+
+    /// Set the `DEFAULT` expressions of columns.
+    pub fn with_defaults(
+        mut self,
+        defaults: impl IntoIterator<Item = (String, SqlExpr)>,
+    ) -> Self {
+        self.defaults.extend(defaults);
+        self
+    }
+
+    /// Set the identity columns that are `GENERATED ALWAYS`.
+    pub fn with_generated_always(
+        mut self,
+        columns: impl IntoIterator<Item = String>,
+    ) -> Self {
+        self.generated_always.extend(columns);
+        self
+    }
+
     pub fn column_names(&self) -> impl Iterator<Item = &str> {
         self.columns.keys().map(|str| str as &str)
     }
@@ -66,6 +118,22 @@ impl Schema {
             })
     }
 
+    /// Return the collation of column `name`.
+    pub fn column_collation(&self, name: &str) -> Collation {
+        self.collations.get(name).copied().unwrap_or_default()
+    }
+
+    /// Return the `DEFAULT` expression of column `name`.
+    pub fn column_default(&self, name: &str) -> Option<&SqlExpr> {
+        self.defaults.get(name)
+    }
+
+    /// Return true if column `name` is an identity column that is
+    /// `GENERATED ALWAYS`.
+    pub fn is_generated_always(&self, name: &str) -> bool {
+        self.generated_always.contains(name)
+    }
+
     pub fn n_columns(&self) -> usize {
         self.columns.len()
     }
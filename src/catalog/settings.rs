@@ -0,0 +1,85 @@
+//! Settings of the current session, which are changed by `SET` and shown by
+//! `SHOW`.
+
+use super::error::{CatalogError, CatalogResult};
+use crate::data::datetime::{display_time_zone, parse_time_zone};
+use chrono::FixedOffset;
+
+/// Settings of the current session.
+///
+/// Like functions, they are not persisted, every session starts with the
+/// default values.
+#[derive(Debug, Copy, Clone)]
+pub struct Settings {
+    /// The time zone used to display `TIMESTAMPTZ` values and to parse the
+    /// ones without a UTC offset.
+    pub time_zone: FixedOffset,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            time_zone: FixedOffset::east_opt(0).unwrap(),
+        }
+    }
+}
+
+impl Settings {
+    pub const TIME_ZONE: &'static str = "timezone";
+
+    /// Return the value of the setting `name`, case-insensitively.
+    pub fn get(&self, name: &str) -> CatalogResult<String> {
+        match name.to_lowercase().as_str() {
+            Self::TIME_ZONE => Ok(display_time_zone(self.time_zone)),
+            _ => Err(CatalogError::UnrecognizedSetting {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    /// Set the setting `name` to `value`, `DEFAULT` resets it to the default
+    /// value.
+    pub fn set(&mut self, name: &str, value: &str) -> CatalogResult<()> {
+        let default = Self::default();
+        let is_default = value.eq_ignore_ascii_case("DEFAULT");
+        let invalid = || CatalogError::InvalidSettingValue {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+
+        match name.to_lowercase().as_str() {
+            Self::TIME_ZONE if is_default => self.time_zone = default.time_zone,
+            Self::TIME_ZONE => {
+                self.time_zone = parse_time_zone(value).ok_or_else(invalid)?
+            }
+            _ => {
+                return Err(CatalogError::UnrecognizedSetting {
+                    name: name.to_string(),
+                })
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn get_and_set() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.get("TimeZone").unwrap(), "UTC");
+        settings.set("timezone", "-05:30").unwrap();
+        assert_eq!(settings.get("timezone").unwrap(), "-05:30");
+        assert!(settings.set("timezone", "Mars/Olympus").is_err());
+        assert_eq!(settings.get("timezone").unwrap(), "-05:30");
+        settings.set("timezone", "default").unwrap();
+        assert_eq!(settings.get("timezone").unwrap(), "UTC");
+
+        assert!(settings.get("foo").is_err());
+        assert!(settings.set("foo", "bar").is_err());
+    }
+}
@@ -0,0 +1,8 @@
+//! Table `vinyl_type` is used to store the user-defined types of VinylDB.
+//!
+//! Like `vinyl_table`, a type is stored as its name and the SQL that creates
+//! it.
+
+pub use super::vinyl_table::{PK, SCHEMA};
+
+pub const TABLE_NAME: &str = "vinyl_type";
@@ -2,19 +2,36 @@ use crate::{
     catalog::Catalog,
     config::{Config, ConfigBuilder},
     data::tuple::{Tuple, TupleStream},
-    error::Result,
+    error::{Error, Result},
     logical_plan::LogicalPlan,
     physical_plan::{
-        create_table::CreateTableExec, describe_table::DescribeTableExec,
+        aggregate::AggregateExec, create_sequence::CreateSequenceExec,
+        create_table::CreateTableExec, create_type::CreateTypeExec,
+        describe_table::DescribeTableExec, empty_relation::EmptyRelationExec,
         explain::ExplainExec, filter::FilterExec, insert::InsertExec,
         limit::LimitExec, one_row_placeholder::OneRowPlaceholderExec,
-        projection::ProjectionExec, show_tables::ShowTablesExec,
-        table_scan::TableScanExec, Executor,
+        projection::ProjectionExec, set_variable::SetVariableExec,
+        show_tables::ShowTablesExec, show_variable::ShowVariableExec,
+        sort::SortExec, table_scan::TableScanExec,
+        transaction::TransactionExec, values::ValuesExec, window::WindowExec,
+        Executor,
     },
+    plan::error::{PlanError, UnimplementedFeature},
     storage_engine::StorageEngine,
+    transaction::{TableIter, Transaction, TransactionError},
 };
 use camino::Utf8Path;
-use sqlparser::{ast::Statement, dialect::PostgreSqlDialect, parser::Parser};
+use sqlparser::{
+    ast::{
+        DataType as SQLDataType, Ident, JsonOperator, SelectItem,
+        SqliteOnConflict, Statement, UserDefinedTypeCompositeAttributeDef,
+        UserDefinedTypeRepresentation,
+    },
+    dialect::PostgreSqlDialect,
+    keywords::Keyword,
+    parser::{Parser, ParserError},
+    tokenizer::{Token, TokenWithLocation, Tokenizer},
+};
 
 const DIALECT: PostgreSqlDialect = PostgreSqlDialect {};
 
@@ -27,6 +44,213 @@ pub struct Context {
     pub config: Config,
     pub catalog: Catalog,
     pub storage: StorageEngine,
+    /// The transaction in progress, started by `BEGIN`.
+    transaction: Option<Transaction>,
+}
+
+/// The tokenizer takes `?` as a placeholder, so the JSON operator in
+/// `json ? 'key'` cannot be parsed. A `?` followed by a string literal is
+/// rewritten to `@?`, which is parsed as an operator and converted to
+/// `jsonb_exists()`.
+///
+/// The real `@?` operator is not supported, it is rejected so that it is not
+/// taken as `?`.
+fn rewrite_exists_operator(
+    mut tokens: Vec<TokenWithLocation>,
+) -> Result<Vec<TokenWithLocation>> {
+    if tokens.iter().any(|token| token.token == Token::AtQuestion) {
+        return Err(Error::PlanError(PlanError::Unimplemented(
+            UnimplementedFeature::JsonOperator {
+                op: JsonOperator::AtQuestion,
+            },
+        )));
+    }
+
+    for idx in 0..tokens.len() {
+        if !matches!(&tokens[idx].token, Token::Placeholder(str) if str == "?")
+        {
+            continue;
+        }
+        let next = tokens[idx + 1..]
+            .iter()
+            .find(|token| !matches!(token.token, Token::Whitespace(_)));
+        if let Some(TokenWithLocation {
+            token:
+                Token::SingleQuotedString(_)
+                | Token::EscapedStringLiteral(_)
+                | Token::DollarQuotedString(_),
+            ..
+        }) = next
+        {
+            tokens[idx].token = Token::AtQuestion;
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// `sqlparser` only parses composite types in `CREATE TYPE`, so
+/// `CREATE TYPE name AS ENUM ('a', 'b')` is parsed here, as a composite type
+/// of a single `ENUM('a', 'b')` attribute, which is what
+/// [`crate::plan::create_type`] expects.
+///
+/// `None` will be returned if `tokens` is not such a statement.
+fn parse_create_enum_type(
+    tokens: &[TokenWithLocation],
+) -> Option<std::result::Result<Statement, ParserError>> {
+    let mut parser =
+        Parser::new(&DIALECT).with_tokens_with_locations(tokens.to_vec());
+    if !parser.parse_keywords(&[Keyword::CREATE, Keyword::TYPE]) {
+        return None;
+    }
+    let name = match parser.parse_object_name(false) {
+        Ok(name) => name,
+        Err(e) => return Some(Err(e)),
+    };
+    if !parser.parse_keywords(&[Keyword::AS, Keyword::ENUM]) {
+        return None;
+    }
+
+    let mut parse_labels = || {
+        let labels = parser.parse_string_values()?;
+        let _ = parser.consume_token(&Token::SemiColon);
+        if parser.peek_token().token != Token::EOF {
+            return parser.expected("end of statement", parser.peek_token());
+        }
+
+        Ok(labels)
+    };
+
+    Some(parse_labels().map(|labels| Statement::CreateType {
+        name,
+        representation: UserDefinedTypeRepresentation::Composite {
+            attributes: vec![UserDefinedTypeCompositeAttributeDef {
+                name: Ident::new("enum"),
+                data_type: SQLDataType::Enum(labels),
+                collation: None,
+            }],
+        },
+    }))
+}
+
+/// `sqlparser` only parses `INSERT OR REPLACE` and `INSERT OR IGNORE` in the
+/// SQLite dialect, so `OR REPLACE` or `OR IGNORE` is removed from `tokens`
+/// here, and set on the parsed `Statement::Insert`.
+///
+/// `None` will be returned if `tokens` is not such a statement.
+fn parse_insert_or(
+    tokens: &[TokenWithLocation],
+) -> Option<std::result::Result<Statement, ParserError>> {
+    let mut words = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| !matches!(token.token, Token::Whitespace(_)))
+        .map(|(idx, token)| match &token.token {
+            Token::Word(word) => (idx, word.keyword),
+            _ => (idx, Keyword::NoKeyword),
+        });
+    let (
+        Some((_, Keyword::INSERT)),
+        Some((or, Keyword::OR)),
+        Some((kind, keyword)),
+    ) = (words.next(), words.next(), words.next())
+    else {
+        return None;
+    };
+    let conflict = match keyword {
+        Keyword::REPLACE => SqliteOnConflict::Replace,
+        Keyword::IGNORE => SqliteOnConflict::Ignore,
+        _ => return None,
+    };
+
+    let tokens = tokens
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| *idx != or && *idx != kind)
+        .map(|(_, token)| token.clone())
+        .collect();
+    let statement = Parser::new(&DIALECT)
+        .with_tokens_with_locations(tokens)
+        .parse_statements()
+        .map(|mut asts| asts.pop().unwrap());
+
+    Some(statement.map(|mut statement| {
+        if let Statement::Insert { or, .. } = &mut statement {
+            *or = Some(conflict);
+        }
+        statement
+    }))
+}
+
+/// `sqlparser` takes `RETURNING` after `INSERT ... SELECT ... FROM t` as an
+/// alias of `t`, so the `RETURNING` clause of an `INSERT` is removed from
+/// `tokens` here, and its items are parsed separately, to be set on the
+/// parsed `Statement::Insert`.
+fn split_insert_returning(
+    mut tokens: Vec<TokenWithLocation>,
+) -> Result<(Vec<TokenWithLocation>, Option<Vec<SelectItem>>)> {
+    let is_keyword = |token: &Token, keyword| {
+        matches!(token, Token::Word(word)
+            if word.keyword == keyword && word.quote_style.is_none())
+    };
+    let first = tokens
+        .iter()
+        .find(|token| !matches!(token.token, Token::Whitespace(_)));
+    if !first.is_some_and(|first| is_keyword(&first.token, Keyword::INSERT)) {
+        return Ok((tokens, None));
+    }
+
+    // the last `RETURNING` outside of parentheses
+    let mut depth = 0_usize;
+    let mut returning = None;
+    for (idx, token) in tokens.iter().enumerate() {
+        match &token.token {
+            Token::LParen => depth += 1,
+            Token::RParen => depth = depth.saturating_sub(1),
+            token if depth == 0 && is_keyword(token, Keyword::RETURNING) => {
+                returning = Some(idx);
+            }
+            _ => {}
+        }
+    }
+    let Some(idx) = returning else {
+        return Ok((tokens, None));
+    };
+
+    let mut parser = Parser::new(&DIALECT)
+        .with_tokens_with_locations(tokens.split_off(idx + 1));
+    let items = parser.parse_comma_separated(Parser::parse_select_item)?;
+    let _ = parser.consume_token(&Token::SemiColon);
+    if parser.peek_token().token != Token::EOF {
+        parser.expected("end of statement", parser.peek_token())?;
+    }
+    tokens.truncate(idx);
+
+    Ok((tokens, Some(items)))
+}
+
+/// Parse `sql`, which should contain exactly 1 statement.
+pub(crate) fn parse_statement(sql: &str) -> Result<Statement> {
+    let tokens = Tokenizer::new(&DIALECT, sql)
+        .tokenize_with_location()
+        .map_err(ParserError::from)?;
+    let tokens = rewrite_exists_operator(tokens)?;
+    if let Some(statement) = parse_create_enum_type(&tokens) {
+        return Ok(statement?);
+    }
+    let (tokens, returning_items) = split_insert_returning(tokens)?;
+    let mut statement = match parse_insert_or(&tokens) {
+        Some(statement) => statement?,
+        None => Parser::new(&DIALECT)
+            .with_tokens_with_locations(tokens)
+            .parse_statements()
+            .map(|mut asts| asts.pop().unwrap())?,
+    };
+    if let Statement::Insert { returning, .. } = &mut statement {
+        *returning = returning_items;
+    }
+
+    Ok(statement)
 }
 
 impl Context {
@@ -43,28 +267,158 @@ impl Context {
             .unwrap();
         let storage = StorageEngine::new(&config)?;
         let catalog = Catalog::new(&storage)?;
+        crate::migration::migrate(&storage, &catalog)?;
         let ctx = Self {
             config,
             catalog,
             storage,
+            transaction: None,
         };
 
         Ok(ctx)
     }
 
+    /// Start another session on the same data, which runs its own
+    /// transactions concurrently with the ones of this session, and starts
+    /// with the default settings.
+    ///
+    /// The tables and types are shared by all the sessions. The user-defined
+    /// functions of this session are known to the new one, but the ones
+    /// registered afterwards in either session are not shared. `currval()`
+    /// only returns the values got by `nextval()` in the same session.
+    pub fn new_session(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            catalog: self.catalog.new_session(&self.storage),
+            storage: self.storage.clone(),
+            transaction: None,
+        }
+    }
+
+    /// Return true if there is a transaction in progress.
+    pub fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    /// Start a transaction.
+    pub fn begin(&mut self) -> Result<()> {
+        if self.transaction.is_some() {
+            return Err(Error::TransactionError(
+                TransactionError::AlreadyInProgress,
+            ));
+        }
+        self.transaction = Some(Transaction::new(&self.storage));
+
+        Ok(())
+    }
+
+    /// Commit the transaction in progress.
+    pub fn commit(&mut self) -> Result<()> {
+        let transaction = self
+            .transaction
+            .take()
+            .ok_or(Error::TransactionError(TransactionError::NotInProgress))?;
+
+        transaction.commit(&self.storage)
+    }
+
+    /// Discard the transaction in progress.
+    pub fn rollback(&mut self) -> Result<()> {
+        self.transaction
+            .take()
+            .ok_or(Error::TransactionError(TransactionError::NotInProgress))?;
+
+        Ok(())
+    }
+
+    /// Return the transaction in progress, `statement` is the one requiring
+    /// it.
+    fn transaction_for(
+        &mut self,
+        statement: &'static str,
+    ) -> Result<&mut Transaction> {
+        self.transaction.as_mut().ok_or(Error::TransactionError(
+            TransactionError::OnlyInTransaction { statement },
+        ))
+    }
+
+    /// Define a savepoint in the transaction in progress.
+    pub fn savepoint(&mut self, name: String) -> Result<()> {
+        self.transaction_for("SAVEPOINT")?.savepoint(name);
+
+        Ok(())
+    }
+
+    /// Roll the transaction in progress back to a savepoint.
+    pub fn rollback_to_savepoint(&mut self, name: &str) -> Result<()> {
+        self.transaction_for("ROLLBACK TO SAVEPOINT")?
+            .rollback_to_savepoint(name)
+    }
+
+    /// Release a savepoint of the transaction in progress.
+    pub fn release_savepoint(&mut self, name: &str) -> Result<()> {
+        self.transaction_for("RELEASE SAVEPOINT")?
+            .release_savepoint(name)
+    }
+
+    /// Write with `f` in the transaction in progress, or in a new one that is
+    /// committed right after if there is none.
+    pub(crate) fn write_in_transaction<T, F>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Catalog, &StorageEngine, &mut Transaction) -> Result<T>,
+    {
+        match self.transaction.as_mut() {
+            Some(transaction) => f(&self.catalog, &self.storage, transaction),
+            None => {
+                let mut transaction = Transaction::new(&self.storage);
+                let ret = f(&self.catalog, &self.storage, &mut transaction)?;
+                transaction.commit(&self.storage)?;
+
+                Ok(ret)
+            }
+        }
+    }
+
+    /// Scan `table` in a snapshot, uncommitted writes of the transaction in
+    /// progress are visible.
+    pub(crate) fn scan_table(&self, table: &str) -> Result<TableIter> {
+        let tree = self.storage.get_tree_of_table(table)?;
+        // the catalog is not versioned
+        if table == crate::catalog::vinyl_table::TABLE_NAME {
+            return Ok(Box::new(tree.iter()));
+        }
+        let iter = match self.transaction.as_ref() {
+            Some(transaction) => transaction.scan(table, &tree),
+            None => Box::new(self.storage.snapshot().scan(&tree)),
+        };
+
+        Ok(iter)
+    }
+
+    /// Remove the row versions that are no longer visible to any snapshot,
+    /// return how many versions are removed.
+    pub fn collect_garbage(&self) -> Result<usize> {
+        let mut trees = Vec::new();
+        for table in self.catalog.tables() {
+            if table.name() != crate::catalog::vinyl_table::TABLE_NAME {
+                trees.push(self.storage.get_tree_of_table(table.name())?);
+            }
+        }
+
+        Ok(crate::mvcc::collect_garbage(self.storage.mvcc(), &trees)?)
+    }
+
     pub fn statement_to_logical_plan(
         &self,
         statement: Statement,
     ) -> Result<LogicalPlan> {
-        crate::plan::statement_to_logical_plan(&self.catalog, statement)
+        let plan =
+            crate::plan::statement_to_logical_plan(&self.catalog, statement)?;
+        crate::optimizer::optimize(&self.catalog, plan)
     }
 
     pub fn sql_to_statement<S: AsRef<str>>(&self, sql: S) -> Result<Statement> {
-        let sql = sql.as_ref();
-        let statement = Parser::parse_sql(&DIALECT, sql)
-            .map(|mut asts| asts.pop().unwrap())?;
-
-        Ok(statement)
+        parse_statement(sql.as_ref())
     }
 
     pub fn create_logical_plan<S: AsRef<str>>(
@@ -85,32 +439,75 @@ impl Context {
                 schema,
                 pk,
                 sql,
+                sequences,
             } => Box::new(CreateTableExec::new(
                 name.clone(),
                 schema.clone(),
                 *pk,
                 sql.clone(),
+                sequences.clone(),
+            )),
+            LogicalPlan::CreateSequence {
+                name,
+                options,
+                if_not_exists,
+            } => Box::new(CreateSequenceExec::new(
+                name.clone(),
+                *options,
+                *if_not_exists,
             )),
+            LogicalPlan::CreateType { enum_type, sql } => {
+                Box::new(CreateTypeExec::new(enum_type.clone(), sql.clone()))
+            }
             LogicalPlan::Explain { plan } => {
                 let plan = self.create_physical_plan(plan)?;
                 Box::new(ExplainExec::new(plan))
             }
             LogicalPlan::ShowTables => Box::new(ShowTablesExec),
+            LogicalPlan::SetVariable { name, value } => {
+                Box::new(SetVariableExec::new(name.clone(), value.clone()))
+            }
+            LogicalPlan::ShowVariable { name } => {
+                Box::new(ShowVariableExec::new(name.clone()))
+            }
+            LogicalPlan::Transaction { control } => {
+                Box::new(TransactionExec::new(control.clone()))
+            }
             LogicalPlan::DescribeTable { name } => {
                 Box::new(DescribeTableExec::new(name.clone()))
             }
-            LogicalPlan::Insert { table, rows } => {
-                Box::new(InsertExec::new(table.clone(), rows.to_vec()))
+            LogicalPlan::Insert {
+                table,
+                values,
+                on_conflict,
+                returning,
+                input,
+            } => {
+                let input = self.create_physical_plan(input)?;
+                Box::new(InsertExec::new(
+                    table.clone(),
+                    values.clone(),
+                    on_conflict.clone(),
+                    returning.clone(),
+                    input,
+                ))
             }
             LogicalPlan::TableScan { name } => {
                 let table_catalog = self.catalog.get_table(name)?;
                 let schema = table_catalog.schema().clone();
                 Box::new(TableScanExec::new(name.clone(), schema))
             }
+            LogicalPlan::Values { rows, schema } => {
+                Box::new(ValuesExec::new(rows.to_vec(), schema.clone()))
+            }
             LogicalPlan::Filter { predicate, input } => {
                 let input = self.create_physical_plan(input)?;
                 Box::new(FilterExec::new(predicate.clone(), input))
             }
+            LogicalPlan::Sort { order_by, input } => {
+                let input = self.create_physical_plan(input)?;
+                Box::new(SortExec::new(order_by.clone(), input))
+            }
             LogicalPlan::Limit {
                 offset,
                 limit,
@@ -132,6 +529,31 @@ impl Context {
                 ))
             }
             LogicalPlan::OneRowPlaceholder => Box::new(OneRowPlaceholderExec),
+            LogicalPlan::EmptyRelation { schema } => {
+                Box::new(EmptyRelationExec::new(schema.clone()))
+            }
+            LogicalPlan::Aggregate {
+                group_by,
+                aggr,
+                schema,
+                input,
+            } => {
+                let input = self.create_physical_plan(input)?;
+                Box::new(AggregateExec::new(
+                    group_by.clone(),
+                    aggr.clone(),
+                    schema.clone(),
+                    input,
+                ))
+            }
+            LogicalPlan::Window {
+                window,
+                schema,
+                input,
+            } => {
+                let input = self.create_physical_plan(input)?;
+                Box::new(WindowExec::new(window.clone(), schema.clone(), input))
+            }
         };
 
         Ok(plan)
@@ -149,6 +571,6 @@ impl Context {
         physical_plan: &dyn Executor,
     ) -> Result<Vec<Tuple>> {
         let iter = self.execute(physical_plan)?;
-        Ok(iter.into_iter().collect())
+        iter.into_iter().collect()
     }
 }
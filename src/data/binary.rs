@@ -0,0 +1,189 @@
+//! Parsing, displaying and encoding binary strings.
+
+use std::fmt::Write;
+
+/// Parse the text form of a binary string.
+///
+/// Like PostgreSQL, two formats are accepted:
+///
+/// 1. hex: `\x` followed by pairs of hex digits, e.g., `\xdeadbeef`
+/// 2. escape: the bytes of `str`, where `\\` is a backslash and `\ooo` is an
+///    octal byte, e.g., `abc\000`
+pub fn parse_binary(str: &str) -> Option<Vec<u8>> {
+    match str.strip_prefix("\\x").or_else(|| str.strip_prefix("\\X")) {
+        Some(hex) => decode_hex(hex),
+        None => unescape(str),
+    }
+}
+
+/// Display a binary string in the hex format, e.g., `\xdeadbeef`.
+pub fn display_binary(bytes: &[u8]) -> String {
+    format!("\\x{}", encode_hex(bytes))
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    let mut str = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        write!(str, "{byte:02x}").expect("writing to a String never fails");
+    }
+
+    str
+}
+
+pub fn decode_hex(str: &str) -> Option<Vec<u8>> {
+    let str = str.as_bytes();
+    if str.len() % 2 != 0 {
+        return None;
+    }
+
+    str.chunks(2)
+        .map(|pair| {
+            let high = char::from(pair[0]).to_digit(16)?;
+            let low = char::from(pair[1]).to_digit(16)?;
+            Some((high * 16 + low) as u8)
+        })
+        .collect()
+}
+
+/// Encode `bytes` in the escape format, backslashes and non-printable bytes
+/// are escaped.
+pub fn escape(bytes: &[u8]) -> String {
+    let mut str = String::with_capacity(bytes.len());
+    for &byte in bytes {
+        match byte {
+            b'\\' => str.push_str("\\\\"),
+            0x20..=0x7e => str.push(char::from(byte)),
+            _ => write!(str, "\\{byte:03o}")
+                .expect("writing to a String never fails"),
+        }
+    }
+
+    str
+}
+
+/// Decode a string in the escape format.
+pub fn unescape(str: &str) -> Option<Vec<u8>> {
+    let str = str.as_bytes();
+    let mut bytes = Vec::with_capacity(str.len());
+    let mut idx = 0;
+    while idx < str.len() {
+        if str[idx] != b'\\' {
+            bytes.push(str[idx]);
+            idx += 1;
+            continue;
+        }
+        if str.get(idx + 1) == Some(&b'\\') {
+            bytes.push(b'\\');
+            idx += 2;
+            continue;
+        }
+        let Some(octal @ [b'0'..=b'3', b'0'..=b'7', b'0'..=b'7']) =
+            str.get(idx + 1..idx + 4)
+        else {
+            return None;
+        };
+        let octal = std::str::from_utf8(octal).ok()?;
+        bytes.push(u8::from_str_radix(octal, 8).ok()?);
+        idx += 4;
+    }
+
+    Some(bytes)
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode `bytes` in base64 with padding.
+pub fn encode_base64(bytes: &[u8]) -> String {
+    let mut str = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group =
+            chunk.iter().enumerate().fold(0_u32, |group, (idx, byte)| {
+                group | u32::from(*byte) << (16 - 8 * idx)
+            });
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                let sextet = (group >> (18 - 6 * idx)) & 0x3f;
+                str.push(char::from(BASE64_ALPHABET[sextet as usize]));
+            } else {
+                str.push('=');
+            }
+        }
+    }
+
+    str
+}
+
+/// Decode a base64 string, whitespace is ignored.
+pub fn decode_base64(str: &str) -> Option<Vec<u8>> {
+    let chars = str
+        .bytes()
+        .filter(|char| !char.is_ascii_whitespace())
+        .collect::<Vec<_>>();
+    if chars.len() % 4 != 0 {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(chars.len() / 4 * 3);
+    let n_chunks = chars.len() / 4;
+    for (chunk_idx, chunk) in chars.chunks(4).enumerate() {
+        let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        // padding is only allowed at the end
+        if padding > 2 || (padding > 0 && chunk_idx != n_chunks - 1) {
+            return None;
+        }
+        let mut group = 0_u32;
+        for &char in &chunk[..4 - padding] {
+            let sextet = BASE64_ALPHABET.iter().position(|&c| c == char)?;
+            group = group << 6 | sextet as u32;
+        }
+        group <<= 6 * padding;
+        let decoded = group.to_be_bytes();
+        bytes.extend_from_slice(&decoded[1..4 - padding]);
+    }
+
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_and_display_binary() {
+        assert_eq!(
+            parse_binary("\\xDEADbeef"),
+            Some(vec![0xde, 0xad, 0xbe, 0xef])
+        );
+        assert_eq!(parse_binary("\\x"), Some(vec![]));
+        assert_eq!(parse_binary("\\xabc"), None);
+        assert_eq!(parse_binary("\\xzz"), None);
+        assert_eq!(parse_binary("a\\\\b\\001"), Some(b"a\\b\x01".to_vec()));
+        assert_eq!(parse_binary("a\\b"), None);
+
+        assert_eq!(display_binary(&[0, 1, 0xff]), "\\x0001ff");
+        assert_eq!(escape(b"a\\b\x01"), "a\\\\b\\001");
+    }
+
+    #[test]
+    fn base64() {
+        for (bytes, encoded) in [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foobar", "Zm9vYmFy"),
+            (&[0xff, 0xfe], "//4="),
+        ] {
+            assert_eq!(encode_base64(bytes), encoded);
+            assert_eq!(decode_base64(encoded).unwrap(), bytes);
+        }
+
+        assert_eq!(decode_base64("Zm9v\nYmFy").unwrap(), b"foobar");
+        assert!(decode_base64("Zm9").is_none());
+        assert!(decode_base64("Zg==Zm9v").is_none());
+        assert!(decode_base64("Z===").is_none());
+        assert!(decode_base64("Zm9*").is_none());
+    }
+}
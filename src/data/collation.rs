@@ -0,0 +1,80 @@
+//! Collations, which decide how strings are compared.
+
+use super::types::Data;
+use derive_more::Display;
+use std::borrow::Cow;
+use strum_macros::EnumString;
+
+/// How strings are compared, sorted, grouped and encoded as primary keys,
+/// specified by `COLLATE name` on a column or an expression.
+///
+/// Strings are collated by their keys, see [`Collation::key()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Collation {
+    /// Byte-wise, this is the default.
+    #[default]
+    #[display(fmt = "binary")]
+    Binary,
+    /// Case-insensitive for ASCII letters only, e.g., `'a' = 'A'` but
+    /// `'é' <> 'É'`.
+    #[display(fmt = "nocase")]
+    NoCase,
+    /// Case-insensitive for all the Unicode letters, e.g., `'é' = 'É'` and
+    /// `'ß' = 'SS'`.
+    #[display(fmt = "unicode")]
+    Unicode,
+}
+
+impl Collation {
+    /// Return the key of `str`, strings are equal under this collation iff
+    /// their keys are equal, and they are ordered by the bytes of their keys.
+    pub fn key<'a>(&self, str: &'a str) -> Cow<'a, str> {
+        match self {
+            Collation::Binary => Cow::Borrowed(str),
+            Collation::NoCase => Cow::Owned(str.to_ascii_lowercase()),
+            // Case folding, approximated by uppercasing and then lowercasing
+            // every char, e.g., both 'ς' and 'σ' are folded to 'σ' and 'ß' is
+            // folded to 'ss'.
+            Collation::Unicode => Cow::Owned(
+                str.chars()
+                    .flat_map(char::to_uppercase)
+                    .flat_map(char::to_lowercase)
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Replace `data` with its key if it is a string, other data are not
+    /// affected by collations.
+    pub fn apply(&self, data: Data) -> Data {
+        match data {
+            Data::String(str) if *self != Collation::Binary => {
+                Data::String(self.key(&str).into_owned())
+            }
+            data => data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn collation_keys() {
+        assert_eq!(Collation::Binary.key("ÉcoLe"), "ÉcoLe");
+        assert_eq!(Collation::NoCase.key("ÉcoLe"), "École");
+        assert_eq!(Collation::Unicode.key("ÉcoLe"), "école");
+        assert_eq!(Collation::Unicode.key("ΟΔΟΣ"), "οδοσ");
+        assert_eq!(Collation::Unicode.key("οδος"), "οδοσ");
+        assert_eq!(Collation::Unicode.key("Straße"), "strasse");
+        assert_eq!(
+            Collation::NoCase.apply(Data::String("Ann".into())),
+            Collation::NoCase.apply(Data::String("aNN".into()))
+        );
+        assert_eq!("NoCase".parse(), Ok(Collation::NoCase));
+        assert!("C".parse::<Collation>().is_err());
+    }
+}
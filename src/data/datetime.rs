@@ -0,0 +1,803 @@
+//! Date/time types: `DATE`, `TIME`, `TIMESTAMP`, `TIMESTAMPTZ` and
+//! `INTERVAL`.
+//!
+//! They are stored as plain integers in [`Data`](super::types::Data), this
+//! module converts them from/to strings and implements the calendar arithmetic
+//! on them.
+//!
+//! Only fixed UTC offsets are supported as time zones, e.g., `+08:00`.
+
+use bytes::{Buf, BufMut};
+use chrono::{
+    DateTime, Datelike, Days, FixedOffset, Months, NaiveDate, NaiveDateTime,
+    NaiveTime, TimeDelta, Timelike,
+};
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+pub const MICROS_PER_SEC: i64 = 1_000_000;
+pub const SECS_PER_DAY: i64 = 86_400;
+pub const MICROS_PER_DAY: i64 = SECS_PER_DAY * MICROS_PER_SEC;
+/// The length of a month used when intervals are compared or normalized.
+const DAYS_PER_MONTH: i64 = 30;
+
+/// A span of time.
+///
+/// Months and days are kept apart from the time part as their lengths vary,
+/// e.g., `timestamp '2024-01-31' + interval '1 month'` is `2024-02-29`.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Interval {
+    pub months: i32,
+    pub days: i32,
+    pub micros: i64,
+}
+
+impl Interval {
+    /// How many bytes it will take after encoding.
+    pub const ENCODE_SIZE: usize = 16;
+
+    pub fn new(months: i32, days: i32, micros: i64) -> Self {
+        Self {
+            months,
+            days,
+            micros,
+        }
+    }
+
+    /// Return the length of this interval in microseconds, a month is assumed
+    /// to have 30 days.
+    fn normalized(&self) -> i128 {
+        (i128::from(self.months) * i128::from(DAYS_PER_MONTH)
+            + i128::from(self.days))
+            * i128::from(MICROS_PER_DAY)
+            + i128::from(self.micros)
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(Self {
+            months: self.months.checked_add(rhs.months)?,
+            days: self.days.checked_add(rhs.days)?,
+            micros: self.micros.checked_add(rhs.micros)?,
+        })
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self {
+            months: self.months.checked_neg()?,
+            days: self.days.checked_neg()?,
+            micros: self.micros.checked_neg()?,
+        })
+    }
+
+    pub fn encode(&self) -> [u8; Self::ENCODE_SIZE] {
+        let mut buf = [0; Self::ENCODE_SIZE];
+        let mut dst = buf.as_mut_slice();
+        dst.put_i32_ne(self.months);
+        dst.put_i32_ne(self.days);
+        dst.put_i64_ne(self.micros);
+
+        buf
+    }
+
+    pub fn decode(mut buf: &[u8]) -> Self {
+        Self {
+            months: buf.get_i32_ne(),
+            days: buf.get_i32_ne(),
+            micros: buf.get_i64_ne(),
+        }
+    }
+}
+
+/// Intervals are equal if they have the same length, e.g., `1 month` and
+/// `30 days`.
+impl PartialEq for Interval {
+    fn eq(&self, other: &Self) -> bool {
+        self.normalized() == other.normalized()
+    }
+}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.normalized().cmp(&other.normalized()))
+    }
+}
+
+/// Same as the default output style of PostgreSQL, e.g.,
+/// `1 year 2 mons 3 days 04:05:06`.
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let plural = |n: i64| if n.abs() == 1 { "" } else { "s" };
+        let years = i64::from(self.months / 12);
+        let months = i64::from(self.months % 12);
+        let days = i64::from(self.days);
+
+        let mut parts = Vec::new();
+        if years != 0 {
+            parts.push(format!("{years} year{}", plural(years)));
+        }
+        if months != 0 {
+            parts.push(format!("{months} mon{}", plural(months)));
+        }
+        if days != 0 {
+            parts.push(format!("{days} day{}", plural(days)));
+        }
+        if self.micros != 0 || parts.is_empty() {
+            let sign = if self.micros < 0 { "-" } else { "" };
+            let micros = self.micros.unsigned_abs();
+            let secs = micros / MICROS_PER_SEC as u64;
+            let mut time = format!(
+                "{sign}{:02}:{:02}:{:02}",
+                secs / 3600,
+                secs / 60 % 60,
+                secs % 60
+            );
+            let frac = micros % MICROS_PER_SEC as u64;
+            if frac != 0 {
+                time.push_str(format!(".{frac:06}").trim_end_matches('0'));
+            }
+            parts.push(time);
+        }
+
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// Fields of a date/time value, used by `extract()`, `date_part()` and
+/// `date_trunc()`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DateTimeField {
+    Year,
+    Quarter,
+    Month,
+    /// ISO 8601 week, which starts on Monday.
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    /// Day of the week, from `0` (Sunday) to `6` (Saturday).
+    Dow,
+    /// Day of the year, from `1` to `366`.
+    Doy,
+    /// Seconds since UNIX Epoch, or the total number of seconds of an
+    /// interval.
+    Epoch,
+}
+
+impl FromStr for DateTimeField {
+    type Err = ();
+
+    /// Parse a field, case-insensitively, plural forms are accepted as well.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let field = match s.to_lowercase().as_str() {
+            "year" | "years" => Self::Year,
+            "quarter" => Self::Quarter,
+            "month" | "months" => Self::Month,
+            "week" | "weeks" => Self::Week,
+            "day" | "days" => Self::Day,
+            "hour" | "hours" => Self::Hour,
+            "minute" | "minutes" => Self::Minute,
+            "second" | "seconds" => Self::Second,
+            "dow" => Self::Dow,
+            "doy" => Self::Doy,
+            "epoch" => Self::Epoch,
+            _ => return Err(()),
+        };
+
+        Ok(field)
+    }
+}
+
+impl Display for DateTimeField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let debug = format!("{:?}", self);
+        f.write_str(&debug.to_lowercase())
+    }
+}
+
+fn epoch() -> NaiveDateTime {
+    DateTime::UNIX_EPOCH.naive_utc()
+}
+
+fn date_from_days(days: i32) -> Option<NaiveDate> {
+    epoch()
+        .date()
+        .checked_add_signed(TimeDelta::try_days(i64::from(days))?)
+}
+
+fn days_from_date(date: NaiveDate) -> i32 {
+    // The range of `NaiveDate` is far smaller than `i32` days.
+    (date - epoch().date()).num_days() as i32
+}
+
+fn time_from_micros(micros: i64) -> Option<NaiveTime> {
+    let secs = u32::try_from(micros.div_euclid(MICROS_PER_SEC)).ok()?;
+    let nanos = micros.rem_euclid(MICROS_PER_SEC) as u32 * 1000;
+    NaiveTime::from_num_seconds_from_midnight_opt(secs, nanos)
+}
+
+fn micros_from_time(time: NaiveTime) -> i64 {
+    i64::from(time.num_seconds_from_midnight()) * MICROS_PER_SEC
+        + i64::from(time.nanosecond() / 1000)
+}
+
+fn datetime_from_timestamp(micros: i64) -> Option<NaiveDateTime> {
+    let secs = micros.div_euclid(MICROS_PER_SEC);
+    let nanos = micros.rem_euclid(MICROS_PER_SEC) as u32 * 1000;
+    DateTime::from_timestamp(secs, nanos).map(|datetime| datetime.naive_utc())
+}
+
+/// Convert a datetime to microseconds since UNIX Epoch, nanoseconds are
+/// truncated.
+fn timestamp_from_datetime(datetime: NaiveDateTime) -> i64 {
+    datetime.and_utc().timestamp_micros()
+}
+
+/// Parse a date in the format of `YYYY-MM-DD`, return the days since UNIX
+/// Epoch.
+pub fn parse_date(str: &str) -> Option<i32> {
+    let date = NaiveDate::parse_from_str(str.trim(), "%Y-%m-%d").ok()?;
+    Some(days_from_date(date))
+}
+
+/// Parse a time in the format of `HH:MM[:SS[.ffffff]]`, return the
+/// microseconds since midnight.
+pub fn parse_time(str: &str) -> Option<i64> {
+    let str = str.trim();
+    let time = NaiveTime::parse_from_str(str, "%H:%M:%S%.f")
+        .or_else(|_| NaiveTime::parse_from_str(str, "%H:%M"))
+        .ok()?;
+    Some(micros_from_time(time))
+}
+
+/// Parse a timestamp in the format of `YYYY-MM-DD[( |T)HH:MM[:SS[.ffffff]]]`,
+/// return the microseconds since UNIX Epoch.
+pub fn parse_timestamp(str: &str) -> Option<i64> {
+    let str = str.trim();
+    let (date, time) = match str.split_once([' ', 'T']) {
+        Some((date, time)) => (date, parse_time(time)?),
+        None => (str, 0),
+    };
+    let datetime =
+        date_from_days(parse_date(date)?)?.and_time(time_from_micros(time)?);
+
+    Some(timestamp_from_datetime(datetime))
+}
+
+/// Parse a timestamp with an optional UTC offset following the time, e.g.,
+/// `2024-01-01 12:00:00+08`, `2024-01-01T12:00:00Z` or
+/// `2024-01-01 12:00:00 UTC`, return the microseconds since UNIX Epoch.
+///
+/// `time_zone` is used if there is no offset.
+pub fn parse_timestamptz(str: &str, time_zone: FixedOffset) -> Option<i64> {
+    let str = str.trim();
+    let (local, time_zone) = match str.split_once([' ', 'T']) {
+        Some((date, time)) => match time.find(['+', '-', ' ', 'Z']) {
+            Some(idx) => (
+                format!("{date} {}", &time[..idx]),
+                parse_time_zone(&time[idx..])?,
+            ),
+            None => (str.to_string(), time_zone),
+        },
+        None => (str.to_string(), time_zone),
+    };
+
+    timestamp_to_timestamptz(parse_timestamp(&local)?, time_zone)
+}
+
+/// Parse a time zone, which is `UTC` or an offset in the format of
+/// `(+|-)HH[:MM]`.
+pub fn parse_time_zone(str: &str) -> Option<FixedOffset> {
+    let str = str.trim();
+    if ["UTC", "GMT", "Z"]
+        .iter()
+        .any(|utc| utc.eq_ignore_ascii_case(str))
+    {
+        return FixedOffset::east_opt(0);
+    }
+
+    let (sign, offset) = if let Some(offset) = str.strip_prefix('+') {
+        (1, offset)
+    } else {
+        (-1, str.strip_prefix('-')?)
+    };
+    let (hours, minutes) = match offset.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if offset.len() == 4 => offset.split_at(2),
+        None => (offset, "0"),
+    };
+    if !(1..=2).contains(&hours.len()) || !(1..=2).contains(&minutes.len()) {
+        return None;
+    }
+    let hours = hours.parse::<i32>().ok()?;
+    let minutes = minutes.parse::<i32>().ok()?;
+    // The same as PostgreSQL, offsets up to 15:59 are accepted.
+    if hours > 15 || minutes > 59 {
+        return None;
+    }
+
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Parse an interval, e.g., `1 year 2 months`, `-3 days 04:05:06` or
+/// `1.5 hours`.
+///
+/// It is a sequence of `<number> <unit>` pairs, optionally with a time part in
+/// the format of `[-]HH:MM[:SS[.ffffff]]`.
+pub fn parse_interval(str: &str) -> Option<Interval> {
+    let mut interval = Interval::default();
+    let mut tokens = str.split_whitespace().peekable();
+    tokens.peek()?;
+
+    while let Some(token) = tokens.next() {
+        if token.contains(':') {
+            let (negative, time) = match token.strip_prefix('-') {
+                Some(time) => (true, time),
+                None => (false, token.strip_prefix('+').unwrap_or(token)),
+            };
+            let mut parts = time.splitn(3, ':');
+            let hours = parts.next()?.parse::<i64>().ok()?;
+            let minutes = parts.next()?.parse::<i64>().ok()?;
+            let secs = match parts.next() {
+                Some(secs) => parse_fraction(secs, MICROS_PER_SEC)?,
+                None => 0,
+            };
+            if !(0..60).contains(&minutes)
+                || !(0..60 * MICROS_PER_SEC).contains(&secs)
+            {
+                return None;
+            }
+            let micros =
+                (hours.checked_mul(3600)?.checked_add(minutes * 60)?)
+                    .checked_mul(MICROS_PER_SEC)?
+                    .checked_add(secs)?;
+            let micros = if negative { -micros } else { micros };
+            interval.micros = interval.micros.checked_add(micros)?;
+            continue;
+        }
+
+        let unit = tokens.next()?;
+        let (months, days, micros) = match unit.to_lowercase().as_str() {
+            "microsecond" | "microseconds" | "us" => (0, 0, 1),
+            "millisecond" | "milliseconds" | "ms" => (0, 0, 1000),
+            "second" | "seconds" | "sec" | "secs" | "s" => {
+                (0, 0, MICROS_PER_SEC)
+            }
+            "minute" | "minutes" | "min" | "mins" | "m" => {
+                (0, 0, 60 * MICROS_PER_SEC)
+            }
+            "hour" | "hours" | "h" => (0, 0, 3600 * MICROS_PER_SEC),
+            "day" | "days" | "d" => (0, 1, 0),
+            "week" | "weeks" | "w" => (0, 7, 0),
+            "month" | "months" | "mon" | "mons" => (1, 0, 0),
+            "year" | "years" | "y" => (12, 0, 0),
+            _ => return None,
+        };
+
+        // The fractional part of a unit is carried down to smaller units,
+        // e.g., `1.5 months` is `1 mon 15 days`.
+        let (whole, frac) = split_number(token)?;
+        let months = whole.checked_mul(months)?;
+        let days = whole.checked_mul(days)? as f64
+            + frac * (months as f64 * DAYS_PER_MONTH as f64 + days as f64);
+        let micros = whole.checked_mul(micros)? as f64
+            + frac * micros as f64
+            + days.fract() * MICROS_PER_DAY as f64;
+
+        interval = interval.checked_add(Interval::new(
+            i32::try_from(months).ok()?,
+            i32::try_from(days.trunc() as i64).ok()?,
+            micros.round() as i64,
+        ))?;
+    }
+
+    Some(interval)
+}
+
+/// Split a decimal number into its whole part and its fractional part, both
+/// of them have the sign of the number.
+fn split_number(str: &str) -> Option<(i64, f64)> {
+    match str.split_once('.') {
+        None => Some((str.parse().ok()?, 0.0)),
+        Some((whole, _)) => {
+            let num = str.parse::<f64>().ok()?;
+            let whole = if whole.is_empty() || whole == "-" || whole == "+" {
+                0
+            } else {
+                whole.parse::<i64>().ok()?
+            };
+            Some((whole, num - whole as f64))
+        }
+    }
+}
+
+/// Parse a non-negative decimal number `str`, return it multiplied by `scale`.
+fn parse_fraction(str: &str, scale: i64) -> Option<i64> {
+    if str.starts_with(['-', '+']) {
+        return None;
+    }
+    let num = str.parse::<f64>().ok()?;
+    Some((num * scale as f64).round() as i64)
+}
+
+pub fn display_date(days: i32) -> String {
+    match date_from_days(days) {
+        Some(date) => date.to_string(),
+        None => days.to_string(),
+    }
+}
+
+pub fn display_time(micros: i64) -> String {
+    match time_from_micros(micros) {
+        Some(time) => time.to_string(),
+        None => micros.to_string(),
+    }
+}
+
+pub fn display_timestamp(micros: i64) -> String {
+    match datetime_from_timestamp(micros) {
+        Some(datetime) => datetime.to_string(),
+        None => micros.to_string(),
+    }
+}
+
+/// Display a `TIMESTAMPTZ` in `time_zone`, e.g., `2024-01-01 20:00:00+08`.
+pub fn display_timestamptz(micros: i64, time_zone: FixedOffset) -> String {
+    let local = timestamptz_to_timestamp(micros, time_zone);
+    match local.and_then(datetime_from_timestamp) {
+        Some(datetime) => {
+            let offset = time_zone.local_minus_utc();
+            let sign = if offset < 0 { '-' } else { '+' };
+            let (hours, minutes) =
+                (offset.abs() / 3600, offset.abs() / 60 % 60);
+            match minutes {
+                0 => format!("{datetime}{sign}{hours:02}"),
+                _ => format!("{datetime}{sign}{hours:02}:{minutes:02}"),
+            }
+        }
+        None => micros.to_string(),
+    }
+}
+
+/// Display a time zone in the format accepted by [`parse_time_zone()`].
+pub fn display_time_zone(time_zone: FixedOffset) -> String {
+    if time_zone.local_minus_utc() == 0 {
+        String::from("UTC")
+    } else {
+        time_zone.to_string()
+    }
+}
+
+/// Convert a `TIMESTAMP` in `time_zone` to a `TIMESTAMPTZ`, i.e., UTC.
+pub fn timestamp_to_timestamptz(
+    micros: i64,
+    time_zone: FixedOffset,
+) -> Option<i64> {
+    micros.checked_sub(i64::from(time_zone.local_minus_utc()) * MICROS_PER_SEC)
+}
+
+/// Convert a `TIMESTAMPTZ` to a `TIMESTAMP` in `time_zone`.
+pub fn timestamptz_to_timestamp(
+    micros: i64,
+    time_zone: FixedOffset,
+) -> Option<i64> {
+    micros.checked_add(i64::from(time_zone.local_minus_utc()) * MICROS_PER_SEC)
+}
+
+/// Convert a date to a timestamp at midnight.
+pub fn date_to_timestamp(days: i32) -> i64 {
+    i64::from(days) * MICROS_PER_DAY
+}
+
+/// `timestamp + interval`.
+pub fn timestamp_add_interval(micros: i64, interval: Interval) -> Option<i64> {
+    let datetime = datetime_from_timestamp(micros)?;
+    let datetime = if interval.months >= 0 {
+        datetime.checked_add_months(Months::new(interval.months as u32))?
+    } else {
+        datetime
+            .checked_sub_months(Months::new(interval.months.unsigned_abs()))?
+    };
+    let datetime = if interval.days >= 0 {
+        datetime.checked_add_days(Days::new(interval.days as u64))?
+    } else {
+        datetime.checked_sub_days(Days::new(u64::from(
+            interval.days.unsigned_abs(),
+        )))?
+    };
+    let datetime = datetime
+        .checked_add_signed(TimeDelta::microseconds(interval.micros))?;
+
+    Some(timestamp_from_datetime(datetime))
+}
+
+/// `time + interval`, only the time part of `interval` counts and the result
+/// wraps around midnight.
+pub fn time_add_interval(micros: i64, interval: Interval) -> i64 {
+    (micros + interval.micros.rem_euclid(MICROS_PER_DAY))
+        .rem_euclid(MICROS_PER_DAY)
+}
+
+/// `timestamp - timestamp`, whole days are put in the `days` field.
+pub fn timestamp_diff(lhs: i64, rhs: i64) -> Option<Interval> {
+    let micros = lhs.checked_sub(rhs)?;
+    Some(Interval::new(
+        0,
+        i32::try_from(micros / MICROS_PER_DAY).ok()?,
+        micros % MICROS_PER_DAY,
+    ))
+}
+
+/// Truncate a timestamp to the precision of `field`.
+///
+/// `None` will be returned if `field` is not supported.
+pub fn date_trunc(field: DateTimeField, micros: i64) -> Option<i64> {
+    let datetime = datetime_from_timestamp(micros)?.with_nanosecond(0)?;
+    let date = datetime.date();
+    let truncated = match field {
+        DateTimeField::Year => date.with_day(1)?.with_month(1)?.into(),
+        DateTimeField::Quarter => date
+            .with_day(1)?
+            .with_month((date.month() - 1) / 3 * 3 + 1)?
+            .into(),
+        DateTimeField::Month => date.with_day(1)?.into(),
+        DateTimeField::Week => (date
+            - TimeDelta::try_days(i64::from(
+                date.weekday().num_days_from_monday(),
+            ))?)
+        .into(),
+        DateTimeField::Day => date.into(),
+        DateTimeField::Hour => datetime.with_minute(0)?.with_second(0)?,
+        DateTimeField::Minute => datetime.with_second(0)?,
+        DateTimeField::Second => datetime,
+        DateTimeField::Dow | DateTimeField::Doy | DateTimeField::Epoch => {
+            return None
+        }
+    };
+
+    Some(timestamp_from_datetime(truncated))
+}
+
+/// Extract `field` from a timestamp.
+///
+/// `None` will be returned if `field` is not supported.
+pub fn timestamp_part(field: DateTimeField, micros: i64) -> Option<f64> {
+    let datetime = datetime_from_timestamp(micros)?;
+    let part = match field {
+        DateTimeField::Epoch => micros as f64 / MICROS_PER_SEC as f64,
+        DateTimeField::Hour | DateTimeField::Minute | DateTimeField::Second => {
+            return time_part(field, micros_from_time(datetime.time()))
+        }
+        field => return date_part(field, days_from_date(datetime.date())),
+    };
+
+    Some(part)
+}
+
+/// Extract `field` from a date.
+///
+/// `None` will be returned if `field` is not supported.
+pub fn date_part(field: DateTimeField, days: i32) -> Option<f64> {
+    let date = date_from_days(days)?;
+    let part = match field {
+        DateTimeField::Year => f64::from(date.year()),
+        DateTimeField::Quarter => f64::from((date.month() - 1) / 3 + 1),
+        DateTimeField::Month => f64::from(date.month()),
+        DateTimeField::Week => f64::from(date.iso_week().week()),
+        DateTimeField::Day => f64::from(date.day()),
+        DateTimeField::Dow => f64::from(date.weekday().num_days_from_sunday()),
+        DateTimeField::Doy => f64::from(date.ordinal()),
+        DateTimeField::Epoch => i64::from(days) as f64 * SECS_PER_DAY as f64,
+        DateTimeField::Hour | DateTimeField::Minute | DateTimeField::Second => {
+            return None
+        }
+    };
+
+    Some(part)
+}
+
+/// Extract `field` from a time.
+///
+/// `None` will be returned if `field` is not supported.
+pub fn time_part(field: DateTimeField, micros: i64) -> Option<f64> {
+    let part = match field {
+        DateTimeField::Hour => (micros / (3600 * MICROS_PER_SEC)) as f64,
+        DateTimeField::Minute => (micros / (60 * MICROS_PER_SEC) % 60) as f64,
+        DateTimeField::Second => {
+            (micros % (60 * MICROS_PER_SEC)) as f64 / MICROS_PER_SEC as f64
+        }
+        DateTimeField::Epoch => micros as f64 / MICROS_PER_SEC as f64,
+        _ => return None,
+    };
+
+    Some(part)
+}
+
+/// Extract `field` from an interval.
+///
+/// `None` will be returned if `field` is not supported.
+pub fn interval_part(field: DateTimeField, interval: Interval) -> Option<f64> {
+    let part = match field {
+        DateTimeField::Year => f64::from(interval.months / 12),
+        DateTimeField::Quarter => f64::from(interval.months % 12 / 3 + 1),
+        DateTimeField::Month => f64::from(interval.months % 12),
+        DateTimeField::Day => f64::from(interval.days),
+        DateTimeField::Epoch => {
+            // The same as PostgreSQL, a year has 365.25 days.
+            let years = f64::from(interval.months / 12) * 365.25;
+            let months =
+                f64::from(interval.months % 12) * DAYS_PER_MONTH as f64;
+            (years + months + f64::from(interval.days)) * SECS_PER_DAY as f64
+                + interval.micros as f64 / MICROS_PER_SEC as f64
+        }
+        field => return time_part(field, interval.micros),
+    };
+
+    Some(part)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_and_display() {
+        let date = parse_date("2024-02-29").unwrap();
+        assert_eq!(display_date(date), "2024-02-29");
+        assert_eq!(parse_date("1969-12-31"), Some(-1));
+        assert_eq!(parse_date("2024-02-30"), None);
+
+        let time = parse_time("12:34:56.789").unwrap();
+        assert_eq!(display_time(time), "12:34:56.789");
+        assert_eq!(display_time(parse_time("01:02").unwrap()), "01:02:00");
+        assert_eq!(parse_time("25:00"), None);
+
+        let ts = parse_timestamp("2024-01-02 03:04:05").unwrap();
+        assert_eq!(display_timestamp(ts), "2024-01-02 03:04:05");
+        assert_eq!(
+            parse_timestamp("1970-01-02T00:00:01.5"),
+            Some(86_401_500_000)
+        );
+        assert_eq!(parse_timestamp("1970-01-02"), Some(MICROS_PER_DAY));
+        assert_eq!(
+            display_timestamp(
+                parse_timestamp("1969-12-31 23:59:59.25").unwrap()
+            ),
+            "1969-12-31 23:59:59.250"
+        );
+    }
+
+    #[test]
+    fn parse_and_display_timestamptz() {
+        let utc = parse_time_zone("UTC").unwrap();
+        let cst = parse_time_zone("+08").unwrap();
+        let ts = parse_timestamptz("2024-01-01 20:00:00+08", utc).unwrap();
+        assert_eq!(display_timestamptz(ts, utc), "2024-01-01 12:00:00+00");
+        assert_eq!(display_timestamptz(ts, cst), "2024-01-01 20:00:00+08");
+        for str in [
+            "2024-01-01 12:00:00Z",
+            "2024-01-01T12:00:00 UTC",
+            "2024-01-01 06:30:00-05:30",
+            "2024-01-01 12:00:00",
+        ] {
+            assert_eq!(parse_timestamptz(str, utc), Some(ts), "{str}");
+        }
+        assert_eq!(parse_timestamptz("2024-01-01 20:00", cst), Some(ts));
+        assert_eq!(parse_timestamptz("2024-01-01 12:00:00+24", utc), None);
+
+        assert_eq!(display_time_zone(cst), "+08:00");
+        assert_eq!(display_time_zone(utc), "UTC");
+        assert_eq!(
+            parse_time_zone("-0930").map(display_time_zone),
+            Some(String::from("-09:30"))
+        );
+        assert_eq!(parse_time_zone("Asia/Shanghai"), None);
+    }
+
+    #[test]
+    fn parse_and_display_interval() {
+        let cases = [
+            ("1 day", "1 day"),
+            ("2 hours 30 minutes", "02:30:00"),
+            ("1 year 14 months -3 days", "2 years 2 mons -3 days"),
+            ("1.5 months", "1 mon 15 days"),
+            ("1.5 days", "1 day 12:00:00"),
+            ("-04:05:06.5", "-04:05:06.5"),
+            ("3 days 04:05", "3 days 04:05:00"),
+            ("0 seconds", "00:00:00"),
+            ("1 week 2 ms", "7 days 00:00:00.002"),
+        ];
+        for (input, expected) in cases {
+            let interval = parse_interval(input).unwrap();
+            assert_eq!(interval.to_string(), expected, "{input}");
+            // the output can be parsed back
+            assert_eq!(
+                parse_interval(expected).unwrap().encode(),
+                interval.encode()
+            );
+        }
+
+        assert_eq!(parse_interval(""), None);
+        assert_eq!(parse_interval("1"), None);
+        assert_eq!(parse_interval("1 fortnight"), None);
+        assert_eq!(parse_interval("00:61"), None);
+    }
+
+    #[test]
+    fn interval_comparison() {
+        assert_eq!(Interval::new(1, 0, 0), Interval::new(0, 30, 0));
+        assert!(Interval::new(0, 1, 0) > Interval::new(0, 0, 1));
+        let interval = Interval::new(-1, 2, -3);
+        assert_eq!(
+            Interval::decode(&interval.encode()).encode(),
+            interval.encode()
+        );
+    }
+
+    #[test]
+    fn timestamp_arithmetic() {
+        let ts = parse_timestamp("2024-01-31 12:00:00").unwrap();
+        let one_month = parse_interval("1 month").unwrap();
+        assert_eq!(
+            display_timestamp(timestamp_add_interval(ts, one_month).unwrap()),
+            "2024-02-29 12:00:00"
+        );
+        let back = parse_interval("-1 day -12 hours").unwrap();
+        assert_eq!(
+            display_timestamp(timestamp_add_interval(ts, back).unwrap()),
+            "2024-01-30 00:00:00"
+        );
+
+        let earlier = parse_timestamp("2024-01-30 10:00:00").unwrap();
+        assert_eq!(
+            timestamp_diff(ts, earlier).unwrap().to_string(),
+            "1 day 02:00:00"
+        );
+
+        let time = parse_time("23:00").unwrap();
+        let two_hours = parse_interval("2 hours").unwrap();
+        assert_eq!(
+            display_time(time_add_interval(time, two_hours)),
+            "01:00:00"
+        );
+    }
+
+    #[test]
+    fn trunc_and_part() {
+        let ts = parse_timestamp("2024-05-16 13:14:15").unwrap();
+        let trunc = |field| display_timestamp(date_trunc(field, ts).unwrap());
+        assert_eq!(trunc(DateTimeField::Year), "2024-01-01 00:00:00");
+        assert_eq!(trunc(DateTimeField::Quarter), "2024-04-01 00:00:00");
+        assert_eq!(trunc(DateTimeField::Month), "2024-05-01 00:00:00");
+        assert_eq!(trunc(DateTimeField::Week), "2024-05-13 00:00:00");
+        assert_eq!(trunc(DateTimeField::Day), "2024-05-16 00:00:00");
+        assert_eq!(trunc(DateTimeField::Hour), "2024-05-16 13:00:00");
+        assert_eq!(trunc(DateTimeField::Minute), "2024-05-16 13:14:00");
+        assert_eq!(date_trunc(DateTimeField::Dow, ts), None);
+
+        let part = |field| timestamp_part(field, ts).unwrap();
+        assert_eq!(part(DateTimeField::Year), 2024.0);
+        assert_eq!(part(DateTimeField::Quarter), 2.0);
+        assert_eq!(part(DateTimeField::Week), 20.0);
+        assert_eq!(part(DateTimeField::Dow), 4.0);
+        assert_eq!(part(DateTimeField::Doy), 137.0);
+        assert_eq!(part(DateTimeField::Second), 15.0);
+        let ts = parse_timestamp("1970-01-01 00:00:01.5").unwrap();
+        assert_eq!(timestamp_part(DateTimeField::Second, ts), Some(1.5));
+        assert_eq!(timestamp_part(DateTimeField::Epoch, ts), Some(1.5));
+        assert_eq!(
+            display_timestamp(date_trunc(DateTimeField::Second, ts).unwrap()),
+            "1970-01-01 00:00:01"
+        );
+
+        let interval =
+            parse_interval("1 year 2 months 3 days 04:05:06").unwrap();
+        assert_eq!(interval_part(DateTimeField::Month, interval), Some(2.0));
+        assert_eq!(interval_part(DateTimeField::Minute, interval), Some(5.0));
+        assert_eq!(time_part(DateTimeField::Year, 0), None);
+    }
+}
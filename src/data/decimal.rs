@@ -0,0 +1,335 @@
+//! Exact decimal numbers, the values of `DECIMAL(precision, scale)`.
+
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+};
+
+/// The maximum number of significant digits of a decimal.
+pub const MAX_PRECISION: u8 = 38;
+
+/// A decimal number `mantissa * 10^(-scale)`.
+///
+/// Decimals of different scales can be compared, `1.5` equals to `1.50`.
+#[derive(Debug, Copy, Clone)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u8,
+}
+
+/// Return `10^exp`, `None` on overflow.
+fn pow10(exp: u32) -> Option<i128> {
+    10_i128.checked_pow(exp)
+}
+
+impl Decimal {
+    /// How many bytes it will take after encoding.
+    pub const ENCODE_SIZE: usize = std::mem::size_of::<i128>();
+
+    pub fn new(mantissa: i128, scale: u8) -> Self {
+        Self { mantissa, scale }
+    }
+
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// Parse a decimal like `-12.340`, the scale is the number of fractional
+    /// digits in `str`.
+    pub fn parse(str: &str) -> Option<Self> {
+        let str = str.trim();
+        let (negative, digits) = match str.as_bytes().first()? {
+            b'-' => (true, &str[1..]),
+            b'+' => (false, &str[1..]),
+            _ => (false, str),
+        };
+        let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+        if int.is_empty() && frac.is_empty() {
+            return None;
+        }
+
+        let mut mantissa = 0_i128;
+        for char in int.chars().chain(frac.chars()) {
+            let digit = char.to_digit(10)?;
+            mantissa = mantissa.checked_mul(10)?.checked_add(digit.into())?;
+        }
+        let scale = u8::try_from(frac.len()).ok()?;
+        if scale > MAX_PRECISION {
+            return None;
+        }
+
+        Some(Self::new(
+            if negative { -mantissa } else { mantissa },
+            scale,
+        ))
+    }
+
+    /// Parse a number literal like `1.5` or `-1.5e3`, `None` will be returned
+    /// if it does not fit in [`MAX_PRECISION`] digits.
+    pub fn parse_literal(str: &str) -> Option<Self> {
+        let (number, exp) = match str.split_once(['e', 'E']) {
+            Some((number, exp)) => (number, exp.parse::<i32>().ok()?),
+            None => (str, 0),
+        };
+        let decimal = Self::parse(number)?;
+        let scale = i32::from(decimal.scale) - exp;
+        let decimal = if scale < 0 {
+            let factor = pow10(u32::try_from(-scale).ok()?)?;
+            Self::new(decimal.mantissa.checked_mul(factor)?, 0)
+        } else {
+            Self::new(decimal.mantissa, u8::try_from(scale).ok()?)
+        };
+        if decimal.scale > MAX_PRECISION
+            || decimal.mantissa.unsigned_abs()
+                >= pow10(u32::from(MAX_PRECISION))? as u128
+        {
+            return None;
+        }
+
+        Some(decimal)
+    }
+
+    /// Convert a float through its shortest representation, so that `0.1` is
+    /// converted to `0.1` rather than `0.1000000000000000055511151231257827`.
+    pub fn from_f64(float: f64) -> Option<Self> {
+        if !float.is_finite() {
+            return None;
+        }
+        Self::parse(&float.to_string())
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().expect("should be a valid float")
+    }
+
+    /// Return the number of digits before the decimal point.
+    fn integral_digits(&self) -> u32 {
+        let integral =
+            self.mantissa.unsigned_abs() / 10_u128.pow(u32::from(self.scale));
+        integral.checked_ilog10().map_or(0, |digits| digits + 1)
+    }
+
+    /// Convert it to `scale`, rounding half away from zero, `None` on
+    /// overflow.
+    pub fn rescale(&self, scale: u8) -> Option<Self> {
+        let mantissa = match scale.cmp(&self.scale) {
+            Ordering::Equal => self.mantissa,
+            Ordering::Greater => self
+                .mantissa
+                .checked_mul(pow10(u32::from(scale - self.scale))?)?,
+            Ordering::Less => {
+                let Some(divisor) = pow10(u32::from(self.scale - scale)) else {
+                    // `self` is less than 0.5 at `scale`
+                    return Some(Self::new(0, scale));
+                };
+                let quotient = self.mantissa / divisor;
+                let remainder = self.mantissa % divisor;
+                if remainder.unsigned_abs() * 2 >= divisor.unsigned_abs() {
+                    quotient + self.mantissa.signum()
+                } else {
+                    quotient
+                }
+            }
+        };
+
+        Some(Self::new(mantissa, scale))
+    }
+
+    /// Round it to `scale`, `None` will be returned if the result does not
+    /// fit in `precision` digits.
+    pub fn round(&self, precision: u8, scale: u8) -> Option<Self> {
+        let rounded = self.rescale(scale)?;
+        if rounded.integral_digits() > u32::from(precision - scale) {
+            return None;
+        }
+
+        Some(rounded)
+    }
+
+    /// Compute `self + rhs` in the larger scale of them, `None` on overflow.
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        let scale = self.scale.max(rhs.scale);
+        let mantissa = self
+            .rescale(scale)?
+            .mantissa
+            .checked_add(rhs.rescale(scale)?.mantissa)?;
+        if mantissa.unsigned_abs() >= pow10(u32::from(MAX_PRECISION))? as u128 {
+            return None;
+        }
+
+        Some(Self::new(mantissa, scale))
+    }
+
+    /// Compute `self - rhs` in the larger scale of them, `None` on overflow.
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        self.checked_add(&Self::new(rhs.mantissa.checked_neg()?, rhs.scale))
+    }
+
+    /// Compute `self / rhs` in `scale`, rounding half away from zero, `None`
+    /// on overflow or division by zero.
+    pub fn checked_div_int(&self, rhs: i128, scale: u8) -> Option<Self> {
+        let dividend = self.rescale(scale)?.mantissa;
+        let quotient = dividend.checked_div(rhs)?;
+        let remainder = dividend % rhs;
+        let mantissa = if remainder.unsigned_abs() * 2 >= rhs.unsigned_abs() {
+            quotient + (dividend.signum() * rhs.signum())
+        } else {
+            quotient
+        };
+
+        Some(Self::new(mantissa, scale))
+    }
+
+    /// Encode it so that the bytes of decimals of the same scale are ordered
+    /// as the numbers.
+    pub fn encode(&self) -> [u8; Self::ENCODE_SIZE] {
+        // flipping the sign bit puts negative numbers before the positive ones
+        ((self.mantissa as u128) ^ (1 << 127)).to_be_bytes()
+    }
+
+    /// Decode a decimal of `scale` encoded by [`Decimal::encode()`].
+    pub fn decode(buf: &[u8], scale: u8) -> Self {
+        let bytes = buf[..Self::ENCODE_SIZE]
+            .try_into()
+            .expect("slice has the correct length");
+        let mantissa = (u128::from_be_bytes(bytes) ^ (1 << 127)) as i128;
+
+        Self::new(mantissa, scale)
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        match (self.rescale(scale), other.rescale(scale)) {
+            (Some(lhs), Some(rhs)) => lhs.mantissa.cmp(&rhs.mantissa),
+            // The one that overflows has more integral digits, so its sign
+            // decides.
+            (None, _) => self.mantissa.cmp(&0),
+            (_, None) => 0.cmp(&other.mantissa),
+        }
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = usize::from(self.scale);
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (int, frac) = digits.split_at(digits.len() - scale);
+        if self.mantissa < 0 {
+            write!(f, "-")?;
+        }
+        write!(f, "{int}")?;
+        if !frac.is_empty() {
+            write!(f, ".{frac}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_and_display() {
+        for (str, expected) in [
+            ("12.340", "12.340"),
+            ("-0.05", "-0.05"),
+            ("+7", "7"),
+            (".5", "0.5"),
+            ("3.", "3"),
+        ] {
+            assert_eq!(Decimal::parse(str).unwrap().to_string(), expected);
+        }
+        for str in ["", "-", ".", "1.2.3", "1e5", "abc"] {
+            assert!(Decimal::parse(str).is_none(), "{str}");
+        }
+
+        assert_eq!(Decimal::from_f64(0.1).unwrap(), Decimal::new(1, 1));
+        assert!(Decimal::from_f64(f64::NAN).is_none());
+
+        for (str, expected) in [
+            ("12345678901234567.89", "12345678901234567.89"),
+            ("1.5e3", "1500"),
+            ("-25E-3", "-0.025"),
+        ] {
+            assert_eq!(
+                Decimal::parse_literal(str).unwrap().to_string(),
+                expected
+            );
+        }
+        for str in ["1e39", "1e-39", "1e", "e5"] {
+            assert!(Decimal::parse_literal(str).is_none(), "{str}");
+        }
+    }
+
+    #[test]
+    fn round() {
+        let round = |str: &str, precision, scale| {
+            Decimal::parse(str)
+                .unwrap()
+                .round(precision, scale)
+                .map(|d| d.to_string())
+        };
+        assert_eq!(round("1.005", 5, 2).unwrap(), "1.01");
+        assert_eq!(round("-1.005", 5, 2).unwrap(), "-1.01");
+        assert_eq!(round("1.004", 5, 2).unwrap(), "1.00");
+        assert_eq!(round("7", 5, 2).unwrap(), "7.00");
+        assert_eq!(round("999.995", 5, 2), None);
+        assert_eq!(round("999.994", 5, 2).unwrap(), "999.99");
+        assert_eq!(round("0.5", 1, 0).unwrap(), "1");
+    }
+
+    #[test]
+    fn arithmetic_and_comparison() {
+        let a = Decimal::parse("0.1").unwrap();
+        let b = Decimal::parse("0.20").unwrap();
+        assert_eq!(a.checked_add(&b).unwrap().to_string(), "0.30");
+        assert_eq!(a.checked_sub(&b).unwrap().to_string(), "-0.10");
+        assert_eq!(Decimal::parse("1.5"), Decimal::parse("1.50"));
+        assert!(a < b);
+
+        let sum = Decimal::parse("0.30").unwrap();
+        assert_eq!(sum.checked_div_int(2, 6).unwrap().to_string(), "0.150000");
+        assert_eq!(sum.checked_div_int(-4, 1).unwrap().to_string(), "-0.1");
+        assert!(sum.checked_div_int(0, 2).is_none());
+
+        let max = Decimal::new(pow10(38).unwrap() - 1, 0);
+        assert!(max.checked_add(&Decimal::new(1, 0)).is_none());
+        assert!(max > Decimal::new(1, 10));
+    }
+
+    #[test]
+    fn encoding_preserves_order() {
+        let values = ["-100.01", "-1.00", "0", "0.01", "1", "99999.99"]
+            .map(|str| Decimal::parse(str).unwrap().rescale(2).unwrap());
+        for pair in values.windows(2) {
+            assert!(pair[0].encode() < pair[1].encode());
+        }
+        for value in values {
+            assert_eq!(Decimal::decode(&value.encode(), 2), value);
+        }
+    }
+}
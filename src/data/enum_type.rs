@@ -0,0 +1,133 @@
+//! User-defined enum types, created by `CREATE TYPE name AS ENUM (...)`.
+
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+    sync::Arc,
+};
+
+/// An enum type, i.e., a fixed set of labels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumType {
+    name: String,
+    /// Labels in the declaration order, which is also the order of values.
+    labels: Vec<String>,
+}
+
+impl EnumType {
+    pub fn new(name: String, labels: Vec<String>) -> Self {
+        Self { name, labels }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Return the value of `label`, `None` will be returned if it is not a
+    /// label of this type.
+    pub fn value(self: &Arc<Self>, label: &str) -> Option<EnumValue> {
+        let ordinal = self.labels.iter().position(|l| l == label)?;
+
+        Some(EnumValue {
+            enum_type: Arc::clone(self),
+            ordinal: ordinal as u32,
+        })
+    }
+
+    /// Return the value of `ordinal`, which should be a valid one.
+    pub fn value_of_ordinal(self: &Arc<Self>, ordinal: u32) -> EnumValue {
+        assert!((ordinal as usize) < self.labels.len(), "invalid ordinal");
+
+        EnumValue {
+            enum_type: Arc::clone(self),
+            ordinal,
+        }
+    }
+}
+
+/// A value of an enum type, which is one of its labels.
+///
+/// Values are ordered by the declaration order of their labels.
+#[derive(Debug, Clone)]
+pub struct EnumValue {
+    enum_type: Arc<EnumType>,
+    /// Index of the label.
+    ordinal: u32,
+}
+
+impl EnumValue {
+    /// How many bytes it will take after encoding.
+    pub const ENCODE_SIZE: usize = std::mem::size_of::<u32>();
+
+    pub fn enum_type(&self) -> &Arc<EnumType> {
+        &self.enum_type
+    }
+
+    pub fn ordinal(&self) -> u32 {
+        self.ordinal
+    }
+
+    pub fn label(&self) -> &str {
+        &self.enum_type.labels[self.ordinal as usize]
+    }
+
+    /// Encode it as its ordinal, in big-endian so that the encoded bytes are
+    /// ordered as the values.
+    pub fn encode(&self) -> [u8; Self::ENCODE_SIZE] {
+        self.ordinal.to_be_bytes()
+    }
+}
+
+impl PartialEq for EnumValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.enum_type == other.enum_type && self.ordinal == other.ordinal
+    }
+}
+
+impl PartialOrd for EnumValue {
+    /// Values of different enum types are not comparable.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.enum_type != other.enum_type {
+            return None;
+        }
+
+        self.ordinal.partial_cmp(&other.ordinal)
+    }
+}
+
+impl Display for EnumValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn values_are_ordered_by_declaration() {
+        let mood = Arc::new(EnumType::new(
+            "mood".into(),
+            vec!["sad".into(), "ok".into(), "happy".into()],
+        ));
+        let sad = mood.value("sad").unwrap();
+        let happy = mood.value("happy").unwrap();
+        assert!(sad < happy);
+        assert_eq!(happy.ordinal(), 2);
+        assert_eq!(happy.to_string(), "happy");
+        assert_eq!(mood.value_of_ordinal(0), sad);
+        assert!(mood.value("Happy").is_none());
+        assert!(sad.encode() < happy.encode());
+
+        let other = Arc::new(EnumType::new("other".into(), vec!["sad".into()]));
+        let other_sad = other.value("sad").unwrap();
+        assert_ne!(other_sad, sad);
+        assert_eq!(other_sad.partial_cmp(&sad), None);
+    }
+}
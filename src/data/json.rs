@@ -0,0 +1,635 @@
+//! JSON values, the values of `JSON` (and `JSONB`).
+//!
+//! Like `JSONB` of PostgreSQL, values are normalized when parsed: whitespace
+//! is dropped, object keys are sorted and only the last one of duplicate keys
+//! is kept.
+
+use bytes::{Buf, BufMut};
+use std::fmt::{Display, Formatter, Write};
+
+/// A JSON value.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Members sorted by [`key_order()`], keys are unique.
+    Object(Vec<(String, Json)>),
+}
+
+/// The order of object keys, shorter keys go first, keys of the same length
+/// are ordered by their bytes, like PostgreSQL.
+fn key_order(lhs: &str, rhs: &str) -> std::cmp::Ordering {
+    lhs.len().cmp(&rhs.len()).then_with(|| lhs.cmp(rhs))
+}
+
+// Tags of the binary encoding.
+const TAG_NULL: u8 = 0;
+const TAG_FALSE: u8 = 1;
+const TAG_TRUE: u8 = 2;
+const TAG_NUMBER: u8 = 3;
+const TAG_STRING: u8 = 4;
+const TAG_ARRAY: u8 = 5;
+const TAG_OBJECT: u8 = 6;
+
+/// Return how many bytes `value` takes as a LEB128 varint.
+fn varint_size(mut value: u64) -> usize {
+    let mut size = 1;
+    while value >= 0x80 {
+        value >>= 7;
+        size += 1;
+    }
+
+    size
+}
+
+fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.put_u8((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buf.put_u8(value as u8);
+}
+
+fn get_varint(buf: &mut &[u8]) -> u64 {
+    let mut value = 0_u64;
+    let mut shift = 0;
+    loop {
+        let byte = buf.get_u8();
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return value;
+        }
+        shift += 7;
+    }
+}
+
+fn put_str(buf: &mut Vec<u8>, str: &str) {
+    put_varint(buf, str.len() as u64);
+    buf.put_slice(str.as_bytes());
+}
+
+fn get_str(buf: &mut &[u8]) -> String {
+    let len = get_varint(buf) as usize;
+    let str = std::str::from_utf8(&buf[..len])
+        .expect("should be UTF-8 encoded")
+        .to_string();
+    buf.advance(len);
+
+    str
+}
+
+fn str_size(str: &str) -> usize {
+    varint_size(str.len() as u64) + str.len()
+}
+
+impl Json {
+    /// Parse a JSON text, `None` will be returned if it is invalid.
+    pub fn parse(str: &str) -> Option<Self> {
+        let mut parser = Parser {
+            str: str.as_bytes(),
+            pos: 0,
+        };
+        let json = parser.parse_value()?;
+        parser.skip_whitespace();
+        if parser.pos != str.len() {
+            return None;
+        }
+
+        Some(json)
+    }
+
+    /// Create an object from `members`, sorting the keys and keeping the
+    /// last one of duplicate keys.
+    fn object(members: Vec<(String, Json)>) -> Self {
+        let mut sorted: Vec<(String, Json)> = Vec::with_capacity(members.len());
+        for (key, value) in members {
+            match sorted
+                .binary_search_by(|(existing, _)| key_order(existing, &key))
+            {
+                Ok(idx) => sorted[idx].1 = value,
+                Err(idx) => sorted.insert(idx, (key, value)),
+            }
+        }
+
+        Json::Object(sorted)
+    }
+
+    /// Return the name of its type, as `jsonb_typeof()` does.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Json::Null => "null",
+            Json::Bool(_) => "boolean",
+            Json::Number(_) => "number",
+            Json::String(_) => "string",
+            Json::Array(_) => "array",
+            Json::Object(_) => "object",
+        }
+    }
+
+    /// Return the member `key` of an object.
+    pub fn field(&self, key: &str) -> Option<&Json> {
+        let Json::Object(members) = self else {
+            return None;
+        };
+        members
+            .binary_search_by(|(existing, _)| key_order(existing, key))
+            .ok()
+            .map(|idx| &members[idx].1)
+    }
+
+    /// Return the element at `idx` of an array, a negative `idx` counts from
+    /// the end.
+    pub fn element(&self, idx: i64) -> Option<&Json> {
+        let Json::Array(elements) = self else {
+            return None;
+        };
+        let idx = if idx < 0 {
+            elements.len().checked_sub(idx.unsigned_abs() as usize)?
+        } else {
+            idx as usize
+        };
+
+        elements.get(idx)
+    }
+
+    /// Return true if `key` is a key of an object, or a string element of
+    /// an array, i.e., the `?` operator.
+    pub fn exists(&self, key: &str) -> bool {
+        match self {
+            Json::Object(_) => self.field(key).is_some(),
+            Json::Array(elements) => elements.iter().any(
+                |element| matches!(element, Json::String(str) if str == key),
+            ),
+            _ => false,
+        }
+    }
+
+    /// Return true if `self` contains `other`, i.e., the `@>` operator.
+    ///
+    /// An object contains another one if it has all the keys of the other one
+    /// and their values contain the values of the other one, an array
+    /// contains another one if every element of the other one is contained by
+    /// one of its elements. As a special case, an array contains a scalar if
+    /// it has the scalar as an element.
+    pub fn contains(&self, other: &Json) -> bool {
+        match (self, other) {
+            (Json::Array(elements), Json::Array(_) | Json::Object(_)) => {
+                other.is_contained_in_array(elements)
+            }
+            (Json::Array(elements), scalar) => {
+                elements.iter().any(|element| element == scalar)
+            }
+            _ => self.contains_nested(other),
+        }
+    }
+
+    fn contains_nested(&self, other: &Json) -> bool {
+        match (self, other) {
+            (Json::Object(_), Json::Object(members)) => {
+                members.iter().all(|(key, value)| {
+                    self.field(key)
+                        .is_some_and(|field| field.contains_nested(value))
+                })
+            }
+            (Json::Array(elements), Json::Array(_)) => {
+                other.is_contained_in_array(elements)
+            }
+            _ => self == other,
+        }
+    }
+
+    /// Return true if every element of the array `self` is contained by one
+    /// of `elements`.
+    fn is_contained_in_array(&self, elements: &[Json]) -> bool {
+        let Json::Array(others) = self else {
+            return false;
+        };
+        others.iter().all(|other| {
+            elements
+                .iter()
+                .any(|element| element.contains_nested(other))
+        })
+    }
+
+    /// How many bytes it will take after encoding.
+    pub fn encode_size(&self) -> usize {
+        1 + match self {
+            Json::Null | Json::Bool(_) => 0,
+            Json::Number(_) => std::mem::size_of::<f64>(),
+            Json::String(str) => str_size(str),
+            Json::Array(elements) => {
+                varint_size(elements.len() as u64)
+                    + elements.iter().map(Json::encode_size).sum::<usize>()
+            }
+            Json::Object(members) => {
+                varint_size(members.len() as u64)
+                    + members
+                        .iter()
+                        .map(|(key, value)| str_size(key) + value.encode_size())
+                        .sum::<usize>()
+            }
+        }
+    }
+
+    /// Encode it in a compact binary form, a tag byte followed by the value,
+    /// where lengths are varints.
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        match self {
+            Json::Null => buf.put_u8(TAG_NULL),
+            Json::Bool(false) => buf.put_u8(TAG_FALSE),
+            Json::Bool(true) => buf.put_u8(TAG_TRUE),
+            Json::Number(number) => {
+                buf.put_u8(TAG_NUMBER);
+                buf.put_f64_ne(*number);
+            }
+            Json::String(str) => {
+                buf.put_u8(TAG_STRING);
+                put_str(buf, str);
+            }
+            Json::Array(elements) => {
+                buf.put_u8(TAG_ARRAY);
+                put_varint(buf, elements.len() as u64);
+                for element in elements {
+                    element.encode(buf);
+                }
+            }
+            Json::Object(members) => {
+                buf.put_u8(TAG_OBJECT);
+                put_varint(buf, members.len() as u64);
+                for (key, value) in members {
+                    put_str(buf, key);
+                    value.encode(buf);
+                }
+            }
+        }
+    }
+
+    /// Decode a value encoded by [`Json::encode()`], `buf` is advanced past
+    /// it.
+    pub fn decode(buf: &mut &[u8]) -> Self {
+        match buf.get_u8() {
+            TAG_NULL => Json::Null,
+            TAG_FALSE => Json::Bool(false),
+            TAG_TRUE => Json::Bool(true),
+            TAG_NUMBER => Json::Number(buf.get_f64_ne()),
+            TAG_STRING => Json::String(get_str(buf)),
+            TAG_ARRAY => {
+                let len = get_varint(buf) as usize;
+                Json::Array((0..len).map(|_| Json::decode(buf)).collect())
+            }
+            TAG_OBJECT => {
+                let len = get_varint(buf) as usize;
+                Json::Object(
+                    (0..len)
+                        .map(|_| (get_str(buf), Json::decode(buf)))
+                        .collect(),
+                )
+            }
+            tag => panic!("invalid JSON tag {tag}"),
+        }
+    }
+}
+
+/// Write `str` as a JSON string.
+fn write_string(f: &mut Formatter<'_>, str: &str) -> std::fmt::Result {
+    f.write_char('"')?;
+    for char in str.chars() {
+        match char {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            '\u{8}' => f.write_str("\\b")?,
+            '\u{c}' => f.write_str("\\f")?,
+            char if char.is_control() => {
+                write!(f, "\\u{:04x}", u32::from(char))?
+            }
+            char => f.write_char(char)?,
+        }
+    }
+    f.write_char('"')
+}
+
+impl Display for Json {
+    /// Display it like PostgreSQL displays `JSONB`, e.g.,
+    /// `{"a": 1, "b": [true, null]}`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(bool) => write!(f, "{bool}"),
+            Json::Number(number) => write!(f, "{number}"),
+            Json::String(str) => write_string(f, str),
+            Json::Array(elements) => {
+                f.write_char('[')?;
+                for (idx, element) in elements.iter().enumerate() {
+                    if idx != 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{element}")?;
+                }
+                f.write_char(']')
+            }
+            Json::Object(members) => {
+                f.write_char('{')?;
+                for (idx, (key, value)) in members.iter().enumerate() {
+                    if idx != 0 {
+                        f.write_str(", ")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ": {value}")?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+/// A recursive descent parser of JSON texts.
+struct Parser<'str> {
+    str: &'str [u8],
+    pos: usize,
+}
+
+impl<'str> Parser<'str> {
+    fn peek(&self) -> Option<u8> {
+        self.str.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consume `expected` if it is the next byte.
+    fn eat(&mut self, expected: u8) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            return true;
+        }
+
+        false
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.str[self.pos..].starts_with(keyword.as_bytes()) {
+            self.pos += keyword.len();
+            return true;
+        }
+
+        false
+    }
+
+    fn parse_value(&mut self) -> Option<Json> {
+        self.skip_whitespace();
+        match self.peek()? {
+            b'n' if self.eat_keyword("null") => Some(Json::Null),
+            b't' if self.eat_keyword("true") => Some(Json::Bool(true)),
+            b'f' if self.eat_keyword("false") => Some(Json::Bool(false)),
+            b'"' => self.parse_string().map(Json::String),
+            b'[' => self.parse_array(),
+            b'{' => self.parse_object(),
+            b'-' | b'0'..=b'9' => self.parse_number(),
+            _ => None,
+        }
+    }
+
+    fn parse_array(&mut self) -> Option<Json> {
+        self.pos += 1;
+        let mut elements = Vec::new();
+        self.skip_whitespace();
+        if self.eat(b']') {
+            return Some(Json::Array(elements));
+        }
+        loop {
+            elements.push(self.parse_value()?);
+            self.skip_whitespace();
+            if self.eat(b']') {
+                return Some(Json::Array(elements));
+            }
+            if !self.eat(b',') {
+                return None;
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Option<Json> {
+        self.pos += 1;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.eat(b'}') {
+            return Some(Json::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return None;
+            }
+            let key = self.parse_string()?;
+            self.skip_whitespace();
+            if !self.eat(b':') {
+                return None;
+            }
+            members.push((key, self.parse_value()?));
+            self.skip_whitespace();
+            if self.eat(b'}') {
+                return Some(Json::object(members));
+            }
+            if !self.eat(b',') {
+                return None;
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Option<Json> {
+        let start = self.pos;
+        let digits = |parser: &mut Self| {
+            let start = parser.pos;
+            while matches!(parser.peek(), Some(b'0'..=b'9')) {
+                parser.pos += 1;
+            }
+            parser.pos > start
+        };
+
+        self.eat(b'-');
+        // no leading zeros
+        if !self.eat(b'0') && !digits(self) {
+            return None;
+        }
+        if self.eat(b'.') && !digits(self) {
+            return None;
+        }
+        if self.eat(b'e') || self.eat(b'E') {
+            if !self.eat(b'+') {
+                self.eat(b'-');
+            }
+            if !digits(self) {
+                return None;
+            }
+        }
+
+        let number = std::str::from_utf8(&self.str[start..self.pos])
+            .ok()?
+            .parse::<f64>()
+            .ok()?;
+        number.is_finite().then_some(Json::Number(number))
+    }
+
+    fn parse_hex4(&mut self) -> Option<u32> {
+        let hex = self.str.get(self.pos..self.pos + 4)?;
+        self.pos += 4;
+        u32::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()
+    }
+
+    fn parse_string(&mut self) -> Option<String> {
+        self.pos += 1;
+        let mut str = String::new();
+        loop {
+            let start = self.pos;
+            while !matches!(self.peek()?, b'"' | b'\\' | 0x00..=0x1f) {
+                self.pos += 1;
+            }
+            str.push_str(std::str::from_utf8(&self.str[start..self.pos]).ok()?);
+
+            match self.peek()? {
+                b'"' => {
+                    self.pos += 1;
+                    return Some(str);
+                }
+                b'\\' => {
+                    self.pos += 1;
+                    let escaped = self.peek()?;
+                    self.pos += 1;
+                    let char = match escaped {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let high = self.parse_hex4()?;
+                            let code = if (0xd800..0xdc00).contains(&high) {
+                                // a surrogate pair
+                                if !self.eat_keyword("\\u") {
+                                    return None;
+                                }
+                                let low = self.parse_hex4()?;
+                                if !(0xdc00..0xe000).contains(&low) {
+                                    return None;
+                                }
+                                0x10000
+                                    + ((high - 0xd800) << 10)
+                                    + (low - 0xdc00)
+                            } else {
+                                high
+                            };
+                            char::from_u32(code)?
+                        }
+                        _ => return None,
+                    };
+                    str.push(char);
+                }
+                // control characters must be escaped
+                _ => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn json(str: &str) -> Json {
+        Json::parse(str).unwrap()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        for (str, expected) in [
+            (" null ", "null"),
+            ("[1, 2.5,-3e2,true]", "[1, 2.5, -300, true]"),
+            (
+                r#"{"b":1,"aa":2,"a":{"x":[]},"b":3}"#,
+                r#"{"a": {"x": []}, "b": 3, "aa": 2}"#,
+            ),
+            (
+                r#""tab\t\"q\" \u00e9 \ud83d\ude00""#,
+                "\"tab\\t\\\"q\\\" é 😀\"",
+            ),
+            ("{}", "{}"),
+        ] {
+            assert_eq!(json(str).to_string(), expected);
+        }
+
+        for str in [
+            "",
+            "nul",
+            "[1,]",
+            "{\"a\"}",
+            "{a: 1}",
+            "01",
+            "1.",
+            "-",
+            "1e",
+            "\"\n\"",
+            "\"\\x\"",
+            "[1] 2",
+            "1e999",
+            "\"\\ud83d\"",
+        ] {
+            assert!(Json::parse(str).is_none(), "{str}");
+        }
+    }
+
+    #[test]
+    fn encoding() {
+        let value = json(
+            r#"{"name": "vinyl", "tags": ["a", "b"], "n": 1.5, "ok": false, "none": null}"#,
+        );
+        let mut buf = Vec::new();
+        value.encode(&mut buf);
+        assert_eq!(buf.len(), value.encode_size());
+        // trailing bytes are not consumed
+        buf.push(0xff);
+        let mut slice = buf.as_slice();
+        assert_eq!(Json::decode(&mut slice), value);
+        assert_eq!(slice, &[0xff]);
+
+        let long = Json::String("x".repeat(300));
+        let mut buf = Vec::new();
+        long.encode(&mut buf);
+        assert_eq!(buf.len(), 1 + 2 + 300);
+        assert_eq!(Json::decode(&mut buf.as_slice()), long);
+    }
+
+    #[test]
+    fn access_and_containment() {
+        let value = json(r#"{"a": {"b": [1, 2, {"c": "d"}]}, "e": ["x"]}"#);
+        assert_eq!(
+            value.field("a").unwrap().field("b").unwrap().element(-1),
+            Some(&json(r#"{"c": "d"}"#))
+        );
+        assert_eq!(value.field("z"), None);
+        assert_eq!(value.element(0), None);
+
+        assert!(value.exists("e"));
+        assert!(value.field("e").unwrap().exists("x"));
+        assert!(!value.exists("x"));
+
+        assert!(value.contains(&json(r#"{"a": {"b": [{"c": "d"}, 1]}}"#)));
+        assert!(value.contains(&json("{}")));
+        assert!(!value.contains(&json(r#"{"a": {"b": 1}}"#)));
+        assert!(json("[1, [2, 3]]").contains(&json("[[3]]")));
+        assert!(json("[1, 2]").contains(&json("1")));
+        assert!(!json(r#"{"a": [1, 2]}"#).contains(&json(r#"{"a": 1}"#)));
+    }
+}
@@ -0,0 +1,295 @@
+//! Arrays, the values of list types like `INT64[]`.
+
+use super::types::{Data, DataType};
+use bytes::{Buf, BufMut};
+use chrono::FixedOffset;
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+};
+
+/// An array of values of the same type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct List {
+    /// The value type of the elements, which is known even if there is no
+    /// element.
+    element_type: DataType,
+    elements: Vec<Data>,
+}
+
+impl List {
+    pub fn new(element_type: DataType, elements: Vec<Data>) -> Self {
+        Self {
+            element_type,
+            elements,
+        }
+    }
+
+    pub fn element_type(&self) -> &DataType {
+        &self.element_type
+    }
+
+    pub fn elements(&self) -> &[Data] {
+        &self.elements
+    }
+
+    pub fn into_elements(self) -> Vec<Data> {
+        self.elements
+    }
+
+    /// How many bytes it will take after encoding.
+    pub fn encode_size(&self) -> usize {
+        std::mem::size_of::<u64>()
+            + self.elements.iter().map(Data::encode_size).sum::<usize>()
+    }
+
+    /// Encode it as the number of elements (`u64`) followed by the encoded
+    /// elements.
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        buf.put_u64_ne(
+            self.elements
+                .len()
+                .try_into()
+                .expect("should never fail on a 64-bit machine"),
+        );
+        for element in &self.elements {
+            buf.put_slice(element.encode().as_ref());
+        }
+    }
+
+    /// Decode a list of `element_type` encoded by [`List::encode()`].
+    pub fn decode(mut buf: &[u8], element_type: &DataType) -> Self {
+        let len: usize = buf
+            .get_u64_ne()
+            .try_into()
+            .expect("should never fail on a 64-bit machine");
+        let elements = (0..len)
+            .map(|_| {
+                let element = Data::decode(buf, element_type);
+                buf.advance(element.encode_size());
+                element
+            })
+            .collect();
+
+        Self::new(element_type.value_type(), elements)
+    }
+
+    /// Parse the text form of an array of `element_type`, e.g., `{1,2,3}`
+    /// or `{"a b",c}`, `None` will be returned if it is invalid.
+    ///
+    /// `time_zone` is used by the `TIMESTAMPTZ` elements without a UTC
+    /// offset.
+    pub fn parse(
+        str: &str,
+        element_type: &DataType,
+        time_zone: FixedOffset,
+    ) -> Option<Self> {
+        let mut parser = Parser {
+            str,
+            pos: 0,
+            time_zone,
+        };
+        let list = parser.parse_list(element_type)?;
+        parser.skip_whitespace();
+        if parser.pos != str.len() {
+            return None;
+        }
+
+        Some(list)
+    }
+}
+
+impl PartialOrd for List {
+    /// Lists are compared element by element.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.elements.partial_cmp(&other.elements)
+    }
+}
+
+/// Return true if `str` has to be quoted as an element of an array.
+fn needs_quotes(str: &str) -> bool {
+    str.is_empty()
+        || str.eq_ignore_ascii_case("null")
+        || str.chars().any(|char| {
+            matches!(char, '{' | '}' | ',' | '"' | '\\') || char.is_whitespace()
+        })
+}
+
+impl Display for List {
+    /// Display it like PostgreSQL, e.g., `{1,2,3}` or `{"a b",c}`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{")?;
+        for (idx, element) in self.elements.iter().enumerate() {
+            if idx != 0 {
+                write!(f, ",")?;
+            }
+            let str = element.to_string();
+            if matches!(element, Data::List(_)) || !needs_quotes(&str) {
+                write!(f, "{str}")?;
+            } else {
+                write!(
+                    f,
+                    "\"{}\"",
+                    str.replace('\\', "\\\\").replace('"', "\\\"")
+                )?;
+            }
+        }
+        write!(f, "}}")
+    }
+}
+
+/// A parser of the text form of arrays.
+struct Parser<'str> {
+    str: &'str str,
+    pos: usize,
+    time_zone: FixedOffset,
+}
+
+impl<'str> Parser<'str> {
+    fn peek(&self) -> Option<char> {
+        self.str[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let char = self.peek()?;
+        self.pos += char.len_utf8();
+        Some(char)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(char) = self.peek().filter(|char| char.is_whitespace()) {
+            self.pos += char.len_utf8();
+        }
+    }
+
+    fn parse_list(&mut self, element_type: &DataType) -> Option<List> {
+        self.skip_whitespace();
+        if self.next()? != '{' {
+            return None;
+        }
+        let mut elements = Vec::new();
+        self.skip_whitespace();
+        if self.peek()? == '}' {
+            self.pos += 1;
+            return Some(List::new(element_type.value_type(), elements));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let element = match (self.peek()?, element_type) {
+                ('{', DataType::List(inner)) => {
+                    Data::List(self.parse_list(inner)?)
+                }
+                ('"', _) => {
+                    let str = self.parse_quoted()?;
+                    Data::parse(&str, element_type, self.time_zone)?
+                }
+                _ => {
+                    let str = self.parse_unquoted()?;
+                    // NULL is not supported
+                    if str.eq_ignore_ascii_case("null") {
+                        return None;
+                    }
+                    Data::parse(&str, element_type, self.time_zone)?
+                }
+            };
+            elements.push(element);
+
+            self.skip_whitespace();
+            match self.next()? {
+                ',' => continue,
+                '}' => {
+                    return Some(List::new(element_type.value_type(), elements))
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// Parse an element in double quotes, where `\` escapes the next
+    /// character.
+    fn parse_quoted(&mut self) -> Option<String> {
+        self.pos += 1;
+        let mut str = String::new();
+        loop {
+            match self.next()? {
+                '"' => return Some(str),
+                '\\' => str.push(self.next()?),
+                char => str.push(char),
+            }
+        }
+    }
+
+    /// Parse an element without quotes, which ends before `,` or `}`, and
+    /// has its surrounding whitespace removed.
+    fn parse_unquoted(&mut self) -> Option<String> {
+        let mut str = String::new();
+        loop {
+            match self.peek()? {
+                ',' | '}' => break,
+                '{' | '"' => return None,
+                '\\' => {
+                    self.pos += 1;
+                    str.push(self.next()?);
+                }
+                char => {
+                    self.pos += char.len_utf8();
+                    str.push(char);
+                }
+            }
+        }
+        let str = str.trim_end().to_string();
+        if str.is_empty() {
+            return None;
+        }
+
+        Some(str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn parse(str: &str, element_type: DataType) -> Option<List> {
+        List::parse(str, &element_type, FixedOffset::east_opt(0).unwrap())
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let ints = parse(" { 1, -2 ,3 } ", DataType::Int32).unwrap();
+        assert_eq!(ints.element_type(), &DataType::Int64);
+        assert_eq!(
+            ints.elements(),
+            &[Data::Int64(1), Data::Int64(-2), Data::Int64(3)]
+        );
+        assert_eq!(ints.to_string(), "{1,-2,3}");
+
+        let strs =
+            parse(r#"{a, "b c", "", "q\"", d\,e, "null"}"#, DataType::String)
+                .unwrap();
+        assert_eq!(strs.to_string(), r#"{a,"b c","","q\"","d,e","null"}"#);
+        assert_eq!(parse(&strs.to_string(), DataType::String).unwrap(), strs);
+
+        let nested = DataType::List(Box::new(DataType::Int64));
+        let matrix = parse("{{1,2},{},{3}}", nested).unwrap();
+        assert_eq!(matrix.to_string(), "{{1,2},{},{3}}");
+
+        assert_eq!(parse("{}", DataType::Bool).unwrap().to_string(), "{}");
+        for str in ["", "{", "{1,}", "{,1}", "{1} 2", "{a}", "{NULL}", "1"] {
+            assert!(parse(str, DataType::Int64).is_none(), "{str}");
+        }
+        assert!(parse("{32768}", DataType::Int16).is_none());
+    }
+
+    #[test]
+    fn encoding() {
+        let nested = DataType::List(Box::new(DataType::String));
+        let list = parse(r#"{{a,"b c"},{}}"#, nested.clone()).unwrap();
+        let mut buf = Vec::new();
+        list.encode(&mut buf);
+        assert_eq!(buf.len(), list.encode_size());
+        assert_eq!(List::decode(&buf, &nested), list);
+    }
+}
@@ -1,2 +1,10 @@
+pub mod binary;
+pub mod collation;
+pub mod datetime;
+pub mod decimal;
+pub mod enum_type;
+pub mod json;
+pub mod list;
 pub mod tuple;
 pub mod types;
+pub mod uuid;
@@ -1,5 +1,5 @@
 use super::types::{Data, DataType};
-use crate::catalog::schema::Schema;
+use crate::{catalog::schema::Schema, error::Result};
 use bytes::BufMut;
 use std::fmt::{Display, Formatter};
 
@@ -85,8 +85,9 @@ impl Tuple {
     }
 }
 
-/// An iterator over [`Tuple`]s.
-pub type TupleStream = Box<dyn Iterator<Item = Tuple>>;
+/// An iterator over [`Tuple`]s, producing a row could fail, e.g., on
+/// overflow, then an error is yielded instead.
+pub type TupleStream = Box<dyn Iterator<Item = Result<Tuple>>>;
 
 #[cfg(test)]
 mod tests {
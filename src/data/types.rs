@@ -1,26 +1,129 @@
 //! Data types
 
-use crate::as_variant;
+use super::{
+    binary::{display_binary, parse_binary},
+    datetime::{
+        display_date, display_time, display_timestamp, display_timestamptz,
+        parse_date, parse_interval, parse_time, parse_timestamp,
+        parse_timestamptz, Interval,
+    },
+    decimal::{Decimal, MAX_PRECISION},
+    enum_type::{EnumType, EnumValue},
+    json::Json,
+    list::List,
+    uuid::Uuid,
+};
 use bytes::{Buf, BufMut};
+use chrono::FixedOffset;
 use derive_more::Display;
-use std::{
-    fmt::Formatter,
-    ops::{Add, Sub},
-};
+use std::{fmt::Formatter, sync::Arc};
 
 /// Data types.
-#[derive(Debug, PartialEq, Copy, Clone, Eq)]
+#[derive(Debug, PartialEq, Clone, Eq)]
 pub enum DataType {
     Bool,
+    /// Stored as an `INT64` with a range check.
+    Int16,
+    /// Stored as an `INT64` with a range check.
+    Int32,
     Int64,
+    /// Stored as a `FLOAT64` with a range check.
+    Float32,
     Float64,
-    // Timestamp since UNIX Epoch, in seconds.
+    /// Exact numbers of at most `precision` digits, `scale` of them are after
+    /// the decimal point.
+    Decimal {
+        precision: u8,
+        scale: u8,
+    },
+    // Timestamp since UNIX Epoch, in microseconds.
     Timestamp,
+    // Timestamp in UTC, displayed in the session time zone.
+    TimestampTz,
+    Date,
+    Time,
+    Interval,
     String,
+    /// Strings of exactly `length` characters, shorter ones are padded with
+    /// spaces.
+    Char {
+        length: u32,
+    },
+    /// Strings of at most `length` characters.
+    Varchar {
+        length: u32,
+    },
+    /// Binary strings, i.e., sequences of bytes.
+    Binary,
+    /// JSON values, `JSON` and `JSONB` are the same type.
+    Json,
+    Uuid,
+    /// Arrays of values of the element type, e.g., `INT64[]`.
+    List(Box<DataType>),
+    /// User-defined enum types, displayed as their names.
+    Enum(Arc<EnumType>),
+}
+
+impl DataType {
+    /// Return the type of the values of a column of this type, which is the
+    /// type used in expressions.
+    ///
+    /// `INT16`, `INT32`, `FLOAT32`, `CHAR(n)` and `VARCHAR(n)` only restrict
+    /// what can be stored, their values are `INT64`, `FLOAT64` and `STRING`.
+    pub fn value_type(&self) -> DataType {
+        match self {
+            DataType::Int16 | DataType::Int32 => DataType::Int64,
+            DataType::Float32 => DataType::Float64,
+            DataType::Char { .. } | DataType::Varchar { .. } => {
+                DataType::String
+            }
+            DataType::List(element) => {
+                DataType::List(Box::new(element.value_type()))
+            }
+            datatype => datatype.clone(),
+        }
+    }
+
+    /// Return true if this is `CHAR(n)`, `VARCHAR(n)` or `STRING`.
+    pub fn is_string(&self) -> bool {
+        self.value_type() == DataType::String
+    }
+
+    /// Return true if this is `INT64`, `FLOAT64` or `DECIMAL`.
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            DataType::Int64 | DataType::Float64 | DataType::Decimal { .. }
+        )
+    }
+
+    /// Return true if this is a date/time type, including `INTERVAL`.
+    pub fn is_datetime(&self) -> bool {
+        matches!(
+            self,
+            DataType::Timestamp
+                | DataType::TimestampTz
+                | DataType::Date
+                | DataType::Time
+                | DataType::Interval
+        )
+    }
 }
 
 impl std::fmt::Display for DataType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataType::Decimal { precision, scale } => {
+                return write!(f, "DECIMAL({precision},{scale})")
+            }
+            DataType::Char { length } => return write!(f, "CHAR({length})"),
+            DataType::Varchar { length } => {
+                return write!(f, "VARCHAR({length})")
+            }
+            DataType::List(element) => return write!(f, "{element}[]"),
+            DataType::Enum(enum_type) => return f.write_str(enum_type.name()),
+            _ => {}
+        }
         let debug = format!("{:?}", self);
         f.write_str(&debug.to_uppercase())?;
         Ok(())
@@ -33,11 +136,52 @@ pub enum Data {
     Bool(bool),
     Int64(i64),
     Float64(f64),
-    /// Timestamp since UNIX Epoch, in seconds.
+    /// Stored as an `i128` whose bytes are ordered as the numbers, the scale
+    /// comes from the [`DataType`].
+    Decimal(Decimal),
+    /// Timestamp since UNIX Epoch, in microseconds.
     ///
     /// Stored as an `i64`.
+    #[display(fmt = "{}", "display_timestamp(*_0)")]
     Timestamp(i64),
+    /// Microseconds since UNIX Epoch in UTC, use [`Data::to_string_in()`] to
+    /// display it in another time zone.
+    ///
+    /// Stored as an `i64`.
+    #[display(
+        fmt = "{}",
+        "display_timestamptz(*_0, FixedOffset::east_opt(0).unwrap())"
+    )]
+    TimestampTz(i64),
+    /// Days since UNIX Epoch.
+    ///
+    /// Stored as an `i32`.
+    #[display(fmt = "{}", "display_date(*_0)")]
+    Date(i32),
+    /// Microseconds since midnight.
+    ///
+    /// Stored as an `i64`.
+    #[display(fmt = "{}", "display_time(*_0)")]
+    Time(i64),
+    Interval(Interval),
     String(String),
+    /// Displayed in the hex format, e.g., `\xdeadbeef`.
+    ///
+    /// Stored as its length (`u64`) followed by the bytes.
+    #[display(fmt = "{}", "display_binary(_0)")]
+    Binary(Vec<u8>),
+    /// Stored in a compact binary form, see [`Json::encode()`].
+    Json(Json),
+    /// Stored as its 16 bytes, which are ordered as the UUIDs.
+    Uuid(Uuid),
+    /// Displayed like `{1,2,3}`.
+    ///
+    /// Stored as the number of elements (`u64`) followed by the elements.
+    List(List),
+    /// Displayed as its label.
+    ///
+    /// Stored as its ordinal (`u32`), see [`EnumValue::encode()`].
+    Enum(EnumValue),
 }
 
 /// Encoded data, it will be `Borrowed` when allocation is not needed.
@@ -63,8 +207,19 @@ impl Data {
             Data::Bool(_) => std::mem::size_of::<u8>(),
             Data::Int64(_) => std::mem::size_of::<i64>(),
             Data::Float64(_) => std::mem::size_of::<f64>(),
-            Data::Timestamp(_) => std::mem::size_of::<i64>(),
+            Data::Decimal(_) => Decimal::ENCODE_SIZE,
+            Data::Timestamp(_) | Data::TimestampTz(_) => {
+                std::mem::size_of::<i64>()
+            }
+            Data::Date(_) => std::mem::size_of::<i32>(),
+            Data::Time(_) => std::mem::size_of::<i64>(),
+            Data::Interval(_) => Interval::ENCODE_SIZE,
             Data::String(raw) => std::mem::size_of::<u64>() + raw.len(),
+            Data::Binary(raw) => std::mem::size_of::<u64>() + raw.len(),
+            Data::Json(json) => json.encode_size(),
+            Data::Uuid(_) => Uuid::ENCODE_SIZE,
+            Data::List(list) => list.encode_size(),
+            Data::Enum(_) => EnumValue::ENCODE_SIZE,
         }
     }
 
@@ -83,10 +238,20 @@ impl Data {
                 let bytes: &[u8; 8] = bytemuck::cast_ref(raw);
                 DataEncoded::Borrowed(bytes.as_slice())
             }
-            Data::Timestamp(raw) => {
+            Data::Decimal(raw) => DataEncoded::Owned(Box::new(raw.encode())),
+            Data::Timestamp(raw) | Data::TimestampTz(raw) => {
                 let bytes: &[u8; 8] = bytemuck::cast_ref(raw);
                 DataEncoded::Borrowed(bytes.as_slice())
             }
+            Data::Date(raw) => {
+                let bytes: &[u8; 4] = bytemuck::cast_ref(raw);
+                DataEncoded::Borrowed(bytes.as_slice())
+            }
+            Data::Time(raw) => {
+                let bytes: &[u8; 8] = bytemuck::cast_ref(raw);
+                DataEncoded::Borrowed(bytes.as_slice())
+            }
+            Data::Interval(raw) => DataEncoded::Owned(Box::new(raw.encode())),
             Data::String(raw) => {
                 let mut buf = Vec::new();
                 // string is var-len, so wew store a length before the actual data
@@ -98,6 +263,28 @@ impl Data {
                 buf.put_slice(raw.as_bytes());
                 DataEncoded::Owned(buf.into_boxed_slice())
             }
+            Data::Binary(raw) => {
+                let mut buf = Vec::with_capacity(self.encode_size());
+                buf.put_u64_ne(
+                    raw.len()
+                        .try_into()
+                        .expect("should never fail on a 64-bit machine"),
+                );
+                buf.put_slice(raw);
+                DataEncoded::Owned(buf.into_boxed_slice())
+            }
+            Data::Json(json) => {
+                let mut buf = Vec::with_capacity(json.encode_size());
+                json.encode(&mut buf);
+                DataEncoded::Owned(buf.into_boxed_slice())
+            }
+            Data::Uuid(uuid) => DataEncoded::Borrowed(uuid.as_bytes()),
+            Data::List(list) => {
+                let mut buf = Vec::with_capacity(list.encode_size());
+                list.encode(&mut buf);
+                DataEncoded::Owned(buf.into_boxed_slice())
+            }
+            Data::Enum(value) => DataEncoded::Owned(Box::new(value.encode())),
         }
     }
 
@@ -109,10 +296,23 @@ impl Data {
         let mut buf = buf.as_ref();
         match ty {
             DataType::Bool => Self::Bool(buf.get_u8() == 1),
-            DataType::Int64 => Self::Int64(buf.get_i64_ne()),
-            DataType::Float64 => Self::Float64(buf.get_f64_ne()),
+            DataType::Int16 | DataType::Int32 | DataType::Int64 => {
+                Self::Int64(buf.get_i64_ne())
+            }
+            DataType::Float32 | DataType::Float64 => {
+                Self::Float64(buf.get_f64_ne())
+            }
+            DataType::Decimal { scale, .. } => {
+                Self::Decimal(Decimal::decode(buf, *scale))
+            }
             DataType::Timestamp => Self::Timestamp(buf.get_i64_ne()),
-            DataType::String => {
+            DataType::TimestampTz => Self::TimestampTz(buf.get_i64_ne()),
+            DataType::Date => Self::Date(buf.get_i32_ne()),
+            DataType::Time => Self::Time(buf.get_i64_ne()),
+            DataType::Interval => Self::Interval(Interval::decode(buf)),
+            DataType::String
+            | DataType::Char { .. }
+            | DataType::Varchar { .. } => {
                 let len: usize = buf
                     .get_u64_ne()
                     .try_into()
@@ -124,6 +324,26 @@ impl Data {
 
                 Self::String(str)
             }
+            DataType::Binary => {
+                let len: usize = buf
+                    .get_u64_ne()
+                    .try_into()
+                    .expect("should never fail on a 64-bit machine");
+                let mut dst = vec![0; len];
+                buf.copy_to_slice(dst.as_mut_slice());
+
+                Self::Binary(dst)
+            }
+            DataType::Json => Self::Json(Json::decode(&mut buf)),
+            DataType::Uuid => Self::Uuid(Uuid::from_bytes(
+                buf[..Uuid::ENCODE_SIZE]
+                    .try_into()
+                    .expect("slice has the correct length"),
+            )),
+            DataType::List(element) => Self::List(List::decode(buf, element)),
+            DataType::Enum(enum_type) => {
+                Self::Enum(enum_type.value_of_ordinal(buf.get_u32()))
+            }
         }
     }
 
@@ -133,59 +353,183 @@ impl Data {
             Data::Bool(_) => DataType::Bool,
             Data::Int64(_) => DataType::Int64,
             Data::Float64(_) => DataType::Float64,
+            // The precision of a value is unknown, the maximum one is used.
+            Data::Decimal(decimal) => DataType::Decimal {
+                precision: MAX_PRECISION,
+                scale: decimal.scale(),
+            },
             Data::String(_) => DataType::String,
             Data::Timestamp(_) => DataType::Timestamp,
+            Data::TimestampTz(_) => DataType::TimestampTz,
+            Data::Date(_) => DataType::Date,
+            Data::Time(_) => DataType::Time,
+            Data::Interval(_) => DataType::Interval,
+            Data::Binary(_) => DataType::Binary,
+            Data::Json(_) => DataType::Json,
+            Data::Uuid(_) => DataType::Uuid,
+            Data::List(list) => {
+                DataType::List(Box::new(list.element_type().clone()))
+            }
+            Data::Enum(value) => DataType::Enum(value.enum_type().clone()),
         }
     }
-}
 
-impl Add for &Data {
-    type Output = Data;
-    fn add(self, rhs: Self) -> Self::Output {
-        match self {
-            Data::Int64(lhs) => {
-                let rhs = as_variant!(Data::Int64, rhs);
-                Data::Int64(lhs + rhs)
+    /// Parse `str` as a value of `datatype`, e.g., the string of a typed
+    /// literal `DATE '2024-01-01'`.
+    ///
+    /// `time_zone` is used by a `TIMESTAMPTZ` without a UTC offset, `None`
+    /// will be returned if `str` is not a valid value.
+    pub fn parse(
+        str: &str,
+        datatype: &DataType,
+        time_zone: FixedOffset,
+    ) -> Option<Self> {
+        if &datatype.value_type() != datatype {
+            return Self::parse(str, &datatype.value_type(), time_zone)?
+                .fit(datatype);
+        }
+
+        let data = match datatype {
+            DataType::Bool => Data::Bool(str.trim().parse().ok()?),
+            DataType::Int64 => Data::Int64(str.trim().parse().ok()?),
+            DataType::Float64 => Data::Float64(str.trim().parse().ok()?),
+            DataType::Decimal { precision, scale } => {
+                Data::Decimal(Decimal::parse(str)?.round(*precision, *scale)?)
+            }
+            DataType::Timestamp => Data::Timestamp(parse_timestamp(str)?),
+            DataType::TimestampTz => {
+                Data::TimestampTz(parse_timestamptz(str, time_zone)?)
             }
-            Data::Float64(lhs) => {
-                let rhs = as_variant!(Data::Float64, rhs);
-                Data::Float64(lhs + rhs)
+            DataType::Date => Data::Date(parse_date(str)?),
+            DataType::Time => Data::Time(parse_time(str)?),
+            DataType::Interval => Data::Interval(parse_interval(str)?),
+            DataType::String => Data::String(str.to_string()),
+            DataType::Binary => Data::Binary(parse_binary(str)?),
+            DataType::Json => Data::Json(Json::parse(str)?),
+            DataType::Uuid => Data::Uuid(Uuid::parse(str)?),
+            DataType::List(element) => {
+                Data::List(List::parse(str, element, time_zone)?)
             }
+            DataType::Enum(enum_type) => Data::Enum(enum_type.value(str)?),
+            DataType::Int16
+            | DataType::Int32
+            | DataType::Float32
+            | DataType::Char { .. }
+            | DataType::Varchar { .. } => unreachable!("handled above"),
+        };
 
-            _ => panic!("trying to do Add with {} and {}", self, rhs),
+        Some(data)
+    }
+
+    /// Fit a value of `datatype.value_type()` into `datatype`, `None` will be
+    /// returned if it is out of the range of `datatype`.
+    ///
+    /// Like PostgreSQL, a string that is too long can be truncated only if
+    /// the excess characters are spaces.
+    pub fn fit(self, datatype: &DataType) -> Option<Data> {
+        let fit_string = |str: String, length: u32, pad: bool| {
+            let length = length as usize;
+            let n_chars = str.chars().count();
+            if n_chars > length {
+                let (idx, _) = str.char_indices().nth(length).unwrap();
+                if !str[idx..].chars().all(|char| char == ' ') {
+                    return None;
+                }
+                return Some(Data::String(str[..idx].to_string()));
+            }
+            if pad {
+                return Some(Data::String(format!("{str:length$}")));
+            }
+
+            Some(Data::String(str))
+        };
+
+        match (self, datatype) {
+            (Data::Int64(int), DataType::Int16) => {
+                i16::try_from(int).ok().map(|_| Data::Int64(int))
+            }
+            (Data::Int64(int), DataType::Int32) => {
+                i32::try_from(int).ok().map(|_| Data::Int64(int))
+            }
+            (Data::Float64(float), DataType::Float32)
+                if float.is_finite() && float.abs() > f64::from(f32::MAX) =>
+            {
+                None
+            }
+            (Data::String(str), DataType::Char { length }) => {
+                fit_string(str, *length, true)
+            }
+            (Data::String(str), DataType::Varchar { length }) => {
+                fit_string(str, *length, false)
+            }
+            (Data::List(list), DataType::List(element)) => {
+                let elements = list
+                    .into_elements()
+                    .into_iter()
+                    .map(|data| data.fit(element))
+                    .collect::<Option<_>>()?;
+                Some(Data::List(List::new(element.value_type(), elements)))
+            }
+            (data, _) => Some(data),
         }
     }
-}
 
-impl Add for Data {
-    type Output = Data;
-    fn add(self, rhs: Self) -> Self::Output {
-        <&Data as Add>::add(&self, &rhs)
+    /// Convert a number or a string to `DECIMAL(precision, scale)`, rounding
+    /// it to `scale`, `None` will be returned if it does not fit.
+    pub fn to_decimal(&self, precision: u8, scale: u8) -> Option<Decimal> {
+        let decimal = match self {
+            Data::Int64(int) => Decimal::new(i128::from(*int), 0),
+            Data::Float64(float) => Decimal::from_f64(*float)?,
+            Data::Decimal(decimal) => *decimal,
+            Data::String(str) => Decimal::parse(str)?,
+            _ => return None,
+        };
+
+        decimal.round(precision, scale)
     }
-}
 
-impl Sub for &Data {
-    type Output = Data;
-    fn sub(self, rhs: Self) -> Self::Output {
-        match self {
-            Data::Int64(lhs) => {
-                let rhs = as_variant!(Data::Int64, rhs);
-                Data::Int64(lhs - rhs)
+    /// Return `self + rhs` of 2 numbers of the same type, `None` will be
+    /// returned on overflow.
+    pub fn checked_add(&self, rhs: &Data) -> Option<Data> {
+        match (self, rhs) {
+            (Data::Int64(lhs), Data::Int64(rhs)) => {
+                lhs.checked_add(*rhs).map(Data::Int64)
             }
-            Data::Float64(lhs) => {
-                let rhs = as_variant!(Data::Float64, rhs);
-                Data::Float64(lhs - rhs)
+            (Data::Float64(lhs), Data::Float64(rhs)) => {
+                Some(Data::Float64(lhs + rhs))
             }
+            (Data::Decimal(lhs), Data::Decimal(rhs)) => {
+                lhs.checked_add(rhs).map(Data::Decimal)
+            }
+            _ => panic!("trying to do Add with {} and {}", self, rhs),
+        }
+    }
 
+    /// Return `self - rhs` of 2 numbers of the same type, `None` will be
+    /// returned on overflow.
+    pub fn checked_sub(&self, rhs: &Data) -> Option<Data> {
+        match (self, rhs) {
+            (Data::Int64(lhs), Data::Int64(rhs)) => {
+                lhs.checked_sub(*rhs).map(Data::Int64)
+            }
+            (Data::Float64(lhs), Data::Float64(rhs)) => {
+                Some(Data::Float64(lhs - rhs))
+            }
+            (Data::Decimal(lhs), Data::Decimal(rhs)) => {
+                lhs.checked_sub(rhs).map(Data::Decimal)
+            }
             _ => panic!("trying to do Sub with {} and {}", self, rhs),
         }
     }
-}
 
-impl Sub for Data {
-    type Output = Data;
-    fn sub(self, rhs: Self) -> Self::Output {
-        <&Data as Sub>::sub(&self, &rhs)
+    /// Convert it to a string, `TIMESTAMPTZ` is displayed in `time_zone`.
+    pub fn to_string_in(&self, time_zone: FixedOffset) -> String {
+        match self {
+            Data::TimestampTz(micros) => {
+                display_timestamptz(*micros, time_zone)
+            }
+            data => data.to_string(),
+        }
     }
 }
 
@@ -224,6 +568,13 @@ mod tests {
             DataEncoded::Borrowed(raw.to_ne_bytes().as_slice())
         );
 
+        let raw = -3_i32;
+        let date = Data::Date(raw);
+        assert_eq!(
+            date.encode(),
+            DataEncoded::Borrowed(raw.to_ne_bytes().as_slice())
+        );
+
         let raw = String::from("VinylDB");
         let string = Data::String(raw.clone());
         let expected = {
@@ -245,18 +596,35 @@ mod tests {
 
     #[test]
     fn data_decode_works() {
-        let types = vec![
+        let types = [
             DataType::String,
             DataType::Int64,
             DataType::Bool,
+            DataType::Date,
             DataType::String,
+            DataType::Interval,
+            DataType::Time,
+            DataType::Decimal {
+                precision: 10,
+                scale: 2,
+            },
+            DataType::Binary,
+            DataType::Json,
+            DataType::Uuid,
             DataType::Bool,
         ];
         let data = vec![
             Data::String("Vinyl".into()),
             Data::Int64(0),
             Data::Bool(false),
+            Data::Date(19_000),
             Data::String("DB".into()),
+            Data::Interval(Interval::new(1, -2, 3)),
+            Data::Time(1),
+            Data::Decimal(Decimal::new(-12345, 2)),
+            Data::Binary(vec![0xde, 0xad]),
+            Data::Json(Json::parse(r#"{"a": [1, "b"]}"#).unwrap()),
+            Data::Uuid(Uuid::new_v4()),
             Data::Bool(true),
         ];
 
@@ -276,4 +644,39 @@ mod tests {
             start += res.encode_size();
         }
     }
+
+    #[test]
+    fn fit_checks_ranges_and_lengths() {
+        assert_eq!(
+            Data::Int64(-32768).fit(&DataType::Int16),
+            Some(Data::Int64(-32768))
+        );
+        assert_eq!(Data::Int64(32768).fit(&DataType::Int16), None);
+        assert_eq!(Data::Int64(1 << 31).fit(&DataType::Int32), None);
+        assert_eq!(Data::Float64(1e39).fit(&DataType::Float32), None);
+
+        let char3 = DataType::Char { length: 3 };
+        let varchar3 = DataType::Varchar { length: 3 };
+        let string = |str: &str| Data::String(str.to_string());
+        assert_eq!(string("a").fit(&char3), Some(string("a  ")));
+        assert_eq!(string("a").fit(&varchar3), Some(string("a")));
+        assert_eq!(string("äbc   ").fit(&varchar3), Some(string("äbc")));
+        assert_eq!(string("abcd").fit(&varchar3), None);
+        assert_eq!(
+            Data::parse(
+                "70000",
+                &DataType::Int32,
+                FixedOffset::east_opt(0).unwrap()
+            ),
+            Some(Data::Int64(70000))
+        );
+        assert_eq!(
+            Data::parse(
+                "70000",
+                &DataType::Int16,
+                FixedOffset::east_opt(0).unwrap()
+            ),
+            None
+        );
+    }
 }
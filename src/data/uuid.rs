@@ -0,0 +1,152 @@
+//! UUIDs, the values of `UUID`.
+
+use std::fmt::{Display, Formatter};
+
+/// A UUID, stored as its 16 bytes.
+///
+/// UUIDs are ordered by their bytes, which is also the order of their text
+/// forms, so the encoded bytes can be compared directly, e.g., as keys.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Uuid([u8; Uuid::ENCODE_SIZE]);
+
+impl Uuid {
+    /// How many bytes it will take after encoding.
+    pub const ENCODE_SIZE: usize = 16;
+
+    pub fn from_bytes(bytes: [u8; Self::ENCODE_SIZE]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; Self::ENCODE_SIZE] {
+        &self.0
+    }
+
+    /// Generate a random (version 4) UUID.
+    ///
+    /// The random bytes are taken from the OS, which is cryptographically
+    /// secure, so the UUIDs can not be predicted.
+    pub fn new_v4() -> Self {
+        let mut bytes = [0; Self::ENCODE_SIZE];
+        getrandom::getrandom(&mut bytes)
+            .expect("the OS random number generator should be available");
+        // version 4
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        // variant 1, i.e., RFC 4122
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+        Self(bytes)
+    }
+
+    /// Parse a UUID, `None` will be returned if it is invalid.
+    ///
+    /// Like PostgreSQL, besides the standard form like
+    /// `a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11`, upper-case digits, surrounding
+    /// braces, and hyphens after any group of 4 digits (or none at all) are
+    /// accepted.
+    pub fn parse(str: &str) -> Option<Self> {
+        let str = str.trim();
+        let str = match str.strip_prefix('{') {
+            Some(braced) => braced.strip_suffix('}')?,
+            None => str,
+        };
+
+        let mut bytes = [0; Self::ENCODE_SIZE];
+        let mut n_digits = 0;
+        let mut after_hyphen = false;
+        for char in str.chars() {
+            if char == '-' {
+                if n_digits == 0 || n_digits % 4 != 0 || after_hyphen {
+                    return None;
+                }
+                after_hyphen = true;
+                continue;
+            }
+            let digit = char.to_digit(16)? as u8;
+            let byte = bytes.get_mut(n_digits / 2)?;
+            *byte = *byte << 4 | digit;
+            n_digits += 1;
+            after_hyphen = false;
+        }
+        if n_digits != Self::ENCODE_SIZE * 2 || after_hyphen {
+            return None;
+        }
+
+        Some(Self(bytes))
+    }
+}
+
+impl Display for Uuid {
+    /// Display it in the standard form, e.g.,
+    /// `a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (idx, byte) in self.0.iter().enumerate() {
+            if matches!(idx, 4 | 6 | 8 | 10) {
+                write!(f, "-")?;
+            }
+            write!(f, "{byte:02x}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_and_display() {
+        let expected = "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11";
+        for str in [
+            expected,
+            "A0EEBC99-9C0B-4EF8-BB6D-6BB9BD380A11",
+            "{a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11}",
+            "a0eebc999c0b4ef8bb6d6bb9bd380a11",
+            "a0ee-bc99-9c0b-4ef8-bb6d-6bb9-bd38-0a11",
+        ] {
+            assert_eq!(Uuid::parse(str).unwrap().to_string(), expected);
+        }
+
+        for str in [
+            "",
+            "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a1",
+            "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a111",
+            "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a1g",
+            "-a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11",
+            "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11-",
+            "a0eebc99--9c0b-4ef8-bb6d-6bb9bd380a11",
+            "a0eeb-c99-9c0b-4ef8-bb6d-6bb9bd380a11",
+            "{a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11",
+        ] {
+            assert!(Uuid::parse(str).is_none(), "{str}");
+        }
+    }
+
+    #[test]
+    fn random_uuids() {
+        let uuid = Uuid::new_v4();
+        assert_eq!(uuid.as_bytes()[6] >> 4, 4);
+        assert_eq!(uuid.as_bytes()[8] >> 6, 0b10);
+        assert_ne!(uuid, Uuid::new_v4());
+
+        let str = uuid.to_string();
+        assert_eq!(str.chars().nth(14), Some('4'));
+        assert_eq!(Uuid::parse(&str), Some(uuid));
+    }
+
+    #[test]
+    fn byte_order_is_text_order() {
+        let mut uuids = [
+            "ffffffff-0000-0000-0000-000000000000",
+            "00000000-0000-0000-0000-000000000001",
+            "0a000000-0000-0000-0000-000000000000",
+        ]
+        .map(|str| Uuid::parse(str).unwrap());
+        uuids.sort();
+        let strs = uuids.map(|uuid| uuid.to_string());
+        let mut sorted = strs.clone();
+        sorted.sort();
+        assert_eq!(strs, sorted);
+    }
+}
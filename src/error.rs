@@ -1,6 +1,7 @@
 use crate::{
     catalog::error::CatalogError, meta_cmd::MetaCmdError,
-    plan::error::PlanError,
+    migration::MigrationError, plan::error::PlanError, sequence::SequenceError,
+    transaction::TransactionError,
 };
 use derive_more::{Display, Error, From};
 use sled::Error as SledError;
@@ -13,6 +14,9 @@ pub enum Error {
     PlanError(PlanError),
     CatalogError(CatalogError),
     MetaCmdError(MetaCmdError),
+    MigrationError(MigrationError),
+    TransactionError(TransactionError),
+    SequenceError(SequenceError),
     SledError(SledError),
     IoError(IoError),
 }
@@ -3,43 +3,329 @@
 use crate::{
     catalog::schema::Schema,
     data::{
+        collation::Collation,
+        datetime,
+        decimal::{self, Decimal},
+        enum_type::EnumValue,
         tuple::Tuple,
         types::{Data, DataType},
     },
     error::{Error, Result},
+    function::{
+        aggregate::AggregateExpr, scalar::ScalarFunctionExpr,
+        window::WindowExpr,
+    },
+    pattern::{Pattern, PatternKind},
     plan::error::{ExprEvaluationError, PlanError},
 };
 use derive_more::Display;
+use std::{
+    cmp::Ordering,
+    fmt::{Display as StdDisplay, Formatter},
+};
 
+/// An expression.
+///
+/// Its `Display` impl produces SQL that can be parsed back to the same `Expr`,
+/// operands are parenthesized where needed according to the precedence of
+/// operators.
 #[derive(Debug, Clone, Display)]
 pub enum Expr {
+    #[display(fmt = "{}", "display_ident(_0)")]
     /// A named column
     Column(String),
+    #[display(fmt = "{}", "display_literal(_0)")]
     /// A literal value
     Literal(Data),
-    #[display(fmt = "{} {} {}", left, op, right)]
+    #[display(
+        fmt = "{} {} {}",
+        "Operand::left(left, op.precedence())",
+        op,
+        "Operand::right(right, op.precedence())"
+    )]
     /// Binary operation.
     BinaryExpr {
         left: Box<Expr>,
         op: Operator,
         right: Box<Expr>,
     },
+    #[display(
+        fmt = "{} {} {}",
+        "Operand::left(expr, self.precedence())",
+        "pattern.kind().operator(*negated)",
+        pattern
+    )]
+    /// Pattern matching, e.g. `a LIKE 'foo%'` or `a ~ '^foo'`.
+    Match {
+        expr: Box<Expr>,
+        pattern: Pattern,
+        negated: bool,
+    },
+    #[display(
+        fmt = "{} {}BETWEEN {} AND {}",
+        "Operand::left(expr, BETWEEN_PRECEDENCE)",
+        r#"if *negated { "NOT " } else { "" }"#,
+        "Operand::right(low, BETWEEN_PRECEDENCE)",
+        "Operand::right(high, BETWEEN_PRECEDENCE)"
+    )]
+    /// `expr [NOT] BETWEEN low AND high`, both bounds are inclusive.
+    Between {
+        expr: Box<Expr>,
+        negated: bool,
+        low: Box<Expr>,
+        high: Box<Expr>,
+    },
+    #[display(
+        fmt = "{} {}IN ({})",
+        "Operand::left(expr, BETWEEN_PRECEDENCE)",
+        r#"if *negated { "NOT " } else { "" }"#,
+        "display_comma_separated(list)"
+    )]
+    /// `expr [NOT] IN (list)`.
+    InList {
+        expr: Box<Expr>,
+        list: Vec<Expr>,
+        negated: bool,
+    },
+    #[display(
+        fmt = "{} {} ANY({})",
+        "Operand::left(left, COMPARISON_PRECEDENCE)",
+        op,
+        right
+    )]
+    /// `left op ANY(right)`, which is true if comparing `left` with any
+    /// element of the array `right` by `op` is true.
+    Any {
+        left: Box<Expr>,
+        op: Operator,
+        right: Box<Expr>,
+    },
+    #[display(
+        fmt = "{} COLLATE {}",
+        "Operand::right(expr, COLLATE_PRECEDENCE)",
+        collation
+    )]
+    /// `expr COLLATE collation`, which compares the string `expr` under
+    /// `collation` rather than the one of its column.
+    Collate {
+        expr: Box<Expr>,
+        collation: Collation,
+    },
+    #[display(fmt = "{}", "display_case(operand, when_then, else_expr)")]
+    /// `CASE [operand] WHEN .. THEN .. ELSE .. END`.
+    ///
+    /// If `operand` is present, the `WHEN` exprs are compared with it,
+    /// otherwise they are boolean conditions.
+    ///
+    /// `ELSE` is mandatory as its absence implies NULL, which is not supported.
+    Case {
+        operand: Option<Box<Expr>>,
+        when_then: Vec<(Expr, Expr)>,
+        else_expr: Box<Expr>,
+    },
+    /// Scalar function call.
+    ScalarFunction(ScalarFunctionExpr),
+    /// Aggregate function call, it can only be evaluated by an aggregation.
+    Aggregate(AggregateExpr),
+    /// Window function call, it can only be evaluated by a window operator.
+    Window(Box<WindowExpr>),
 }
 
 impl Expr {
+    /// Return the direct children of this `Expr`.
+    pub fn children(&self) -> Vec<&Expr> {
+        match self {
+            Expr::Column(_) | Expr::Literal(_) => Vec::new(),
+            Expr::BinaryExpr { left, right, .. }
+            | Expr::Any { left, right, .. } => {
+                vec![left, right]
+            }
+            Expr::Match { expr, .. } | Expr::Collate { expr, .. } => {
+                vec![expr]
+            }
+            Expr::Between {
+                expr, low, high, ..
+            } => vec![expr, low, high],
+            Expr::InList { expr, list, .. } => {
+                std::iter::once(expr.as_ref()).chain(list).collect()
+            }
+            Expr::Case {
+                operand,
+                when_then,
+                else_expr,
+            } => operand
+                .iter()
+                .map(|operand| operand.as_ref())
+                .chain(when_then.iter().flat_map(|(when, then)| [when, then]))
+                .chain(std::iter::once(else_expr.as_ref()))
+                .collect(),
+            Expr::ScalarFunction(func) => func.args.iter().collect(),
+            Expr::Aggregate(aggr) => aggr.args.iter().collect(),
+            Expr::Window(window) => window
+                .aggr
+                .args
+                .iter()
+                .chain(window.partition_by.iter())
+                .chain(window.order_by.iter().map(|sort| &sort.expr))
+                .collect(),
+        }
+    }
+
+    /// Rebuild this `Expr` by applying `f` to its direct children.
+    pub fn map_children<F>(self, mut f: F) -> Result<Expr>
+    where
+        F: FnMut(Expr) -> Result<Expr>,
+    {
+        let expr = match self {
+            Expr::Column(_) | Expr::Literal(_) => self,
+            Expr::BinaryExpr { left, op, right } => Expr::BinaryExpr {
+                left: Box::new(f(Box::into_inner(left))?),
+                op,
+                right: Box::new(f(Box::into_inner(right))?),
+            },
+            Expr::Match {
+                expr,
+                pattern,
+                negated,
+            } => Expr::Match {
+                expr: Box::new(f(Box::into_inner(expr))?),
+                pattern,
+                negated,
+            },
+            Expr::Collate { expr, collation } => Expr::Collate {
+                expr: Box::new(f(Box::into_inner(expr))?),
+                collation,
+            },
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => Expr::Between {
+                expr: Box::new(f(Box::into_inner(expr))?),
+                negated,
+                low: Box::new(f(Box::into_inner(low))?),
+                high: Box::new(f(Box::into_inner(high))?),
+            },
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => Expr::InList {
+                expr: Box::new(f(Box::into_inner(expr))?),
+                list: list.into_iter().map(&mut f).collect::<Result<_>>()?,
+                negated,
+            },
+            Expr::Any { left, op, right } => Expr::Any {
+                left: Box::new(f(Box::into_inner(left))?),
+                op,
+                right: Box::new(f(Box::into_inner(right))?),
+            },
+            Expr::Case {
+                operand,
+                when_then,
+                else_expr,
+            } => Expr::Case {
+                operand: operand
+                    .map(|operand| f(Box::into_inner(operand)).map(Box::new))
+                    .transpose()?,
+                when_then: when_then
+                    .into_iter()
+                    .map(|(when, then)| Ok((f(when)?, f(then)?)))
+                    .collect::<Result<_>>()?,
+                else_expr: Box::new(f(Box::into_inner(else_expr))?),
+            },
+            Expr::ScalarFunction(mut func) => {
+                func.args =
+                    func.args.into_iter().map(&mut f).collect::<Result<_>>()?;
+                Expr::ScalarFunction(func)
+            }
+            Expr::Aggregate(mut aggr) => {
+                aggr.args =
+                    aggr.args.into_iter().map(&mut f).collect::<Result<_>>()?;
+                Expr::Aggregate(aggr)
+            }
+            Expr::Window(mut window) => {
+                window.aggr.args = std::mem::take(&mut window.aggr.args)
+                    .into_iter()
+                    .map(&mut f)
+                    .collect::<Result<_>>()?;
+                window.partition_by = std::mem::take(&mut window.partition_by)
+                    .into_iter()
+                    .map(&mut f)
+                    .collect::<Result<_>>()?;
+                window.order_by = std::mem::take(&mut window.order_by)
+                    .into_iter()
+                    .map(|sort| {
+                        Ok(SortExpr {
+                            expr: f(sort.expr)?,
+                            asc: sort.asc,
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                Expr::Window(window)
+            }
+        };
+
+        Ok(expr)
+    }
+
+    /// Return true if this `Expr` contains an aggregate function call that is
+    /// not part of a window function.
+    pub fn contains_aggregate(&self) -> bool {
+        match self {
+            Expr::Aggregate(_) => true,
+            Expr::Window(_) => false,
+            expr => expr.children().iter().any(|c| c.contains_aggregate()),
+        }
+    }
+
+    /// Return true if this `Expr` contains a window function call.
+    pub fn contains_window(&self) -> bool {
+        matches!(self, Expr::Window(_))
+            || self.children().iter().any(|c| c.contains_window())
+    }
+
+    /// Return true if this `Expr` calls a volatile function, see
+    /// [`crate::function::scalar::ScalarImpl::is_volatile()`].
+    pub fn is_volatile(&self) -> bool {
+        matches!(
+            self,
+            Expr::ScalarFunction(call) if call.func.implementation().is_volatile()
+        ) || self.children().iter().any(|c| c.is_volatile())
+    }
+
     /// Figuring out if this `Expr` is a constant, or can be evaluated as a constant.
     ///
     /// An `Expr` is a constant as long as it does not involve `Column`s
     pub fn is_constant(&self) -> bool {
         match self {
             Expr::Literal(_) => true,
-            // TODO: handle the cases where short-circuit may happen
-            //
-            // I think we can have short-circuit here, say op is `Operator::Or`,
-            // then left is a constant and can be evaluated to `Expr::Literal(true)`,
-            // then `true Or anything` will be true, i.e., a constant.
-            Expr::BinaryExpr { left, right, .. } => {
-                left.is_constant() && right.is_constant()
+            // `false AND x` and `true OR x` are constants even if `x` is not,
+            // as we don't have NULL, `x` can be on either side.
+            Expr::BinaryExpr { left, op, right } => {
+                let (lhs, rhs) = (left.is_constant(), right.is_constant());
+                (lhs && rhs)
+                    || op.short_circuit_value().is_some_and(|value| {
+                        [(left, lhs), (right, rhs)].into_iter().any(
+                            |(operand, is_constant)| {
+                                is_constant
+                                    && operand
+                                        .evaluate_constant_expr()
+                                        .is_ok_and(|data| data == value)
+                            },
+                        )
+                    })
+            }
+            Expr::Match { expr, .. } | Expr::Collate { expr, .. } => {
+                expr.is_constant()
+            }
+            Expr::Between { .. }
+            | Expr::InList { .. }
+            | Expr::Any { .. }
+            | Expr::Case { .. }
+            | Expr::ScalarFunction(_) => {
+                self.children().iter().all(|c| c.is_constant())
             }
 
             _ => false,
@@ -58,10 +344,36 @@ impl Expr {
             }
             Expr::Literal(literal) => Ok(literal.clone()),
             Expr::BinaryExpr { left, op, right } => {
-                let left = left.evaluate(schema, data)?;
-                let right = right.evaluate(schema, data)?;
+                let comparison = self.comparison_of_operands(schema)?;
+                let left = comparison.apply(left.evaluate(schema, data)?);
+                let right = comparison.apply(right.evaluate(schema, data)?);
                 op.operate(left, right)
             }
+            Expr::Match {
+                expr,
+                pattern,
+                negated,
+            } => {
+                let data = expr.evaluate(schema, data)?;
+                match_pattern(pattern, *negated, data)
+            }
+            Expr::Collate { expr, .. } => expr.evaluate(schema, data),
+            Expr::Between { .. }
+            | Expr::InList { .. }
+            | Expr::Any { .. }
+            | Expr::Case { .. } => self.evaluate_with(
+                |expr| expr.evaluate(schema, data),
+                || self.datatype(schema),
+                self.comparison_of_operands(schema)?,
+            ),
+            Expr::ScalarFunction(func) => {
+                func.evaluate_with(|arg| arg.evaluate(schema, data))
+            }
+            Expr::Aggregate(_) | Expr::Window(_) => {
+                Err(Error::PlanError(PlanError::ExprEvaluationError(
+                    ExprEvaluationError::NotScalar { expr: self.clone() },
+                )))
+            }
         }
     }
 
@@ -86,13 +398,48 @@ impl Expr {
         match self {
             Expr::Literal(data) => Ok(data.clone()),
             Expr::BinaryExpr { left, op, right } => {
-                let left = left.evaluate_constant_expr()?;
-                // TODO: handle short-circuit
-                let right = right.evaluate_constant_expr()?;
+                let left = left.evaluate_constant_expr();
+                let right = match op.short_circuit_value() {
+                    Some(value) if matches!(&left, Ok(data) if *data == value) => {
+                        return Ok(value)
+                    }
+                    Some(value) => {
+                        let right = right.evaluate_constant_expr();
+                        if matches!(&right, Ok(data) if *data == value) {
+                            return Ok(value);
+                        }
+                        right
+                    }
+                    None => right.evaluate_constant_expr(),
+                };
+                let comparison =
+                    self.comparison_of_operands(&Schema::empty())?;
+                let (left, right) =
+                    (comparison.apply(left?), comparison.apply(right?));
                 let data = op.operate(left, right)?;
 
                 Ok(data)
             }
+            Expr::Match {
+                expr,
+                pattern,
+                negated,
+            } => {
+                let data = expr.evaluate_constant_expr()?;
+                match_pattern(pattern, *negated, data)
+            }
+            Expr::Collate { expr, .. } => expr.evaluate_constant_expr(),
+            Expr::Between { .. }
+            | Expr::InList { .. }
+            | Expr::Any { .. }
+            | Expr::Case { .. } => self.evaluate_with(
+                Expr::evaluate_constant_expr,
+                || self.datatype_of_constant_expr(),
+                self.comparison_of_operands(&Schema::empty())?,
+            ),
+            Expr::ScalarFunction(func) => {
+                func.evaluate_with(Expr::evaluate_constant_expr)
+            }
             _ => Err(Error::PlanError(PlanError::ExprEvaluationError(
                 ExprEvaluationError::ExprIsNotConstant { expr: self.clone() },
             ))),
@@ -102,7 +449,9 @@ impl Expr {
     /// Return the datatype of this `Expr`.
     pub fn datatype(&self, schema: &Schema) -> Result<DataType> {
         match self {
-            Expr::Column(col_name) => Ok(*schema.column_datatype(col_name)?),
+            Expr::Column(col_name) => {
+                Ok(schema.column_datatype(col_name)?.value_type())
+            }
             Expr::Literal(data) => Ok(data.datatype()),
             Expr::BinaryExpr { left, op, right } => {
                 let left_datatype = left.datatype(schema)?;
@@ -110,8 +459,26 @@ impl Expr {
 
                 let dt =
                     op.datatype_of_operation(&left_datatype, &right_datatype)?;
+                check_enum_label(left, &left_datatype, right, &right_datatype)?;
+                self.collation_of_operands(schema)?;
                 Ok(dt)
             }
+            Expr::Match { expr, .. } => {
+                datatype_of_match(&expr.datatype(schema)?)
+            }
+            Expr::Collate { expr, .. } => {
+                datatype_of_collate(expr.datatype(schema)?)
+            }
+            Expr::Between { .. }
+            | Expr::InList { .. }
+            | Expr::Any { .. }
+            | Expr::Case { .. } => {
+                self.collation_of_operands(schema)?;
+                self.datatype_with(|expr| expr.datatype(schema))
+            }
+            Expr::ScalarFunction(func) => func.datatype(schema),
+            Expr::Aggregate(aggr) => aggr.datatype(schema),
+            Expr::Window(window) => window.datatype(schema),
         }
     }
 
@@ -127,8 +494,25 @@ impl Expr {
 
                 let dt =
                     op.datatype_of_operation(&left_datatype, &right_datatype)?;
+                self.collation_of_operands(&Schema::empty())?;
                 Ok(dt)
             }
+            Expr::Match { expr, .. } => {
+                datatype_of_match(&expr.datatype_of_constant_expr()?)
+            }
+            Expr::Collate { expr, .. } => {
+                datatype_of_collate(expr.datatype_of_constant_expr()?)
+            }
+            Expr::Between { .. }
+            | Expr::InList { .. }
+            | Expr::Any { .. }
+            | Expr::Case { .. } => {
+                self.collation_of_operands(&Schema::empty())?;
+                self.datatype_with(Expr::datatype_of_constant_expr)
+            }
+            Expr::ScalarFunction(func) => {
+                func.datatype_with(Expr::datatype_of_constant_expr)
+            }
 
             _ => Err(Error::PlanError(PlanError::ExprEvaluationError(
                 ExprEvaluationError::ExprIsNotConstant { expr: self.clone() },
@@ -137,6 +521,626 @@ impl Expr {
     }
 }
 
+impl Expr {
+    /// Evaluate `BETWEEN`, `IN`, `ANY` and `CASE`, whose children are
+    /// evaluated with `eval`.
+    ///
+    /// `datatype` is called to get the result type of `CASE` when a branch
+    /// needs to be coerced to it, strings are compared as per `comparison`.
+    fn evaluate_with<F, D>(
+        &self,
+        mut eval: F,
+        datatype: D,
+        comparison: Comparison,
+    ) -> Result<Data>
+    where
+        F: FnMut(&Expr) -> Result<Data>,
+        D: FnOnce() -> Result<DataType>,
+    {
+        match self {
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                let data = comparison.apply(eval(expr)?);
+                let ge_low = Operator::GtEq
+                    .operate(data.clone(), comparison.apply(eval(low)?))?;
+                let le_high = Operator::LtEq
+                    .operate(data, comparison.apply(eval(high)?))?;
+                let between = Operator::And.operate(ge_low, le_high)?;
+                Ok(Data::Bool(between == Data::Bool(!*negated)))
+            }
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                let data = comparison.apply(eval(expr)?);
+                for item in list {
+                    let item = comparison.apply(eval(item)?);
+                    let eq = Operator::Eq.operate(data.clone(), item)?;
+                    if eq == Data::Bool(true) {
+                        return Ok(Data::Bool(!*negated));
+                    }
+                }
+                Ok(Data::Bool(*negated))
+            }
+            Expr::Any { left, op, right } => {
+                let data = comparison.apply(eval(left)?);
+                let Data::List(list) = eval(right)? else {
+                    unreachable!("type has been checked")
+                };
+                for element in list.into_elements() {
+                    let element = comparison.apply(element);
+                    if op.operate(data.clone(), element)? == Data::Bool(true) {
+                        return Ok(Data::Bool(true));
+                    }
+                }
+                Ok(Data::Bool(false))
+            }
+            Expr::Case {
+                operand,
+                when_then,
+                else_expr,
+            } => {
+                let operand = operand
+                    .as_ref()
+                    .map(|o| eval(o).map(|data| comparison.apply(data)))
+                    .transpose()?;
+                let mut result = None;
+                for (when, then) in when_then {
+                    let when = eval(when)?;
+                    let matched = match &operand {
+                        Some(operand) => Operator::Eq
+                            .operate(operand.clone(), comparison.apply(when))?,
+                        None => when,
+                    };
+                    match matched {
+                        Data::Bool(true) => {
+                            result = Some(eval(then)?);
+                            break;
+                        }
+                        Data::Bool(false) => {}
+                        data => {
+                            return Err(Error::PlanError(
+                                PlanError::ExprEvaluationError(
+                                    ExprEvaluationError::NonBoolCaseCondition {
+                                        datatype: data.datatype(),
+                                    },
+                                ),
+                            ))
+                        }
+                    }
+                }
+                let result = match result {
+                    Some(result) => result,
+                    None => eval(else_expr)?,
+                };
+
+                // Branches of type INT64 are promoted if the result is FLOAT64.
+                match result {
+                    Data::Int64(int) if datatype()? == DataType::Float64 => {
+                        Ok(Data::Float64(int as f64))
+                    }
+                    result => Ok(result),
+                }
+            }
+            _ => {
+                unreachable!("evaluate_with() is only for BETWEEN/IN/ANY/CASE")
+            }
+        }
+    }
+
+    /// Return the datatype of `BETWEEN`, `IN`, `ANY` and `CASE`, the
+    /// datatypes of children are computed with `datatype`.
+    fn datatype_with<F>(&self, mut datatype: F) -> Result<DataType>
+    where
+        F: FnMut(&Expr) -> Result<DataType>,
+    {
+        match self {
+            Expr::Between {
+                expr, low, high, ..
+            } => {
+                let dt = datatype(expr)?;
+                for bound in [low, high] {
+                    let bound_dt = datatype(bound)?;
+                    Operator::GtEq.datatype_of_operation(&dt, &bound_dt)?;
+                    check_enum_label(expr, &dt, bound, &bound_dt)?;
+                }
+                Ok(DataType::Bool)
+            }
+            Expr::InList { expr, list, .. } => {
+                let dt = datatype(expr)?;
+                for item in list {
+                    let item_dt = datatype(item)?;
+                    Operator::Eq.datatype_of_operation(&dt, &item_dt)?;
+                    check_enum_label(expr, &dt, item, &item_dt)?;
+                }
+                Ok(DataType::Bool)
+            }
+            Expr::Any { left, op, right } => {
+                let dt = datatype(left)?;
+                let DataType::List(element_type) = datatype(right)? else {
+                    return Err(Error::PlanError(
+                        PlanError::ExprEvaluationError(
+                            ExprEvaluationError::AnyOfNonArray,
+                        ),
+                    ));
+                };
+                let bool = op.datatype_of_operation(&dt, &element_type)?;
+                check_enum_label(left, &dt, right, &element_type)?;
+                Ok(bool)
+            }
+            Expr::Case {
+                operand,
+                when_then,
+                else_expr,
+            } => {
+                let operand =
+                    operand.as_ref().map(|o| datatype(o)).transpose()?;
+                let mut result = datatype(else_expr)?;
+                for (when, then) in when_then {
+                    let when = datatype(when)?;
+                    match &operand {
+                        Some(operand) => {
+                            Operator::Eq
+                                .datatype_of_operation(operand, &when)?;
+                        }
+                        None if when != DataType::Bool => {
+                            return Err(Error::PlanError(
+                                PlanError::ExprEvaluationError(
+                                    ExprEvaluationError::NonBoolCaseCondition {
+                                        datatype: when,
+                                    },
+                                ),
+                            ));
+                        }
+                        None => {}
+                    }
+                    result = unify_case_types(result, datatype(then)?)?;
+                }
+
+                Ok(result)
+            }
+            _ => {
+                unreachable!("datatype_with() is only for BETWEEN/IN/ANY/CASE")
+            }
+        }
+    }
+}
+
+/// A string literal compared with an enum is taken as one of its labels, check
+/// it during planning so that the comparison won't fail during execution.
+fn check_enum_label(
+    lhs: &Expr,
+    lhs_dt: &DataType,
+    rhs: &Expr,
+    rhs_dt: &DataType,
+) -> Result<()> {
+    for (literal, datatype) in [(lhs, rhs_dt), (rhs, lhs_dt)] {
+        if let (Expr::Literal(Data::String(label)), DataType::Enum(enum_type)) =
+            (literal, datatype)
+        {
+            if enum_type.value(label).is_none() {
+                return Err(Error::PlanError(PlanError::ExprEvaluationError(
+                    ExprEvaluationError::InvalidEnumLabel {
+                        name: enum_type.name().to_string(),
+                        label: label.clone(),
+                    },
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Return the datatype of `expr COLLATE ..` whose `expr` is of `datatype`,
+/// only strings can be collated.
+fn datatype_of_collate(datatype: DataType) -> Result<DataType> {
+    if datatype != DataType::String {
+        return Err(Error::PlanError(PlanError::CollationOnNonString {
+            datatype,
+        }));
+    }
+
+    Ok(datatype)
+}
+
+/// The collation of an `Expr`, and whether it is specified by `COLLATE`
+/// explicitly or derived from a column implicitly.
+#[derive(Debug, Clone, Copy)]
+struct DerivedCollation {
+    collation: Collation,
+    explicit: bool,
+}
+
+impl DerivedCollation {
+    /// Combine the collations of 2 operands, an explicit collation overrides
+    /// an implicit one, while different collations of the same kind conflict.
+    fn combine(lhs: Option<Self>, rhs: Option<Self>) -> Result<Option<Self>> {
+        match (lhs, rhs) {
+            (None, derived) | (derived, None) => Ok(derived),
+            (Some(lhs), Some(rhs)) if lhs.explicit != rhs.explicit => {
+                Ok(Some(if lhs.explicit { lhs } else { rhs }))
+            }
+            (Some(lhs), Some(rhs)) if lhs.collation == rhs.collation => {
+                Ok(Some(lhs))
+            }
+            (Some(lhs), Some(rhs)) => {
+                Err(Error::PlanError(PlanError::ExprEvaluationError(
+                    ExprEvaluationError::CollationMismatch {
+                        lhs: lhs.collation,
+                        rhs: rhs.collation,
+                        explicit: lhs.explicit,
+                    },
+                )))
+            }
+        }
+    }
+}
+
+impl Expr {
+    /// Return the collation of this `Expr`, `None` if it has no collation,
+    /// e.g., a literal.
+    fn derived_collation(&self, schema: &Schema) -> Option<DerivedCollation> {
+        match self {
+            Expr::Column(name) => Some(DerivedCollation {
+                collation: schema.column_collation(name),
+                explicit: false,
+            }),
+            Expr::Collate { collation, .. } => Some(DerivedCollation {
+                collation: *collation,
+                explicit: true,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Return the collation of the strings this `Expr` evaluates to, which
+    /// decides how they are sorted and grouped.
+    pub fn collation(&self, schema: &Schema) -> Collation {
+        self.derived_collation(schema)
+            .map_or(Collation::Binary, |derived| derived.collation)
+    }
+
+    /// Return the operands compared by this `Expr`, e.g., `a` and `b` of
+    /// `a = b`, or nothing if it is not a comparison.
+    fn compared_operands(&self) -> Vec<&Expr> {
+        match self {
+            Expr::BinaryExpr { left, op, right } if op.is_comparison() => {
+                vec![left.as_ref(), right.as_ref()]
+            }
+            Expr::Between { .. } | Expr::InList { .. } | Expr::Any { .. } => {
+                self.children()
+            }
+            Expr::Case {
+                operand: Some(operand),
+                when_then,
+                ..
+            } => std::iter::once(operand.as_ref())
+                .chain(when_then.iter().map(|(when, _)| when))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Return the collation used by the comparisons of this `Expr`, e.g.,
+    /// `a = b` or `a IN (b, c)`, which is derived from the operands.
+    fn collation_of_operands(&self, schema: &Schema) -> Result<Collation> {
+        let mut derived = None;
+        for operand in self.compared_operands() {
+            derived = DerivedCollation::combine(
+                derived,
+                operand.derived_collation(schema),
+            )?;
+        }
+        Ok(derived.map_or(Collation::Binary, |derived| derived.collation))
+    }
+
+    /// Return true if this `Expr` is a `CHAR(n)` column, maybe with
+    /// `COLLATE`.
+    fn is_char_column(&self, schema: &Schema) -> bool {
+        match self {
+            Expr::Column(name) => {
+                schema.column_datatype(name).is_ok_and(|datatype| {
+                    matches!(datatype, DataType::Char { .. })
+                })
+            }
+            Expr::Collate { expr, .. } => expr.is_char_column(schema),
+            _ => false,
+        }
+    }
+
+    /// Return how the comparisons of this `Expr` compare strings, see
+    /// [`Comparison`].
+    fn comparison_of_operands(&self, schema: &Schema) -> Result<Comparison> {
+        Ok(Comparison {
+            collation: self.collation_of_operands(schema)?,
+            ignore_trailing_spaces: self
+                .compared_operands()
+                .iter()
+                .any(|operand| operand.is_char_column(schema)),
+        })
+    }
+}
+
+/// How strings are compared by a comparison, under the collation of its
+/// operands, and like PostgreSQL, trailing spaces are insignificant if any
+/// of them is a `CHAR(n)` column, whose values are padded with spaces, e.g.,
+/// `'ab '` of a `CHAR(3)` column equals `'ab'`.
+#[derive(Debug, Clone, Copy)]
+struct Comparison {
+    collation: Collation,
+    ignore_trailing_spaces: bool,
+}
+
+impl Comparison {
+    /// Replace `data` with the key it is compared by if it is a string.
+    fn apply(&self, data: Data) -> Data {
+        match data {
+            Data::String(str) if self.ignore_trailing_spaces => {
+                let trimmed = str.trim_end_matches(' ');
+                self.collation.apply(Data::String(trimmed.to_string()))
+            }
+            data => self.collation.apply(data),
+        }
+    }
+}
+
+/// Return the type that both `lhs` and `rhs` can be converted to, which is
+/// the result type of a `CASE` having branches of these types.
+fn unify_case_types(lhs: DataType, rhs: DataType) -> Result<DataType> {
+    match (lhs, rhs) {
+        (lhs, rhs) if lhs == rhs => Ok(lhs),
+        (DataType::Int64, DataType::Float64)
+        | (DataType::Float64, DataType::Int64) => Ok(DataType::Float64),
+        (lhs, rhs) => Err(Error::PlanError(PlanError::ExprEvaluationError(
+            ExprEvaluationError::MismatchedCaseTypes { lhs, rhs },
+        ))),
+    }
+}
+
+fn display_comma_separated(exprs: &[Expr]) -> String {
+    exprs
+        .iter()
+        .map(|expr| expr.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn display_case(
+    operand: &Option<Box<Expr>>,
+    when_then: &[(Expr, Expr)],
+    else_expr: &Expr,
+) -> String {
+    let mut str = String::from("CASE");
+    if let Some(operand) = operand {
+        str.push_str(&format!(" {operand}"));
+    }
+    for (when, then) in when_then {
+        str.push_str(&format!(" WHEN {when} THEN {then}"));
+    }
+    str.push_str(&format!(" ELSE {else_expr} END"));
+    str
+}
+
+/// Precedences of operators, they are the same as the ones used by the SQL
+/// parser so that a displayed `Expr` can be parsed back.
+const OR_PRECEDENCE: u8 = 5;
+const AND_PRECEDENCE: u8 = 10;
+const LIKE_PRECEDENCE: u8 = 19;
+const BETWEEN_PRECEDENCE: u8 = 20;
+const COMPARISON_PRECEDENCE: u8 = 20;
+const PLUS_MINUS_PRECEDENCE: u8 = 30;
+/// `COLLATE` can only be applied to an atom, see [`ATOM_PRECEDENCE`].
+const COLLATE_PRECEDENCE: u8 = 50;
+/// Precedence of `Expr`s that are not operations, e.g., columns, literals and
+/// function calls, they never need parentheses.
+const ATOM_PRECEDENCE: u8 = u8::MAX;
+
+impl Expr {
+    /// Return the precedence of the outermost operation of this `Expr`.
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::BinaryExpr { op, .. } => op.precedence(),
+            Expr::Match { pattern, .. } => match pattern.kind() {
+                PatternKind::Like
+                | PatternKind::ILike
+                | PatternKind::SimilarTo => LIKE_PRECEDENCE,
+                PatternKind::Regex | PatternKind::IRegex => {
+                    COMPARISON_PRECEDENCE
+                }
+            },
+            Expr::Between { .. } | Expr::InList { .. } => BETWEEN_PRECEDENCE,
+            Expr::Any { .. } => COMPARISON_PRECEDENCE,
+            Expr::Collate { .. } => COLLATE_PRECEDENCE,
+            Expr::Column(_)
+            | Expr::Literal(_)
+            | Expr::Case { .. }
+            | Expr::ScalarFunction(_)
+            | Expr::Aggregate(_)
+            | Expr::Window(_) => ATOM_PRECEDENCE,
+        }
+    }
+}
+
+/// An operand of an operator, which is parenthesized if it binds less tightly
+/// than the operator.
+struct Operand<'expr> {
+    expr: &'expr Expr,
+    parenthesized: bool,
+}
+
+impl<'expr> Operand<'expr> {
+    /// The left operand of an operator of `precedence`.
+    fn left(expr: &'expr Expr, precedence: u8) -> Self {
+        Self {
+            expr,
+            parenthesized: expr.precedence() < precedence,
+        }
+    }
+
+    /// The right operand of an operator of `precedence`.
+    ///
+    /// Operators are left-associative, so an operand of the same precedence
+    /// has to be parenthesized as well.
+    fn right(expr: &'expr Expr, precedence: u8) -> Self {
+        Self {
+            expr,
+            parenthesized: expr.precedence() <= precedence,
+        }
+    }
+}
+
+impl StdDisplay for Operand<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.parenthesized {
+            write!(f, "({})", self.expr)
+        } else {
+            write!(f, "{}", self.expr)
+        }
+    }
+}
+
+/// Keywords that are parsed specially at the beginning of an expression,
+/// columns named after them have to be quoted.
+const EXPR_KEYWORDS: &[&str] = &[
+    "ARRAY",
+    "CASE",
+    "CAST",
+    "CURRENT_CATALOG",
+    "CURRENT_DATE",
+    "CURRENT_TIME",
+    "CURRENT_TIMESTAMP",
+    "CURRENT_USER",
+    "EXISTS",
+    "EXTRACT",
+    "FALSE",
+    "INTERVAL",
+    "LOCALTIME",
+    "LOCALTIMESTAMP",
+    "NOT",
+    "NULL",
+    "SESSION_USER",
+    "TRUE",
+    "USER",
+];
+
+/// Display a column name, it will be double-quoted unless it is a plain
+/// identifier.
+fn display_ident(ident: &str) -> String {
+    let mut chars = ident.chars();
+    let plain = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !EXPR_KEYWORDS
+            .iter()
+            .any(|keyword| keyword.eq_ignore_ascii_case(ident));
+
+    if plain {
+        ident.to_string()
+    } else {
+        format!("\"{}\"", ident.replace('"', "\"\""))
+    }
+}
+
+/// Display `data` as an SQL literal.
+fn display_literal(data: &Data) -> String {
+    match data {
+        // `Debug` always keeps the fractional part so that it won't be parsed
+        // as an integer, e.g., `1.0` instead of `1`.
+        Data::Float64(float) => format!("{float:?}"),
+        Data::String(str) => format!("'{}'", str.replace('\'', "''")),
+        // a label, which is compared with enums as a value of the enum
+        Data::Enum(value) => format!("'{}'", value.label().replace('\'', "''")),
+        // a number with a fraction is parsed as a decimal of the same scale,
+        // e.g., `1.50`, while `DECIMAL '1'` won't be parsed as an integer
+        Data::Decimal(decimal) if decimal.scale() > 0 => data.to_string(),
+        Data::Decimal(_) => format!("DECIMAL '{data}'"),
+        Data::Timestamp(_) => format!("TIMESTAMP '{data}'"),
+        // with an explicit UTC offset, it does not depend on the time zone
+        Data::TimestampTz(_) => format!("TIMESTAMPTZ '{data}'"),
+        Data::Date(_) => format!("DATE '{data}'"),
+        Data::Time(_) => format!("TIME '{data}'"),
+        Data::Interval(_) => format!("INTERVAL '{data}'"),
+        Data::Binary(_) => format!("BYTEA '{data}'"),
+        Data::Uuid(_) => format!("UUID '{data}'"),
+        Data::List(list) => {
+            let elements = list
+                .elements()
+                .iter()
+                .map(display_literal)
+                .collect::<Vec<_>>();
+            format!("ARRAY[{}]", elements.join(", "))
+        }
+        Data::Json(_) => {
+            format!("JSON '{}'", data.to_string().replace('\'', "''"))
+        }
+        Data::Bool(_) | Data::Int64(_) => data.to_string(),
+    }
+}
+
+/// Match `data` against `pattern`.
+fn match_pattern(pattern: &Pattern, negated: bool, data: Data) -> Result<Data> {
+    let Data::String(str) = data else {
+        return Err(Error::PlanError(PlanError::ExprEvaluationError(
+            ExprEvaluationError::UnsupportedTypeForPattern {
+                datatype: data.datatype(),
+            },
+        )));
+    };
+
+    Ok(Data::Bool(pattern.is_match(&str) != negated))
+}
+
+/// Return the datatype of a pattern matching whose input is of `datatype`.
+fn datatype_of_match(datatype: &DataType) -> Result<DataType> {
+    if datatype != &DataType::String {
+        return Err(Error::PlanError(PlanError::ExprEvaluationError(
+            ExprEvaluationError::UnsupportedTypeForPattern {
+                datatype: datatype.clone(),
+            },
+        )));
+    }
+
+    Ok(DataType::Bool)
+}
+
+/// An `Expr` used for sorting, e.g. `a DESC`.
+#[derive(Debug, Clone, Display)]
+#[display(fmt = "{}{}", expr, r#"if *asc { "" } else { " DESC" }"#)]
+pub struct SortExpr {
+    pub expr: Expr,
+    pub asc: bool,
+}
+
+impl SortExpr {
+    /// Compare 2 sort keys, which are `sort_exprs` evaluated against 2 rows.
+    pub fn compare(
+        sort_exprs: &[SortExpr],
+        lhs: &[Data],
+        rhs: &[Data],
+    ) -> Ordering {
+        for ((sort_expr, lhs), rhs) in sort_exprs.iter().zip(lhs).zip(rhs) {
+            let ordering = lhs.partial_cmp(rhs).unwrap_or(Ordering::Equal);
+            let ordering = if sort_expr.asc {
+                ordering
+            } else {
+                ordering.reverse()
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+
+        Ordering::Equal
+    }
+}
+
 /// Operators supported by VinylDB.
 #[derive(Debug, Copy, Clone, Display)]
 pub enum Operator {
@@ -146,10 +1150,10 @@ pub enum Operator {
     #[display(fmt = ">=")]
     /// >=
     GtEq,
-    #[display(fmt = ">")]
+    #[display(fmt = "<")]
     /// <
     Lt,
-    #[display(fmt = ">=")]
+    #[display(fmt = "<=")]
     /// <=
     LtEq,
     #[display(fmt = "=")]
@@ -173,12 +1177,68 @@ pub enum Operator {
 }
 
 impl Operator {
+    /// Return the precedence of this operator.
+    fn precedence(&self) -> u8 {
+        match self {
+            Operator::Gt
+            | Operator::GtEq
+            | Operator::Lt
+            | Operator::LtEq
+            | Operator::Eq
+            | Operator::NotEq => COMPARISON_PRECEDENCE,
+            Operator::Plus | Operator::Minus => PLUS_MINUS_PRECEDENCE,
+            Operator::And => AND_PRECEDENCE,
+            Operator::Or => OR_PRECEDENCE,
+        }
+    }
+
+    /// Return true if this is a comparison operator, e.g., `=` or `<`.
+    fn is_comparison(&self) -> bool {
+        self.precedence() == COMPARISON_PRECEDENCE
+    }
+
+    /// Return the value that decides the result of this operator on its own,
+    /// no matter what the other operand is, i.e., `false` for `AND` and `true`
+    /// for `OR`.
+    fn short_circuit_value(&self) -> Option<Data> {
+        match self {
+            Operator::And => Some(Data::Bool(false)),
+            Operator::Or => Some(Data::Bool(true)),
+            _ => None,
+        }
+    }
+
     /// Operate on `lhs` and `rhs`.
     pub fn operate(&self, lhs: Data, rhs: Data) -> Result<Data> {
-        // Currently, all our Operators require `lhs` and `rhs` should have the same type.
-        // this may change in the future.
+        let (lhs, rhs) = if self.is_comparison() {
+            enum_label_to_value(lhs, rhs)?
+        } else {
+            (lhs, rhs)
+        };
+        let (lhs, rhs) = promote_int_to_float(lhs, rhs);
         let lhs_dt = lhs.datatype();
         let rhs_dt = rhs.datatype();
+        if is_decimal_operation(&lhs_dt, &rhs_dt) {
+            self.datatype_of_operation(&lhs_dt, &rhs_dt)?;
+            return operate_decimal(*self, &lhs, &rhs).ok_or(Error::PlanError(
+                PlanError::ExprEvaluationError(
+                    ExprEvaluationError::NumericOverflow,
+                ),
+            ));
+        }
+        if matches!(self, Operator::Plus | Operator::Minus)
+            && (lhs_dt.is_datetime() || rhs_dt.is_datetime())
+        {
+            self.datatype_of_operation(&lhs_dt, &rhs_dt)?;
+            return operate_datetime(*self, &lhs, &rhs).ok_or(
+                Error::PlanError(PlanError::ExprEvaluationError(
+                    ExprEvaluationError::DateTimeOutOfRange,
+                )),
+            );
+        }
+
+        // Except for date/time arithmetic, all our Operators require `lhs` and
+        // `rhs` should have the same type.
         if lhs_dt != rhs_dt {
             return Err(Error::PlanError(PlanError::ExprEvaluationError(
                 ExprEvaluationError::DoOpOnDiffTypes {
@@ -208,7 +1268,11 @@ impl Operator {
                     ));
                 }
 
-                lhs + rhs
+                lhs.checked_add(&rhs).ok_or(Error::PlanError(
+                    PlanError::ExprEvaluationError(
+                        ExprEvaluationError::NumericOverflow,
+                    ),
+                ))?
             }
             Operator::Minus => {
                 if lhs_dt != DataType::Int64 && lhs_dt != DataType::Float64 {
@@ -222,7 +1286,11 @@ impl Operator {
                     ));
                 }
 
-                lhs - rhs
+                lhs.checked_sub(&rhs).ok_or(Error::PlanError(
+                    PlanError::ExprEvaluationError(
+                        ExprEvaluationError::NumericOverflow,
+                    ),
+                ))?
             }
             Operator::And => {
                 let Data::Bool(left) = lhs else {
@@ -283,14 +1351,73 @@ impl Operator {
         lhs_dt: &DataType,
         rhs_dt: &DataType,
     ) -> Result<DataType> {
-        // Currently, all our Operators require `lhs` and `rhs` should have the same type.
-        // this may change in the future.
+        if is_decimal_operation(lhs_dt, rhs_dt) {
+            return datatype_of_decimal_operation(
+                *self,
+                lhs_dt.clone(),
+                rhs_dt.clone(),
+            )
+            .ok_or(Error::PlanError(
+                PlanError::ExprEvaluationError(
+                    ExprEvaluationError::UnsupportedTypeForOp {
+                        datatype: lhs_dt.clone(),
+                        op: *self,
+                    },
+                ),
+            ));
+        }
+        if matches!(self, Operator::Plus | Operator::Minus)
+            && (lhs_dt.is_datetime() || rhs_dt.is_datetime())
+        {
+            return datatype_of_datetime_operation(
+                *self,
+                lhs_dt.clone(),
+                rhs_dt.clone(),
+            )
+            .ok_or(Error::PlanError(
+                PlanError::ExprEvaluationError(if lhs_dt == rhs_dt {
+                    ExprEvaluationError::UnsupportedTypeForOp {
+                        datatype: lhs_dt.clone(),
+                        op: *self,
+                    }
+                } else {
+                    ExprEvaluationError::DoOpOnDiffTypes {
+                        lhs: lhs_dt.clone(),
+                        op: *self,
+                        rhs: rhs_dt.clone(),
+                    }
+                }),
+            ));
+        }
+
+        // A string is compared with an enum as one of its labels.
+        if self.is_comparison()
+            && matches!(
+                (lhs_dt, rhs_dt),
+                (DataType::Enum(_), DataType::String)
+                    | (DataType::String, DataType::Enum(_))
+            )
+        {
+            return Ok(DataType::Bool);
+        }
+
+        // An `INT64` is promoted when operated with a `FLOAT64`.
+        let (lhs_dt, rhs_dt) = match (lhs_dt, rhs_dt) {
+            (DataType::Int64, DataType::Float64)
+            | (DataType::Float64, DataType::Int64) => {
+                (&DataType::Float64, &DataType::Float64)
+            }
+            operands => operands,
+        };
+
+        // Except for date/time arithmetic, all our Operators require `lhs` and
+        // `rhs` should have the same type.
         if lhs_dt != rhs_dt {
             return Err(Error::PlanError(PlanError::ExprEvaluationError(
                 ExprEvaluationError::DoOpOnDiffTypes {
-                    lhs: *lhs_dt,
+                    lhs: lhs_dt.clone(),
                     op: *self,
-                    rhs: *rhs_dt,
+                    rhs: rhs_dt.clone(),
                 },
             )));
         }
@@ -307,21 +1434,21 @@ impl Operator {
                     return Err(Error::PlanError(
                         PlanError::ExprEvaluationError(
                             ExprEvaluationError::UnsupportedTypeForOp {
-                                datatype: *lhs_dt,
+                                datatype: lhs_dt.clone(),
                                 op: *self,
                             },
                         ),
                     ));
                 }
 
-                Ok(*lhs_dt)
+                Ok(lhs_dt.clone())
             }
             Operator::And | Operator::Or => {
                 if lhs_dt != &DataType::Bool {
                     return Err(Error::PlanError(
                         PlanError::ExprEvaluationError(
                             ExprEvaluationError::UnsupportedTypeForOp {
-                                datatype: *lhs_dt,
+                                datatype: lhs_dt.clone(),
                                 op: *self,
                             },
                         ),
@@ -334,6 +1461,260 @@ impl Operator {
     }
 }
 
+/// Convert a string compared with an enum to the value of the enum whose
+/// label it is.
+fn enum_label_to_value(lhs: Data, rhs: Data) -> Result<(Data, Data)> {
+    let value_of = |value: &EnumValue, label: String| {
+        value
+            .enum_type()
+            .value(&label)
+            .map(Data::Enum)
+            .ok_or_else(|| {
+                Error::PlanError(PlanError::ExprEvaluationError(
+                    ExprEvaluationError::InvalidEnumLabel {
+                        name: value.enum_type().name().to_string(),
+                        label,
+                    },
+                ))
+            })
+    };
+    match (lhs, rhs) {
+        (Data::Enum(value), Data::String(label)) => {
+            let label = value_of(&value, label)?;
+            Ok((Data::Enum(value), label))
+        }
+        (Data::String(label), Data::Enum(value)) => {
+            Ok((value_of(&value, label)?, Data::Enum(value)))
+        }
+        operands => Ok(operands),
+    }
+}
+
+/// Convert the `INT64` operand to a `FLOAT64` if the other one is a `FLOAT64`,
+/// like [`unify_case_types()`].
+fn promote_int_to_float(lhs: Data, rhs: Data) -> (Data, Data) {
+    match (lhs, rhs) {
+        (Data::Int64(int), rhs @ Data::Float64(_)) => {
+            (Data::Float64(int as f64), rhs)
+        }
+        (lhs @ Data::Float64(_), Data::Int64(int)) => {
+            (lhs, Data::Float64(int as f64))
+        }
+        operands => operands,
+    }
+}
+
+/// Return true if `lhs op rhs` is an operation on a `DECIMAL` and another
+/// number.
+fn is_decimal_operation(lhs: &DataType, rhs: &DataType) -> bool {
+    lhs.is_numeric()
+        && rhs.is_numeric()
+        && (matches!(lhs, DataType::Decimal { .. })
+            || matches!(rhs, DataType::Decimal { .. }))
+}
+
+/// Return the precision and scale of a `DECIMAL` or an `INT64` operand.
+fn precision_and_scale(datatype: DataType) -> Option<(u8, u8)> {
+    match datatype {
+        DataType::Decimal { precision, scale } => Some((precision, scale)),
+        // i64::MAX has 19 digits
+        DataType::Int64 => Some((19, 0)),
+        _ => None,
+    }
+}
+
+/// Return the datatype of `lhs op rhs`, where one of the operands is a
+/// `DECIMAL`, `None` if it is not supported.
+///
+/// A `DECIMAL` is converted to `FLOAT64` when it is added to or subtracted
+/// from a `FLOAT64`, and is compared with a `FLOAT64` exactly.
+fn datatype_of_decimal_operation(
+    op: Operator,
+    lhs: DataType,
+    rhs: DataType,
+) -> Option<DataType> {
+    match op {
+        Operator::Gt
+        | Operator::GtEq
+        | Operator::Lt
+        | Operator::LtEq
+        | Operator::Eq
+        | Operator::NotEq => Some(DataType::Bool),
+        Operator::Plus | Operator::Minus => {
+            let (Some((lp, ls)), Some((rp, rs))) =
+                (precision_and_scale(lhs), precision_and_scale(rhs))
+            else {
+                return Some(DataType::Float64);
+            };
+            let scale = ls.max(rs);
+            // one more digit for the carry
+            let precision = ((lp - ls).max(rp - rs) + scale + 1)
+                .min(decimal::MAX_PRECISION);
+
+            Some(DataType::Decimal { precision, scale })
+        }
+        Operator::And | Operator::Or => None,
+    }
+}
+
+/// Compute `lhs op rhs`, which has been type-checked by
+/// [`datatype_of_decimal_operation()`], `None` will be returned on overflow.
+fn operate_decimal(op: Operator, lhs: &Data, rhs: &Data) -> Option<Data> {
+    let to_decimal = |data: &Data| match data {
+        Data::Decimal(decimal) => Some(*decimal),
+        Data::Int64(int) => Some(Decimal::new(i128::from(*int), 0)),
+        Data::Float64(float) => Decimal::from_f64(*float),
+        _ => unreachable!("operands have been type-checked"),
+    };
+    let to_f64 = |data: &Data| match data {
+        Data::Decimal(decimal) => decimal.to_f64(),
+        Data::Int64(int) => *int as f64,
+        Data::Float64(float) => *float,
+        _ => unreachable!("operands have been type-checked"),
+    };
+
+    let data = match op {
+        Operator::Plus | Operator::Minus
+            if matches!(lhs, Data::Float64(_))
+                || matches!(rhs, Data::Float64(_)) =>
+        {
+            let (lhs, rhs) = (to_f64(lhs), to_f64(rhs));
+            Data::Float64(if matches!(op, Operator::Plus) {
+                lhs + rhs
+            } else {
+                lhs - rhs
+            })
+        }
+        Operator::Plus => {
+            Data::Decimal(to_decimal(lhs)?.checked_add(&to_decimal(rhs)?)?)
+        }
+        Operator::Minus => {
+            Data::Decimal(to_decimal(lhs)?.checked_sub(&to_decimal(rhs)?)?)
+        }
+        _ => {
+            let ordering = match (to_decimal(lhs), to_decimal(rhs)) {
+                (Some(lhs), Some(rhs)) => lhs.cmp(&rhs),
+                // infinite or too large floats
+                _ => to_f64(lhs).total_cmp(&to_f64(rhs)),
+            };
+            Data::Bool(match op {
+                Operator::Gt => ordering.is_gt(),
+                Operator::GtEq => ordering.is_ge(),
+                Operator::Lt => ordering.is_lt(),
+                Operator::LtEq => ordering.is_le(),
+                Operator::Eq => ordering.is_eq(),
+                Operator::NotEq => ordering.is_ne(),
+                _ => unreachable!("operands have been type-checked"),
+            })
+        }
+    };
+
+    Some(data)
+}
+
+/// Return the datatype of `lhs op rhs`, where `op` is `+` or `-` and at least
+/// one of the operands is a date/time, `None` if it is not supported.
+fn datatype_of_datetime_operation(
+    op: Operator,
+    lhs: DataType,
+    rhs: DataType,
+) -> Option<DataType> {
+    use DataType::{Date, Int64, Interval, Time, Timestamp, TimestampTz};
+    use Operator::{Minus, Plus};
+
+    match (op, lhs, rhs) {
+        (Plus, Timestamp | Date, Interval)
+        | (Plus, Interval, Timestamp | Date)
+        | (Minus, Timestamp | Date, Interval) => Some(Timestamp),
+        (Plus, TimestampTz, Interval)
+        | (Plus, Interval, TimestampTz)
+        | (Minus, TimestampTz, Interval) => Some(TimestampTz),
+        (Plus, Time, Interval)
+        | (Plus, Interval, Time)
+        | (Minus, Time, Interval) => Some(Time),
+        (Plus | Minus, Interval, Interval)
+        | (Minus, Timestamp, Timestamp)
+        | (Minus, TimestampTz, TimestampTz)
+        | (Minus, Time, Time) => Some(Interval),
+        (Plus, Date, Int64) | (Plus, Int64, Date) | (Minus, Date, Int64) => {
+            Some(Date)
+        }
+        (Minus, Date, Date) => Some(Int64),
+        _ => None,
+    }
+}
+
+/// Compute `lhs op rhs`, which has been type-checked by
+/// [`datatype_of_datetime_operation()`], `None` will be returned on overflow.
+fn operate_datetime(op: Operator, lhs: &Data, rhs: &Data) -> Option<Data> {
+    use Operator::{Minus, Plus};
+
+    let data = match (op, lhs, rhs) {
+        (Plus, Data::Timestamp(ts), Data::Interval(i))
+        | (Plus, Data::Interval(i), Data::Timestamp(ts)) => {
+            Data::Timestamp(datetime::timestamp_add_interval(*ts, *i)?)
+        }
+        (Minus, Data::Timestamp(ts), Data::Interval(i)) => Data::Timestamp(
+            datetime::timestamp_add_interval(*ts, i.checked_neg()?)?,
+        ),
+        // As time zones are fixed offsets, adding in UTC is the same as adding
+        // in the session time zone.
+        (Plus, Data::TimestampTz(ts), Data::Interval(i))
+        | (Plus, Data::Interval(i), Data::TimestampTz(ts)) => {
+            Data::TimestampTz(datetime::timestamp_add_interval(*ts, *i)?)
+        }
+        (Minus, Data::TimestampTz(ts), Data::Interval(i)) => Data::TimestampTz(
+            datetime::timestamp_add_interval(*ts, i.checked_neg()?)?,
+        ),
+        (Plus, Data::Date(d), Data::Interval(i))
+        | (Plus, Data::Interval(i), Data::Date(d)) => {
+            Data::Timestamp(datetime::timestamp_add_interval(
+                datetime::date_to_timestamp(*d),
+                *i,
+            )?)
+        }
+        (Minus, Data::Date(d), Data::Interval(i)) => {
+            Data::Timestamp(datetime::timestamp_add_interval(
+                datetime::date_to_timestamp(*d),
+                i.checked_neg()?,
+            )?)
+        }
+        (Plus, Data::Time(t), Data::Interval(i))
+        | (Plus, Data::Interval(i), Data::Time(t)) => {
+            Data::Time(datetime::time_add_interval(*t, *i))
+        }
+        (Minus, Data::Time(t), Data::Interval(i)) => {
+            Data::Time(datetime::time_add_interval(*t, i.checked_neg()?))
+        }
+        (Plus, Data::Interval(l), Data::Interval(r)) => {
+            Data::Interval(l.checked_add(*r)?)
+        }
+        (Minus, Data::Interval(l), Data::Interval(r)) => {
+            Data::Interval(l.checked_add(r.checked_neg()?)?)
+        }
+        (Minus, Data::Timestamp(l), Data::Timestamp(r))
+        | (Minus, Data::TimestampTz(l), Data::TimestampTz(r)) => {
+            Data::Interval(datetime::timestamp_diff(*l, *r)?)
+        }
+        (Minus, Data::Time(l), Data::Time(r)) => {
+            Data::Interval(datetime::Interval::new(0, 0, l - r))
+        }
+        (Plus, Data::Date(d), Data::Int64(n))
+        | (Plus, Data::Int64(n), Data::Date(d)) => {
+            Data::Date(d.checked_add(i32::try_from(*n).ok()?)?)
+        }
+        (Minus, Data::Date(d), Data::Int64(n)) => {
+            Data::Date(d.checked_sub(i32::try_from(*n).ok()?)?)
+        }
+        (Minus, Data::Date(l), Data::Date(r)) => {
+            Data::Int64(i64::from(*l) - i64::from(*r))
+        }
+        _ => unreachable!("operands have been type-checked"),
+    };
+
+    Some(data)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -470,6 +1851,65 @@ mod tests {
         assert_eq!(minus.evaluate(&schema, &tuple).unwrap(), Data::Int64(1));
     }
 
+    #[test]
+    fn fold_between_in_list_and_case() {
+        let lit = |data: Data| Box::new(Expr::Literal(data));
+
+        let between = Expr::Between {
+            expr: lit(Data::Int64(2)),
+            negated: false,
+            low: lit(Data::Int64(1)),
+            high: lit(Data::Int64(2)),
+        };
+        assert!(between.is_constant());
+        assert_eq!(between.evaluate_constant_expr().unwrap(), Data::Bool(true));
+
+        let in_list = Expr::InList {
+            expr: lit(Data::Int64(3)),
+            list: vec![Expr::Literal(Data::Int64(1))],
+            negated: true,
+        };
+        assert!(in_list.is_constant());
+        assert_eq!(in_list.evaluate_constant_expr().unwrap(), Data::Bool(true));
+
+        let case = Expr::Case {
+            operand: None,
+            when_then: vec![(between, Expr::Literal(Data::Int64(1)))],
+            else_expr: lit(Data::Float64(0.5)),
+        };
+        assert!(case.is_constant());
+        assert_eq!(
+            case.datatype_of_constant_expr().unwrap(),
+            DataType::Float64
+        );
+        assert_eq!(case.evaluate_constant_expr().unwrap(), Data::Float64(1.0));
+        assert_eq!(
+            case.to_string(),
+            "CASE WHEN 2 BETWEEN 1 AND 2 THEN 1 ELSE 0.5 END"
+        );
+    }
+
+    #[test]
+    fn case_with_column_is_not_constant() {
+        let schema = test_schema();
+        let tuple = test_tuple();
+
+        let case = Expr::Case {
+            operand: Some(Box::new(Expr::Column("age".into()))),
+            when_then: vec![(
+                Expr::Literal(Data::Int64(18)),
+                Expr::Column("name".into()),
+            )],
+            else_expr: Box::new(Expr::Literal(Data::String("".into()))),
+        };
+        assert!(!case.is_constant());
+        assert_eq!(case.datatype(&schema).unwrap(), DataType::String);
+        assert_eq!(
+            case.evaluate(&schema, &tuple).unwrap(),
+            Data::String("steve".into())
+        );
+    }
+
     #[test]
     #[should_panic]
     fn plus_2_booleans() {
@@ -0,0 +1,469 @@
+//! Aggregate functions.
+
+use super::{check_n_args, check_numeric};
+use crate::{
+    catalog::schema::Schema,
+    data::{
+        decimal::MAX_PRECISION,
+        types::{Data, DataType},
+    },
+    error::{Error, Result},
+    expr::Expr,
+    plan::error::{ExprEvaluationError, PlanError, UnimplementedFeature},
+};
+use derive_more::Display;
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+
+/// The implementation of an aggregate function.
+///
+/// An `Accumulator` itself is stateless, the intermediate state of an aggregation
+/// is a `Vec<Data>` created by [`Accumulator::init`] and then threaded through
+/// the other methods, so that one implementation can serve many groups (or
+/// window frames) at the same time.
+pub trait Accumulator: Debug + Send + Sync {
+    /// Return the datatype of the final result, given the argument types.
+    ///
+    /// This is also where the arguments should be checked.
+    fn return_type(&self, args: &[DataType]) -> Result<DataType>;
+
+    /// Return the initial state.
+    fn init(&self) -> Vec<Data>;
+
+    /// Feed the arguments evaluated against one row into `state`.
+    fn update(&self, state: &mut Vec<Data>, args: &[Data]) -> Result<()>;
+
+    /// Merge another state `other` into `state`.
+    fn merge(&self, state: &mut Vec<Data>, other: &[Data]) -> Result<()>;
+
+    /// Compute the final result from `state`.
+    fn finalize(&self, state: &[Data]) -> Result<Data>;
+}
+
+/// An aggregate function, built-in or user-defined.
+#[derive(Debug, Clone, Display)]
+#[display(fmt = "{}", name)]
+pub struct AggregateFunction {
+    name: String,
+    accumulator: Arc<dyn Accumulator>,
+}
+
+impl AggregateFunction {
+    /// Create a new [`AggregateFunction`], `name` will be lowercased as function
+    /// names are case-insensitive.
+    pub fn new<S, A>(name: S, accumulator: A) -> Self
+    where
+        S: Into<String>,
+        A: Accumulator + 'static,
+    {
+        Self {
+            name: name.into().to_lowercase(),
+            accumulator: Arc::new(accumulator),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn accumulator(&self) -> &dyn Accumulator {
+        self.accumulator.as_ref()
+    }
+}
+
+/// An aggregate function call, e.g. `sum(a)`.
+#[derive(Debug, Clone)]
+pub struct AggregateExpr {
+    pub func: AggregateFunction,
+    /// Arguments, `count(*)` has no arguments.
+    pub args: Vec<Expr>,
+}
+
+impl std::fmt::Display for AggregateExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}(", self.func)?;
+        if self.args.is_empty() && self.func.name() == "count" {
+            write!(f, "*")?;
+        }
+        for (idx, arg) in self.args.iter().enumerate() {
+            if idx != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{arg}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl AggregateExpr {
+    /// Return the datatype of this aggregate against an input of `schema`.
+    pub fn datatype(&self, schema: &Schema) -> Result<DataType> {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| arg.datatype(schema))
+            .collect::<Result<Vec<_>>>()?;
+        self.func.accumulator().return_type(&args)
+    }
+}
+
+/// The error returned when an aggregate yields NULL.
+fn null_result() -> Error {
+    Error::PlanError(PlanError::Unimplemented(UnimplementedFeature::Null))
+}
+
+/// Compute `lhs + rhs`, both of which are numbers of the same type, an error
+/// will be returned on overflow.
+fn checked_add(lhs: &Data, rhs: &Data) -> Result<Data> {
+    lhs.checked_add(rhs).ok_or(Error::PlanError(
+        PlanError::ExprEvaluationError(ExprEvaluationError::NumericOverflow),
+    ))
+}
+
+/// `count(*)` or `count(expr)`
+#[derive(Debug, Copy, Clone)]
+pub struct Count;
+
+impl Accumulator for Count {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        if args.len() > 1 {
+            check_n_args("count", args, 1)?;
+        }
+        Ok(DataType::Int64)
+    }
+
+    fn init(&self) -> Vec<Data> {
+        vec![Data::Int64(0)]
+    }
+
+    fn update(&self, state: &mut Vec<Data>, _args: &[Data]) -> Result<()> {
+        self.merge(state, &[Data::Int64(1)])
+    }
+
+    fn merge(&self, state: &mut Vec<Data>, other: &[Data]) -> Result<()> {
+        state[0] = checked_add(&state[0], &other[0])?;
+        Ok(())
+    }
+
+    fn finalize(&self, state: &[Data]) -> Result<Data> {
+        Ok(state[0].clone())
+    }
+}
+
+/// `sum(expr)`
+#[derive(Debug, Copy, Clone)]
+pub struct Sum;
+
+impl Accumulator for Sum {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("sum", args, 1)?;
+        check_numeric("sum", &args[0])?;
+        match &args[0] {
+            // the sum may have more digits than the values
+            DataType::Decimal { scale, .. } => Ok(DataType::Decimal {
+                precision: MAX_PRECISION,
+                scale: *scale,
+            }),
+            datatype => Ok(datatype.clone()),
+        }
+    }
+
+    /// The state is empty until the first value comes in, as we don't know
+    /// the type of `0` until then.
+    fn init(&self) -> Vec<Data> {
+        Vec::new()
+    }
+
+    fn update(&self, state: &mut Vec<Data>, args: &[Data]) -> Result<()> {
+        self.merge(state, &args[..1])
+    }
+
+    fn merge(&self, state: &mut Vec<Data>, other: &[Data]) -> Result<()> {
+        match (state.first_mut(), other.first()) {
+            (Some(sum), Some(val)) => *sum = checked_add(sum, val)?,
+            (None, Some(val)) => state.push(val.clone()),
+            (_, None) => { /* nothing to merge */ }
+        }
+        Ok(())
+    }
+
+    fn finalize(&self, state: &[Data]) -> Result<Data> {
+        state.first().cloned().ok_or_else(null_result)
+    }
+}
+
+/// `min(expr)`
+#[derive(Debug, Copy, Clone)]
+pub struct Min;
+
+impl Accumulator for Min {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("min", args, 1)?;
+        Ok(args[0].clone())
+    }
+
+    fn init(&self) -> Vec<Data> {
+        Vec::new()
+    }
+
+    fn update(&self, state: &mut Vec<Data>, args: &[Data]) -> Result<()> {
+        self.merge(state, &args[..1])
+    }
+
+    fn merge(&self, state: &mut Vec<Data>, other: &[Data]) -> Result<()> {
+        match (state.first_mut(), other.first()) {
+            (Some(min), Some(val)) if val < min => *min = val.clone(),
+            (None, Some(val)) => state.push(val.clone()),
+            _ => { /* nothing to merge */ }
+        }
+        Ok(())
+    }
+
+    fn finalize(&self, state: &[Data]) -> Result<Data> {
+        state.first().cloned().ok_or_else(null_result)
+    }
+}
+
+/// `max(expr)`
+#[derive(Debug, Copy, Clone)]
+pub struct Max;
+
+impl Accumulator for Max {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("max", args, 1)?;
+        Ok(args[0].clone())
+    }
+
+    fn init(&self) -> Vec<Data> {
+        Vec::new()
+    }
+
+    fn update(&self, state: &mut Vec<Data>, args: &[Data]) -> Result<()> {
+        self.merge(state, &args[..1])
+    }
+
+    fn merge(&self, state: &mut Vec<Data>, other: &[Data]) -> Result<()> {
+        match (state.first_mut(), other.first()) {
+            (Some(max), Some(val)) if val > max => *max = val.clone(),
+            (None, Some(val)) => state.push(val.clone()),
+            _ => { /* nothing to merge */ }
+        }
+        Ok(())
+    }
+
+    fn finalize(&self, state: &[Data]) -> Result<Data> {
+        state.first().cloned().ok_or_else(null_result)
+    }
+}
+
+/// `avg(expr)`
+///
+/// The average of a `DECIMAL(p, s)` is a `DECIMAL(38, max(s, 6))`, the one of
+/// the other numbers is a `FLOAT64`.
+#[derive(Debug, Copy, Clone)]
+pub struct Avg;
+
+impl Avg {
+    /// The minimum scale of the average of decimals.
+    const MIN_DECIMAL_SCALE: u8 = 6;
+}
+
+impl Accumulator for Avg {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("avg", args, 1)?;
+        check_numeric("avg", &args[0])?;
+        match &args[0] {
+            DataType::Decimal { scale, .. } => Ok(DataType::Decimal {
+                precision: MAX_PRECISION,
+                scale: (*scale).max(Self::MIN_DECIMAL_SCALE),
+            }),
+            _ => Ok(DataType::Float64),
+        }
+    }
+
+    /// `[count, sum]`, the sum is absent until the first value comes in, as
+    /// we don't know whether it is a decimal until then.
+    fn init(&self) -> Vec<Data> {
+        vec![Data::Int64(0)]
+    }
+
+    fn update(&self, state: &mut Vec<Data>, args: &[Data]) -> Result<()> {
+        let val = match &args[0] {
+            Data::Int64(val) => Data::Float64(*val as f64),
+            val @ (Data::Float64(_) | Data::Decimal(_)) => val.clone(),
+            _ => unreachable!("checked by return_type()"),
+        };
+        self.merge(state, &[Data::Int64(1), val])
+    }
+
+    fn merge(&self, state: &mut Vec<Data>, other: &[Data]) -> Result<()> {
+        state[0] = checked_add(&state[0], &other[0])?;
+        match (state.get_mut(1), other.get(1)) {
+            (Some(sum), Some(val)) => *sum = checked_add(sum, val)?,
+            (None, Some(val)) => state.push(val.clone()),
+            (_, None) => { /* nothing to merge */ }
+        }
+        Ok(())
+    }
+
+    fn finalize(&self, state: &[Data]) -> Result<Data> {
+        let Data::Int64(count) = state[0] else {
+            unreachable!("state is created by init()")
+        };
+        match state.get(1) {
+            Some(Data::Float64(sum)) => Ok(Data::Float64(sum / count as f64)),
+            Some(Data::Decimal(sum)) => {
+                let scale = sum.scale().max(Self::MIN_DECIMAL_SCALE);
+                let avg = sum.checked_div_int(i128::from(count), scale).ok_or(
+                    Error::PlanError(PlanError::ExprEvaluationError(
+                        ExprEvaluationError::NumericOverflow,
+                    )),
+                )?;
+                Ok(Data::Decimal(avg))
+            }
+            Some(_) => unreachable!("checked by return_type()"),
+            None => Err(null_result()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::{decimal::Decimal, tuple::Tuple},
+        VinylDB,
+    };
+    use camino_tempfile::Utf8TempDir;
+    use pretty_assertions::assert_eq;
+
+    fn aggregate(acc: &dyn Accumulator, values: &[Data]) -> Result<Data> {
+        let mut state = acc.init();
+        for val in values {
+            acc.update(&mut state, std::slice::from_ref(val))?;
+        }
+        acc.finalize(&state)
+    }
+
+    #[test]
+    fn builtin_accumulators() {
+        let values = [Data::Int64(3), Data::Int64(1), Data::Int64(2)];
+
+        assert_eq!(aggregate(&Count, &values).unwrap(), Data::Int64(3));
+        assert_eq!(aggregate(&Sum, &values).unwrap(), Data::Int64(6));
+        assert_eq!(aggregate(&Min, &values).unwrap(), Data::Int64(1));
+        assert_eq!(aggregate(&Max, &values).unwrap(), Data::Int64(3));
+        assert_eq!(aggregate(&Avg, &values).unwrap(), Data::Float64(2.0));
+
+        assert_eq!(aggregate(&Count, &[]).unwrap(), Data::Int64(0));
+        assert!(aggregate(&Sum, &[]).is_err());
+        assert!(aggregate(&Avg, &[]).is_err());
+    }
+
+    #[test]
+    fn merge_states() {
+        let mut left = Avg.init();
+        Avg.update(&mut left, &[Data::Int64(1)]).unwrap();
+        let mut right = Avg.init();
+        Avg.update(&mut right, &[Data::Int64(2)]).unwrap();
+        Avg.update(&mut right, &[Data::Int64(6)]).unwrap();
+
+        Avg.merge(&mut left, &right).unwrap();
+        assert_eq!(Avg.finalize(&left).unwrap(), Data::Float64(3.0));
+    }
+
+    #[test]
+    fn decimal_aggregates() {
+        let decimal = |str| Data::Decimal(Decimal::parse(str).unwrap());
+        let values = [decimal("0.10"), decimal("0.20")];
+        assert_eq!(aggregate(&Sum, &values).unwrap(), decimal("0.30"));
+        assert_eq!(aggregate(&Avg, &values).unwrap().to_string(), "0.150000");
+
+        let max = decimal("99999999999999999999999999999999999999");
+        assert!(aggregate(&Sum, &[max.clone(), max]).is_err());
+        assert!(
+            aggregate(&Sum, &[Data::Int64(i64::MAX), Data::Int64(1)]).is_err()
+        );
+    }
+
+    #[test]
+    fn return_type_checks_arguments() {
+        assert!(Sum.return_type(&[DataType::String]).is_err());
+        assert!(Sum.return_type(&[]).is_err());
+        assert!(Count.return_type(&[]).is_ok());
+        assert_eq!(
+            Min.return_type(&[DataType::String]).unwrap(),
+            DataType::String
+        );
+    }
+
+    /// `weighted_avg(value, weight)`
+    #[derive(Debug)]
+    struct WeightedAvg;
+
+    impl Accumulator for WeightedAvg {
+        fn return_type(&self, _args: &[DataType]) -> Result<DataType> {
+            Ok(DataType::Float64)
+        }
+
+        fn init(&self) -> Vec<Data> {
+            vec![Data::Float64(0.0), Data::Float64(0.0)]
+        }
+
+        fn update(&self, state: &mut Vec<Data>, args: &[Data]) -> Result<()> {
+            let (Data::Int64(val), Data::Int64(weight)) = (&args[0], &args[1])
+            else {
+                unreachable!()
+            };
+            let product = Data::Float64((val * weight) as f64);
+            self.merge(state, &[product, Data::Float64(*weight as f64)])
+        }
+
+        fn merge(&self, state: &mut Vec<Data>, other: &[Data]) -> Result<()> {
+            state[0] = state[0].checked_add(&other[0]).unwrap();
+            state[1] = state[1].checked_add(&other[1]).unwrap();
+            Ok(())
+        }
+
+        fn finalize(&self, state: &[Data]) -> Result<Data> {
+            let (Data::Float64(sum), Data::Float64(weight)) =
+                (&state[0], &state[1])
+            else {
+                unreachable!()
+            };
+            Ok(Data::Float64(sum / weight))
+        }
+    }
+
+    #[test]
+    fn user_defined_aggregate() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let mut db = VinylDB::new(temp_dir.path());
+        db.register_aggregate_udf("weighted_avg", WeightedAvg)
+            .unwrap();
+        assert!(db.register_aggregate_udf("SUM", WeightedAvg).is_err());
+
+        db.sql("create table foo (k string, v int64, w int64)")
+            .unwrap();
+        db.sql("insert into foo values ('a', 1, 1), ('b', 2, 3), ('c', 4, 1)")
+            .unwrap();
+
+        assert_eq!(
+            db.sql("select weighted_avg(v, w) from foo").unwrap(),
+            vec![Tuple::new([Data::Float64(2.2)])]
+        );
+        assert_eq!(
+            db.sql(
+                "select k, weighted_avg(v, w) over (order by k rows between 1 preceding and current row) from foo"
+            )
+            .unwrap(),
+            vec![
+                Tuple::new([Data::String("a".into()), Data::Float64(1.0)]),
+                Tuple::new([Data::String("b".into()), Data::Float64(1.75)]),
+                Tuple::new([Data::String("c".into()), Data::Float64(2.5)]),
+            ]
+        );
+    }
+}
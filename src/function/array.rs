@@ -0,0 +1,165 @@
+//! Built-in scalar functions on arrays.
+
+use super::{check_n_args, scalar::ScalarImpl, unsupported_type};
+use crate::{
+    data::{
+        list::List,
+        types::{Data, DataType},
+    },
+    error::{Error, Result},
+    expr::Expr,
+    plan::error::{ExprEvaluationError, PlanError},
+};
+
+/// `make_array(VARIADIC elements)` builds an array from its arguments, also
+/// used by `ARRAY[1, 2]` and `[1, 2]`.
+///
+/// All the elements should have the same type, and there should be at least
+/// one element as the type of an empty array cannot be determined.
+#[derive(Debug, Copy, Clone)]
+pub struct MakeArray;
+
+impl ScalarImpl for MakeArray {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        let Some(first) = args.first() else {
+            return Err(Error::PlanError(PlanError::ExprEvaluationError(
+                ExprEvaluationError::EmptyArray,
+            )));
+        };
+        let element_type = first.value_type();
+        for arg in &args[1..] {
+            if arg.value_type() != element_type {
+                return Err(Error::PlanError(PlanError::ExprEvaluationError(
+                    ExprEvaluationError::MismatchedArrayTypes {
+                        lhs: element_type,
+                        rhs: arg.clone(),
+                    },
+                )));
+            }
+        }
+
+        Ok(DataType::List(Box::new(element_type)))
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let element_type = args[0].datatype();
+        Ok(Data::List(List::new(element_type, args.to_vec())))
+    }
+}
+
+/// `array_extract(array, index)` returns the element at `index`, which starts
+/// from 1, also used by `array[index]`.
+///
+/// As NULL is not supported yet, an index out of range is an error.
+#[derive(Debug, Copy, Clone)]
+pub struct ArrayExtract;
+
+impl ScalarImpl for ArrayExtract {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("array_extract", args, 2)?;
+        let DataType::List(element_type) = &args[0] else {
+            return Err(unsupported_type("array_extract", &args[0]));
+        };
+        if args[1] != DataType::Int64 {
+            return Err(unsupported_type("array_extract", &args[1]));
+        }
+
+        Ok(element_type.value_type())
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let (Data::List(list), Data::Int64(index)) = (&args[0], &args[1])
+        else {
+            unreachable!("type has been checked")
+        };
+        usize::try_from(*index)
+            .ok()
+            .and_then(|index| index.checked_sub(1))
+            .and_then(|idx| list.elements().get(idx))
+            .cloned()
+            .ok_or(Error::PlanError(PlanError::ExprEvaluationError(
+                ExprEvaluationError::ArraySubscriptOutOfRange {
+                    index: *index,
+                    length: list.elements().len(),
+                },
+            )))
+    }
+}
+
+/// `array_length(array [, dimension])` returns the number of elements of
+/// `array` in `dimension`, which is 1 by default.
+///
+/// The length in a dimension beyond the first is that of the first element,
+/// and it is 0 if there is no such element.
+#[derive(Debug, Copy, Clone)]
+pub struct ArrayLength;
+
+impl ArrayLength {
+    /// Return the number of dimensions of arrays of `datatype`.
+    fn n_dimensions(datatype: &DataType) -> i64 {
+        match datatype {
+            DataType::List(element_type) => {
+                1 + Self::n_dimensions(element_type)
+            }
+            _ => 0,
+        }
+    }
+}
+
+impl ScalarImpl for ArrayLength {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        if args.len() != 1 {
+            check_n_args("array_length", args, 2)?;
+        }
+        if !matches!(args[0], DataType::List(_)) {
+            return Err(unsupported_type("array_length", &args[0]));
+        }
+        if args.len() == 2 && args[1] != DataType::Int64 {
+            return Err(unsupported_type("array_length", &args[1]));
+        }
+
+        Ok(DataType::Int64)
+    }
+
+    fn check_args(&self, args: &[Expr], types: &[DataType]) -> Result<()> {
+        let Some(dimension) = args.get(1) else {
+            return Ok(());
+        };
+        let invalid = || {
+            Error::PlanError(PlanError::InvalidArrayDimension {
+                expr: dimension.clone(),
+            })
+        };
+        let Expr::Literal(Data::Int64(dimension)) = dimension else {
+            return Err(invalid());
+        };
+        if !(1..=Self::n_dimensions(&types[0])).contains(dimension) {
+            return Err(invalid());
+        }
+
+        Ok(())
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let dimension = match args.get(1) {
+            Some(Data::Int64(dimension)) => *dimension,
+            Some(_) => unreachable!("type has been checked"),
+            None => 1,
+        };
+        let mut array = &args[0];
+        for _ in 1..dimension {
+            match array {
+                Data::List(list) => match list.elements().first() {
+                    Some(element) => array = element,
+                    None => return Ok(Data::Int64(0)),
+                },
+                _ => unreachable!("dimension has been checked"),
+            }
+        }
+        let Data::List(list) = array else {
+            unreachable!("dimension has been checked")
+        };
+
+        Ok(Data::Int64(list.elements().len() as i64))
+    }
+}
@@ -0,0 +1,246 @@
+//! Built-in scalar functions on date/time values.
+
+use super::{check_n_args, scalar::ScalarImpl, unsupported_type};
+use crate::{
+    data::{
+        datetime::{self, DateTimeField},
+        types::{Data, DataType},
+    },
+    error::{Error, Result},
+    expr::Expr,
+    plan::error::{ExprEvaluationError, PlanError},
+};
+use chrono::{FixedOffset, Utc};
+
+/// Parse the unit argument of `date_part()` and `date_trunc()`.
+fn parse_field(field: &str) -> Result<DateTimeField> {
+    field.parse().map_err(|_| {
+        Error::PlanError(PlanError::ExprEvaluationError(
+            ExprEvaluationError::UnrecognizedDateTimeField {
+                field: field.to_string(),
+            },
+        ))
+    })
+}
+
+/// The error returned when `field` does not apply to values of `datatype`.
+fn unsupported_field(field: DateTimeField, datatype: DataType) -> Error {
+    Error::PlanError(PlanError::ExprEvaluationError(
+        ExprEvaluationError::UnsupportedDateTimeField {
+            field: field.to_string(),
+            datatype,
+        },
+    ))
+}
+
+/// Check that the unit, i.e., the first argument, is a string literal that
+/// is supported by `supported`, so that an invalid unit will be rejected
+/// before execution.
+fn check_field_arg<F>(
+    args: &[Expr],
+    types: &[DataType],
+    supported: F,
+) -> Result<()>
+where
+    F: Fn(DateTimeField, DataType) -> bool,
+{
+    let Expr::Literal(Data::String(field)) = &args[0] else {
+        return Err(Error::PlanError(PlanError::NonLiteralDateTimeField {
+            expr: args[0].clone(),
+        }));
+    };
+    let field = parse_field(field)?;
+    if !supported(field, types[1].clone()) {
+        return Err(unsupported_field(field, types[1].clone()));
+    }
+
+    Ok(())
+}
+
+/// `timezone(zone, source)`, also used by `source AT TIME ZONE zone`.
+///
+/// A `TIMESTAMP` is taken as a local time in `zone` and converted to a
+/// `TIMESTAMPTZ`, and a `TIMESTAMPTZ` is converted to the local time in `zone`.
+#[derive(Debug, Copy, Clone)]
+pub struct Timezone;
+
+impl ScalarImpl for Timezone {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("timezone", args, 2)?;
+        if args[0] != DataType::String {
+            return Err(unsupported_type("timezone", &args[0]));
+        }
+        match &args[1] {
+            DataType::Timestamp => Ok(DataType::TimestampTz),
+            DataType::TimestampTz => Ok(DataType::Timestamp),
+            datatype => Err(unsupported_type("timezone", datatype)),
+        }
+    }
+
+    fn check_args(&self, args: &[Expr], _types: &[DataType]) -> Result<()> {
+        let Expr::Literal(Data::String(zone)) = &args[0] else {
+            return Err(Error::PlanError(PlanError::NonLiteralTimeZone {
+                expr: args[0].clone(),
+            }));
+        };
+        parse_time_zone(zone)?;
+
+        Ok(())
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let Data::String(zone) = &args[0] else {
+            unreachable!("type has been checked")
+        };
+        let time_zone = parse_time_zone(zone)?;
+        let data = match &args[1] {
+            Data::Timestamp(micros) => {
+                datetime::timestamp_to_timestamptz(*micros, time_zone)
+                    .map(Data::TimestampTz)
+            }
+            Data::TimestampTz(micros) => {
+                datetime::timestamptz_to_timestamp(*micros, time_zone)
+                    .map(Data::Timestamp)
+            }
+            _ => unreachable!("type has been checked"),
+        };
+
+        data.ok_or(Error::PlanError(PlanError::ExprEvaluationError(
+            ExprEvaluationError::DateTimeOutOfRange,
+        )))
+    }
+}
+
+/// Parse the zone argument of `timezone()`.
+fn parse_time_zone(zone: &str) -> Result<FixedOffset> {
+    datetime::parse_time_zone(zone).ok_or_else(|| {
+        Error::PlanError(PlanError::ExprEvaluationError(
+            ExprEvaluationError::UnrecognizedTimeZone {
+                zone: zone.to_string(),
+            },
+        ))
+    })
+}
+
+/// `date_part(unit, source)`, also used by `extract(unit FROM source)`.
+#[derive(Debug, Copy, Clone)]
+pub struct DatePart;
+
+impl DatePart {
+    fn part(field: DateTimeField, source: &Data) -> Option<f64> {
+        match source {
+            Data::Timestamp(secs) => datetime::timestamp_part(field, *secs),
+            Data::Date(days) => datetime::date_part(field, *days),
+            Data::Time(micros) => datetime::time_part(field, *micros),
+            Data::Interval(interval) => {
+                datetime::interval_part(field, *interval)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl ScalarImpl for DatePart {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("date_part", args, 2)?;
+        if args[0] != DataType::String {
+            return Err(unsupported_type("date_part", &args[0]));
+        }
+        if !matches!(
+            args[1],
+            DataType::Timestamp
+                | DataType::Date
+                | DataType::Time
+                | DataType::Interval
+        ) {
+            return Err(unsupported_type("date_part", &args[1]));
+        }
+
+        Ok(DataType::Float64)
+    }
+
+    fn check_args(&self, args: &[Expr], types: &[DataType]) -> Result<()> {
+        check_field_arg(args, types, |field, datatype| {
+            let sample = match datatype {
+                DataType::Timestamp => Data::Timestamp(0),
+                DataType::Date => Data::Date(0),
+                DataType::Time => Data::Time(0),
+                _ => Data::Interval(datetime::Interval::new(0, 0, 0)),
+            };
+            Self::part(field, &sample).is_some()
+        })
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let Data::String(field) = &args[0] else {
+            unreachable!("type has been checked")
+        };
+        let field = parse_field(field)?;
+        Self::part(field, &args[1])
+            .map(Data::Float64)
+            .ok_or_else(|| unsupported_field(field, args[1].datatype()))
+    }
+}
+
+/// `date_trunc(unit, source)`, the result is always a timestamp.
+#[derive(Debug, Copy, Clone)]
+pub struct DateTrunc;
+
+impl ScalarImpl for DateTrunc {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("date_trunc", args, 2)?;
+        if args[0] != DataType::String {
+            return Err(unsupported_type("date_trunc", &args[0]));
+        }
+        if !matches!(args[1], DataType::Timestamp | DataType::Date) {
+            return Err(unsupported_type("date_trunc", &args[1]));
+        }
+
+        Ok(DataType::Timestamp)
+    }
+
+    fn check_args(&self, args: &[Expr], types: &[DataType]) -> Result<()> {
+        check_field_arg(args, types, |field, _| {
+            datetime::date_trunc(field, 0).is_some()
+        })
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let Data::String(field) = &args[0] else {
+            unreachable!("type has been checked")
+        };
+        let field = parse_field(field)?;
+        let secs = match &args[1] {
+            Data::Timestamp(secs) => *secs,
+            Data::Date(days) => datetime::date_to_timestamp(*days),
+            _ => unreachable!("type has been checked"),
+        };
+        datetime::date_trunc(field, secs)
+            .map(Data::Timestamp)
+            .ok_or(Error::PlanError(PlanError::ExprEvaluationError(
+                ExprEvaluationError::DateTimeOutOfRange,
+            )))
+    }
+}
+
+/// `now()` returns the current date and time, it is volatile, i.e., it is
+/// evaluated on every call, unlike PostgreSQL, where it is the start time of
+/// the current transaction.
+#[derive(Debug, Copy, Clone)]
+pub struct Now;
+
+impl ScalarImpl for Now {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("now", args, 0)?;
+
+        Ok(DataType::TimestampTz)
+    }
+
+    fn is_volatile(&self) -> bool {
+        true
+    }
+
+    fn invoke(&self, _args: &[Data]) -> Result<Data> {
+        Ok(Data::TimestampTz(Utc::now().timestamp_micros()))
+    }
+}
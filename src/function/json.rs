@@ -0,0 +1,189 @@
+//! Built-in scalar functions on JSON values.
+//!
+//! The `json_*` and `jsonb_*` variants of a function are the same, as `JSON`
+//! and `JSONB` are the same type.
+
+use super::{check_n_args, scalar::ScalarImpl, unsupported_type};
+use crate::{
+    data::{
+        json::Json,
+        types::{Data, DataType},
+    },
+    error::{Error, Result},
+    plan::error::{ExprEvaluationError, PlanError},
+};
+
+/// Return the text of a JSON value, a string is returned without quotes.
+fn json_to_text(json: &Json) -> String {
+    match json {
+        Json::String(str) => str.clone(),
+        json => json.to_string(),
+    }
+}
+
+/// `json_extract_path(json, VARIADIC path)` returns the value at `path`,
+/// also used by the `->` and `#>` operators. With `text` set, it is
+/// `json_extract_path_text()`, used by `->>` and `#>>`.
+///
+/// An element of `path` is an object key, or an array index, which is either
+/// an integer or a string of an integer. As NULL is not supported yet, the
+/// result is JSON `null` (or the text `null`) if there is no such value.
+#[derive(Debug, Copy, Clone)]
+pub struct ExtractPath {
+    pub name: &'static str,
+    pub text: bool,
+}
+
+impl ScalarImpl for ExtractPath {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        if args.is_empty() {
+            check_n_args(self.name, args, 1)?;
+        }
+        if args[0] != DataType::Json {
+            return Err(unsupported_type(self.name, &args[0]));
+        }
+        for arg in &args[1..] {
+            if !matches!(arg, DataType::String | DataType::Int64) {
+                return Err(unsupported_type(self.name, arg));
+            }
+        }
+
+        Ok(if self.text {
+            DataType::String
+        } else {
+            DataType::Json
+        })
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let Data::Json(json) = &args[0] else {
+            unreachable!("type has been checked")
+        };
+        let value = args[1..].iter().try_fold(json, |json, step| match step {
+            Data::String(key) => match json {
+                Json::Array(_) => json.element(key.trim().parse().ok()?),
+                _ => json.field(key),
+            },
+            Data::Int64(idx) => json.element(*idx),
+            _ => unreachable!("type has been checked"),
+        });
+        let value = value.unwrap_or(&Json::Null);
+
+        Ok(if self.text {
+            Data::String(json_to_text(value))
+        } else {
+            Data::Json(value.clone())
+        })
+    }
+}
+
+/// `json_array_length(json)` returns the number of elements of an array.
+///
+/// It is 0 for `null`, which is what a missing value is extracted as.
+#[derive(Debug, Copy, Clone)]
+pub struct ArrayLength {
+    pub name: &'static str,
+}
+
+impl ScalarImpl for ArrayLength {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args(self.name, args, 1)?;
+        if args[0] != DataType::Json {
+            return Err(unsupported_type(self.name, &args[0]));
+        }
+
+        Ok(DataType::Int64)
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        match &args[0] {
+            Data::Json(Json::Array(elements)) => {
+                Ok(Data::Int64(elements.len() as i64))
+            }
+            Data::Json(Json::Null) => Ok(Data::Int64(0)),
+            Data::Json(_) => {
+                Err(Error::PlanError(PlanError::ExprEvaluationError(
+                    ExprEvaluationError::JsonArrayLengthOfNonArray,
+                )))
+            }
+            _ => unreachable!("type has been checked"),
+        }
+    }
+}
+
+/// `json_typeof(json)` returns the type of the value, e.g., `object`.
+#[derive(Debug, Copy, Clone)]
+pub struct Typeof {
+    pub name: &'static str,
+}
+
+impl ScalarImpl for Typeof {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args(self.name, args, 1)?;
+        if args[0] != DataType::Json {
+            return Err(unsupported_type(self.name, &args[0]));
+        }
+
+        Ok(DataType::String)
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let Data::Json(json) = &args[0] else {
+            unreachable!("type has been checked")
+        };
+
+        Ok(Data::String(json.type_name().to_string()))
+    }
+}
+
+/// `jsonb_exists(json, key)`, the `?` operator, see [`Json::exists()`].
+#[derive(Debug, Copy, Clone)]
+pub struct Exists;
+
+impl ScalarImpl for Exists {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("jsonb_exists", args, 2)?;
+        if args[0] != DataType::Json {
+            return Err(unsupported_type("jsonb_exists", &args[0]));
+        }
+        if args[1] != DataType::String {
+            return Err(unsupported_type("jsonb_exists", &args[1]));
+        }
+
+        Ok(DataType::Bool)
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let (Data::Json(json), Data::String(key)) = (&args[0], &args[1]) else {
+            unreachable!("type has been checked")
+        };
+
+        Ok(Data::Bool(json.exists(key)))
+    }
+}
+
+/// `jsonb_contains(json, json)`, the `@>` operator, see
+/// [`Json::contains()`].
+#[derive(Debug, Copy, Clone)]
+pub struct Contains;
+
+impl ScalarImpl for Contains {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("jsonb_contains", args, 2)?;
+        for arg in args {
+            if arg != &DataType::Json {
+                return Err(unsupported_type("jsonb_contains", arg));
+            }
+        }
+
+        Ok(DataType::Bool)
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let (Data::Json(json), Data::Json(other)) = (&args[0], &args[1]) else {
+            unreachable!("type has been checked")
+        };
+
+        Ok(Data::Bool(json.contains(other)))
+    }
+}
@@ -0,0 +1,243 @@
+//! Functions that can be called from SQL.
+
+pub mod aggregate;
+pub mod array;
+pub mod datetime;
+pub mod json;
+pub mod scalar;
+pub mod sequence;
+pub mod string;
+pub mod uuid;
+pub mod window;
+
+use crate::{
+    catalog::error::{CatalogError, CatalogResult},
+    data::types::DataType,
+    error::{Error, Result},
+    plan::error::{ExprEvaluationError, PlanError},
+    sequence::{Currvals, Sequences},
+};
+use aggregate::{AggregateFunction, Avg, Count, Max, Min, Sum};
+use indexmap::IndexMap;
+use scalar::ScalarFunction;
+use std::sync::Arc;
+
+/// All the functions known to VinylDB, built-in ones and user-defined ones.
+///
+/// Aggregate functions and scalar functions share the same namespace.
+#[derive(Debug, Clone)]
+pub struct FunctionRegistry {
+    aggregate_functions: IndexMap<String, AggregateFunction>,
+    scalar_functions: IndexMap<String, ScalarFunction>,
+}
+
+impl Default for FunctionRegistry {
+    /// Create a registry with all the built-in functions registered.
+    fn default() -> Self {
+        let mut registry = Self {
+            aggregate_functions: IndexMap::new(),
+            scalar_functions: IndexMap::new(),
+        };
+        for func in [
+            AggregateFunction::new("count", Count),
+            AggregateFunction::new("sum", Sum),
+            AggregateFunction::new("min", Min),
+            AggregateFunction::new("max", Max),
+            AggregateFunction::new("avg", Avg),
+        ] {
+            registry
+                .register_aggregate_function(func)
+                .expect("built-in functions should not have duplicate names");
+        }
+        for func in [
+            ScalarFunction::new("date_part", datetime::DatePart),
+            ScalarFunction::new("date_trunc", datetime::DateTrunc),
+            ScalarFunction::new("timezone", datetime::Timezone),
+            ScalarFunction::new("now", datetime::Now),
+            ScalarFunction::new("length", string::Length),
+            ScalarFunction::new("substring", string::Substring),
+            ScalarFunction::new("encode", string::Encode),
+            ScalarFunction::new("decode", string::Decode),
+            ScalarFunction::new(
+                "json_extract_path",
+                json::ExtractPath {
+                    name: "json_extract_path",
+                    text: false,
+                },
+            ),
+            ScalarFunction::new(
+                "json_extract_path_text",
+                json::ExtractPath {
+                    name: "json_extract_path_text",
+                    text: true,
+                },
+            ),
+            ScalarFunction::new(
+                "json_array_length",
+                json::ArrayLength {
+                    name: "json_array_length",
+                },
+            ),
+            ScalarFunction::new(
+                "json_typeof",
+                json::Typeof {
+                    name: "json_typeof",
+                },
+            ),
+            ScalarFunction::new(
+                "jsonb_extract_path",
+                json::ExtractPath {
+                    name: "jsonb_extract_path",
+                    text: false,
+                },
+            ),
+            ScalarFunction::new(
+                "jsonb_extract_path_text",
+                json::ExtractPath {
+                    name: "jsonb_extract_path_text",
+                    text: true,
+                },
+            ),
+            ScalarFunction::new(
+                "jsonb_array_length",
+                json::ArrayLength {
+                    name: "jsonb_array_length",
+                },
+            ),
+            ScalarFunction::new(
+                "jsonb_typeof",
+                json::Typeof {
+                    name: "jsonb_typeof",
+                },
+            ),
+            ScalarFunction::new("jsonb_exists", json::Exists),
+            ScalarFunction::new("jsonb_contains", json::Contains),
+            ScalarFunction::new("gen_random_uuid", uuid::GenRandomUuid),
+            ScalarFunction::new("make_array", array::MakeArray),
+            ScalarFunction::new("array_extract", array::ArrayExtract),
+            ScalarFunction::new("array_length", array::ArrayLength),
+        ] {
+            registry
+                .register_scalar_function(func)
+                .expect("built-in functions should not have duplicate names");
+        }
+
+        registry
+    }
+}
+
+impl FunctionRegistry {
+    /// Return an error if there is already a function named `name`.
+    fn check_name(&self, name: &str) -> CatalogResult<()> {
+        if self.aggregate_functions.contains_key(name)
+            || self.scalar_functions.contains_key(name)
+        {
+            return Err(CatalogError::FunctionExists {
+                name: name.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Register an aggregate function, an error will be returned if there is
+    /// already a function with the same name.
+    pub fn register_aggregate_function(
+        &mut self,
+        func: AggregateFunction,
+    ) -> CatalogResult<()> {
+        self.check_name(func.name())?;
+        self.aggregate_functions
+            .insert(func.name().to_string(), func);
+
+        Ok(())
+    }
+
+    /// Register a scalar function, an error will be returned if there is
+    /// already a function with the same name.
+    pub fn register_scalar_function(
+        &mut self,
+        func: ScalarFunction,
+    ) -> CatalogResult<()> {
+        self.check_name(func.name())?;
+        self.scalar_functions.insert(func.name().to_string(), func);
+
+        Ok(())
+    }
+
+    /// Register the functions on `sequences`, `nextval()`, `currval()` and
+    /// `setval()`, which record the values of a session in `currvals`.
+    ///
+    /// The ones registered before are replaced, so that another session can
+    /// record its own values.
+    pub fn register_sequence_functions(
+        &mut self,
+        sequences: &Arc<Sequences>,
+        currvals: &Arc<Currvals>,
+    ) {
+        for func in [
+            ScalarFunction::new(
+                "nextval",
+                sequence::NextVal(Arc::clone(sequences), Arc::clone(currvals)),
+            ),
+            ScalarFunction::new(
+                "currval",
+                sequence::CurrVal(Arc::clone(sequences), Arc::clone(currvals)),
+            ),
+            ScalarFunction::new(
+                "setval",
+                sequence::SetVal(Arc::clone(sequences), Arc::clone(currvals)),
+            ),
+        ] {
+            self.scalar_functions.insert(func.name().to_string(), func);
+        }
+    }
+
+    /// Look up the aggregate function named `name`, case-insensitively.
+    pub fn aggregate_function(&self, name: &str) -> Option<&AggregateFunction> {
+        self.aggregate_functions.get(&name.to_lowercase())
+    }
+
+    /// Look up the scalar function named `name`, case-insensitively.
+    pub fn scalar_function(&self, name: &str) -> Option<&ScalarFunction> {
+        self.scalar_functions.get(&name.to_lowercase())
+    }
+}
+
+/// Return an error if `args` does not have `expected` elements.
+pub(crate) fn check_n_args(
+    func: &str,
+    args: &[DataType],
+    expected: usize,
+) -> Result<()> {
+    if args.len() != expected {
+        return Err(Error::PlanError(PlanError::ExprEvaluationError(
+            ExprEvaluationError::WrongNumberOfArguments {
+                func: func.to_string(),
+                expected,
+                found: args.len(),
+            },
+        )));
+    }
+
+    Ok(())
+}
+
+/// Return an error if `datatype` is not a numeric type.
+pub(crate) fn check_numeric(func: &str, datatype: &DataType) -> Result<()> {
+    if !datatype.is_numeric() {
+        return Err(unsupported_type(func, datatype));
+    }
+
+    Ok(())
+}
+
+/// The error returned when `func` does not accept an argument of `datatype`.
+pub(crate) fn unsupported_type(func: &str, datatype: &DataType) -> Error {
+    Error::PlanError(PlanError::ExprEvaluationError(
+        ExprEvaluationError::UnsupportedTypeForFunction {
+            func: func.to_string(),
+            datatype: datatype.clone(),
+        },
+    ))
+}
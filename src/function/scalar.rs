@@ -0,0 +1,122 @@
+//! Scalar functions, which compute a value from the arguments of one row.
+
+use crate::{
+    catalog::schema::Schema,
+    data::types::{Data, DataType},
+    error::Result,
+    expr::Expr,
+};
+use derive_more::Display;
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+
+/// The implementation of a scalar function.
+pub trait ScalarImpl: Debug + Send + Sync {
+    /// Return the datatype of the result, given the argument types.
+    ///
+    /// This is also where the arguments should be checked.
+    fn return_type(&self, args: &[DataType]) -> Result<DataType>;
+
+    /// Check the arguments beyond their types during planning, e.g., some
+    /// arguments have to be literals.
+    ///
+    /// It is called after [`ScalarImpl::return_type`] succeeds.
+    fn check_args(&self, _args: &[Expr], _types: &[DataType]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Return true if the result can differ between calls with the same
+    /// arguments, e.g., `gen_random_uuid()`, such calls are never evaluated
+    /// in advance during planning.
+    fn is_volatile(&self) -> bool {
+        false
+    }
+
+    /// Compute the result from `args`, which have been checked by
+    /// [`ScalarImpl::return_type`].
+    fn invoke(&self, args: &[Data]) -> Result<Data>;
+}
+
+/// A scalar function, built-in or user-defined.
+#[derive(Debug, Clone, Display)]
+#[display(fmt = "{}", name)]
+pub struct ScalarFunction {
+    name: String,
+    implementation: Arc<dyn ScalarImpl>,
+}
+
+impl ScalarFunction {
+    /// Create a new [`ScalarFunction`], `name` will be lowercased as function
+    /// names are case-insensitive.
+    pub fn new<S, I>(name: S, implementation: I) -> Self
+    where
+        S: Into<String>,
+        I: ScalarImpl + 'static,
+    {
+        Self {
+            name: name.into().to_lowercase(),
+            implementation: Arc::new(implementation),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn implementation(&self) -> &dyn ScalarImpl {
+        self.implementation.as_ref()
+    }
+}
+
+/// A scalar function call, e.g. `date_trunc('day', a)`.
+#[derive(Debug, Clone)]
+pub struct ScalarFunctionExpr {
+    pub func: ScalarFunction,
+    pub args: Vec<Expr>,
+}
+
+impl std::fmt::Display for ScalarFunctionExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}(", self.func)?;
+        for (idx, arg) in self.args.iter().enumerate() {
+            if idx != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{arg}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl ScalarFunctionExpr {
+    /// Return the datatype of this call, `datatype` computes the datatypes of
+    /// the arguments.
+    pub(crate) fn datatype_with<F>(&self, datatype: F) -> Result<DataType>
+    where
+        F: FnMut(&Expr) -> Result<DataType>,
+    {
+        let types =
+            self.args.iter().map(datatype).collect::<Result<Vec<_>>>()?;
+        let implementation = self.func.implementation();
+        let return_type = implementation.return_type(&types)?;
+        implementation.check_args(&self.args, &types)?;
+
+        Ok(return_type)
+    }
+
+    /// Return the datatype of this call against an input of `schema`.
+    pub fn datatype(&self, schema: &Schema) -> Result<DataType> {
+        self.datatype_with(|arg| arg.datatype(schema))
+    }
+
+    /// Evaluate this call, `eval` evaluates the arguments.
+    pub(crate) fn evaluate_with<F>(&self, eval: F) -> Result<Data>
+    where
+        F: FnMut(&Expr) -> Result<Data>,
+    {
+        let args = self.args.iter().map(eval).collect::<Result<Vec<_>>>()?;
+        self.func.implementation().invoke(&args)
+    }
+}
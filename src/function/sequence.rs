@@ -0,0 +1,106 @@
+//! Built-in scalar functions on sequences, they are volatile as every call
+//! can change a sequence.
+
+use super::{check_n_args, scalar::ScalarImpl, unsupported_type};
+use crate::{
+    as_variant,
+    data::types::{Data, DataType},
+    error::{Error, Result},
+    plan::error::{ExprEvaluationError, PlanError},
+    sequence::{Currvals, Sequences},
+};
+use std::sync::Arc;
+
+/// `nextval(name)` advances sequence `name` and returns its new value.
+#[derive(Debug)]
+pub struct NextVal(pub Arc<Sequences>, pub Arc<Currvals>);
+
+impl ScalarImpl for NextVal {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("nextval", args, 1)?;
+        if args[0] != DataType::String {
+            return Err(unsupported_type("nextval", &args[0]));
+        }
+
+        Ok(DataType::Int64)
+    }
+
+    fn is_volatile(&self) -> bool {
+        true
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let name = as_variant!(Data::String, &args[0]);
+        self.0.nextval(name, &self.1).map(Data::Int64)
+    }
+}
+
+/// `currval(name)` returns the value returned by the latest `nextval(name)`
+/// in this session.
+#[derive(Debug)]
+pub struct CurrVal(pub Arc<Sequences>, pub Arc<Currvals>);
+
+impl ScalarImpl for CurrVal {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("currval", args, 1)?;
+        if args[0] != DataType::String {
+            return Err(unsupported_type("currval", &args[0]));
+        }
+
+        Ok(DataType::Int64)
+    }
+
+    fn is_volatile(&self) -> bool {
+        true
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let name = as_variant!(Data::String, &args[0]);
+        self.0.currval(name, &self.1).map(Data::Int64)
+    }
+}
+
+/// `setval(name, value [, is_called])` sets the value of sequence `name`, the
+/// next `nextval(name)` returns the value after it, or itself if `is_called`
+/// is false.
+#[derive(Debug)]
+pub struct SetVal(pub Arc<Sequences>, pub Arc<Currvals>);
+
+impl ScalarImpl for SetVal {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        if !matches!(args.len(), 2 | 3) {
+            return Err(Error::PlanError(PlanError::ExprEvaluationError(
+                ExprEvaluationError::WrongNumberOfArguments {
+                    func: "setval".to_string(),
+                    expected: 2,
+                    found: args.len(),
+                },
+            )));
+        }
+        let expected = [DataType::String, DataType::Int64, DataType::Bool];
+        for (datatype, expected) in args.iter().zip(expected.iter()) {
+            if datatype != expected {
+                return Err(unsupported_type("setval", datatype));
+            }
+        }
+
+        Ok(DataType::Int64)
+    }
+
+    fn is_volatile(&self) -> bool {
+        true
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let name = as_variant!(Data::String, &args[0]);
+        let value = *as_variant!(Data::Int64, &args[1]);
+        let is_called = match args.get(2) {
+            Some(is_called) => *as_variant!(Data::Bool, is_called),
+            None => true,
+        };
+
+        self.0
+            .setval(name, value, is_called, &self.1)
+            .map(Data::Int64)
+    }
+}
@@ -0,0 +1,212 @@
+//! Built-in scalar functions on strings and binary strings.
+
+use super::{check_n_args, scalar::ScalarImpl, unsupported_type};
+use crate::{
+    data::{
+        binary,
+        types::{Data, DataType},
+    },
+    error::{Error, Result},
+    expr::Expr,
+    plan::error::{ExprEvaluationError, PlanError},
+};
+
+/// The formats supported by `encode()` and `decode()`.
+#[derive(Debug, Copy, Clone)]
+enum Encoding {
+    Base64,
+    Hex,
+    Escape,
+}
+
+impl Encoding {
+    fn parse(encoding: &str) -> Result<Self> {
+        match encoding.to_lowercase().as_str() {
+            "base64" => Ok(Encoding::Base64),
+            "hex" => Ok(Encoding::Hex),
+            "escape" => Ok(Encoding::Escape),
+            _ => Err(Error::PlanError(PlanError::ExprEvaluationError(
+                ExprEvaluationError::UnrecognizedEncoding {
+                    encoding: encoding.to_string(),
+                },
+            ))),
+        }
+    }
+
+    /// Check that the format argument of `encode()` and `decode()` is a
+    /// supported one, as errors are not allowed during execution.
+    fn check_arg(arg: &Expr) -> Result<()> {
+        let Expr::Literal(Data::String(encoding)) = arg else {
+            return Err(Error::PlanError(PlanError::NonLiteralEncoding {
+                expr: arg.clone(),
+            }));
+        };
+        Self::parse(encoding)?;
+
+        Ok(())
+    }
+}
+
+/// `length(string)` is the number of characters, `length(bytes)` is the
+/// number of bytes.
+#[derive(Debug, Copy, Clone)]
+pub struct Length;
+
+impl ScalarImpl for Length {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("length", args, 1)?;
+        if !matches!(args[0], DataType::String | DataType::Binary) {
+            return Err(unsupported_type("length", &args[0]));
+        }
+
+        Ok(DataType::Int64)
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let length = match &args[0] {
+            Data::String(str) => str.chars().count(),
+            Data::Binary(bytes) => bytes.len(),
+            _ => unreachable!("type has been checked"),
+        };
+
+        Ok(Data::Int64(length as i64))
+    }
+}
+
+/// `substring(source, start[, count])`, also used by
+/// `SUBSTRING(source FROM start FOR count)`.
+///
+/// Like PostgreSQL, positions start from 1, and the characters (or bytes)
+/// from `start` to `start + count - 1` that exist are returned.
+#[derive(Debug, Copy, Clone)]
+pub struct Substring;
+
+impl ScalarImpl for Substring {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        if args.len() != 2 {
+            check_n_args("substring", args, 3)?;
+        }
+        if !matches!(args[0], DataType::String | DataType::Binary) {
+            return Err(unsupported_type("substring", &args[0]));
+        }
+        for arg in &args[1..] {
+            if arg != &DataType::Int64 {
+                return Err(unsupported_type("substring", arg));
+            }
+        }
+
+        Ok(args[0].clone())
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let Data::Int64(start) = args[1] else {
+            unreachable!("type has been checked")
+        };
+        let end = match args.get(2) {
+            Some(Data::Int64(count)) if *count < 0 => {
+                return Err(Error::PlanError(PlanError::ExprEvaluationError(
+                    ExprEvaluationError::NegativeSubstringLength,
+                )))
+            }
+            Some(Data::Int64(count)) => start.saturating_add(*count),
+            Some(_) => unreachable!("type has been checked"),
+            None => i64::MAX,
+        };
+        // the 0-based range in a source of `len` characters or bytes
+        let range = |len: usize| {
+            let clamp = |pos: i64| (pos.max(1) - 1).min(len as i64) as usize;
+            clamp(start)..clamp(end).max(clamp(start))
+        };
+
+        let data = match &args[0] {
+            Data::String(str) => {
+                let chars = str.chars().collect::<Vec<_>>();
+                Data::String(chars[range(chars.len())].iter().collect())
+            }
+            Data::Binary(bytes) => {
+                Data::Binary(bytes[range(bytes.len())].to_vec())
+            }
+            _ => unreachable!("type has been checked"),
+        };
+
+        Ok(data)
+    }
+}
+
+/// `encode(bytes, format)`, the format is `base64`, `hex` or `escape`.
+#[derive(Debug, Copy, Clone)]
+pub struct Encode;
+
+impl ScalarImpl for Encode {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("encode", args, 2)?;
+        if args[0] != DataType::Binary {
+            return Err(unsupported_type("encode", &args[0]));
+        }
+        if args[1] != DataType::String {
+            return Err(unsupported_type("encode", &args[1]));
+        }
+
+        Ok(DataType::String)
+    }
+
+    fn check_args(&self, args: &[Expr], _types: &[DataType]) -> Result<()> {
+        Encoding::check_arg(&args[1])
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let (Data::Binary(bytes), Data::String(encoding)) =
+            (&args[0], &args[1])
+        else {
+            unreachable!("type has been checked")
+        };
+        let str = match Encoding::parse(encoding)? {
+            Encoding::Base64 => binary::encode_base64(bytes),
+            Encoding::Hex => binary::encode_hex(bytes),
+            Encoding::Escape => binary::escape(bytes),
+        };
+
+        Ok(Data::String(str))
+    }
+}
+
+/// `decode(string, format)`, the reverse of `encode()`.
+#[derive(Debug, Copy, Clone)]
+pub struct Decode;
+
+impl ScalarImpl for Decode {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("decode", args, 2)?;
+        for arg in args {
+            if arg != &DataType::String {
+                return Err(unsupported_type("decode", arg));
+            }
+        }
+
+        Ok(DataType::Binary)
+    }
+
+    fn check_args(&self, args: &[Expr], _types: &[DataType]) -> Result<()> {
+        Encoding::check_arg(&args[1])
+    }
+
+    fn invoke(&self, args: &[Data]) -> Result<Data> {
+        let (Data::String(str), Data::String(encoding)) = (&args[0], &args[1])
+        else {
+            unreachable!("type has been checked")
+        };
+        let bytes = match Encoding::parse(encoding)? {
+            Encoding::Base64 => binary::decode_base64(str),
+            Encoding::Hex => binary::decode_hex(str),
+            Encoding::Escape => binary::unescape(str),
+        };
+
+        bytes.map(Data::Binary).ok_or_else(|| {
+            Error::PlanError(PlanError::ExprEvaluationError(
+                ExprEvaluationError::InvalidEncodedData {
+                    encoding: encoding.to_lowercase(),
+                },
+            ))
+        })
+    }
+}
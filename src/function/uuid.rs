@@ -0,0 +1,30 @@
+//! Built-in scalar functions on UUIDs.
+
+use super::{check_n_args, scalar::ScalarImpl};
+use crate::{
+    data::{
+        types::{Data, DataType},
+        uuid::Uuid,
+    },
+    error::Result,
+};
+
+/// `gen_random_uuid()` returns a random (version 4) UUID.
+#[derive(Debug, Copy, Clone)]
+pub struct GenRandomUuid;
+
+impl ScalarImpl for GenRandomUuid {
+    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        check_n_args("gen_random_uuid", args, 0)?;
+
+        Ok(DataType::Uuid)
+    }
+
+    fn is_volatile(&self) -> bool {
+        true
+    }
+
+    fn invoke(&self, _args: &[Data]) -> Result<Data> {
+        Ok(Data::Uuid(Uuid::new_v4()))
+    }
+}
@@ -0,0 +1,201 @@
+//! Aggregate functions evaluated over window frames.
+
+use super::aggregate::AggregateExpr;
+use crate::{
+    catalog::schema::Schema,
+    data::types::DataType,
+    error::Result,
+    expr::{Expr, SortExpr},
+};
+use derive_more::Display;
+use std::fmt::Formatter;
+
+/// Units of a window frame.
+///
+/// `GROUPS` is not supported.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display)]
+pub enum WindowFrameUnits {
+    #[display(fmt = "ROWS")]
+    Rows,
+    /// Only `UNBOUNDED` and `CURRENT ROW` bounds are supported for `RANGE`.
+    #[display(fmt = "RANGE")]
+    Range,
+}
+
+/// The start or end bound of a window frame.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display)]
+pub enum WindowFrameBound {
+    /// `<N> PRECEDING`, or `UNBOUNDED PRECEDING` if it is `None`.
+    #[display(
+        fmt = "{}",
+        "_0.map_or(String::from(\"UNBOUNDED\"), |n| n.to_string()) + \" PRECEDING\""
+    )]
+    Preceding(Option<usize>),
+    #[display(fmt = "CURRENT ROW")]
+    CurrentRow,
+    /// `<N> FOLLOWING`, or `UNBOUNDED FOLLOWING` if it is `None`.
+    #[display(
+        fmt = "{}",
+        "_0.map_or(String::from(\"UNBOUNDED\"), |n| n.to_string()) + \" FOLLOWING\""
+    )]
+    Following(Option<usize>),
+}
+
+/// A window frame, e.g. `ROWS BETWEEN 1 PRECEDING AND CURRENT ROW`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display)]
+#[display(fmt = "{} BETWEEN {} AND {}", units, start, end)]
+pub struct WindowFrame {
+    pub units: WindowFrameUnits,
+    pub start: WindowFrameBound,
+    pub end: WindowFrameBound,
+}
+
+impl Default for WindowFrame {
+    /// `RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW`, which is the frame
+    /// used when none is specified.
+    fn default() -> Self {
+        Self {
+            units: WindowFrameUnits::Range,
+            start: WindowFrameBound::Preceding(None),
+            end: WindowFrameBound::CurrentRow,
+        }
+    }
+}
+
+impl WindowFrame {
+    /// Return the inclusive range `[start, end]` of the frame for the `idx`th
+    /// row of a sorted partition of `len` rows.
+    ///
+    /// `peers` is the inclusive range of rows that are equal to the `idx`th row
+    /// under `ORDER BY`, it is used by `CURRENT ROW` of `RANGE` frames.
+    ///
+    /// `None` will be returned if the frame is empty.
+    pub fn bounds(
+        &self,
+        idx: usize,
+        len: usize,
+        peers: (usize, usize),
+    ) -> Option<(usize, usize)> {
+        let idx = idx as isize;
+        let last = len as isize - 1;
+        let current = |peer: usize| match self.units {
+            WindowFrameUnits::Rows => idx,
+            WindowFrameUnits::Range => peer as isize,
+        };
+        let offset = |n: usize| isize::try_from(n).unwrap_or(isize::MAX);
+
+        let start = match self.start {
+            WindowFrameBound::Preceding(None) => 0,
+            WindowFrameBound::Preceding(Some(n)) => {
+                idx.saturating_sub(offset(n))
+            }
+            WindowFrameBound::CurrentRow => current(peers.0),
+            WindowFrameBound::Following(Some(n)) => {
+                idx.saturating_add(offset(n))
+            }
+            WindowFrameBound::Following(None) => last + 1,
+        }
+        .max(0);
+        let end = match self.end {
+            WindowFrameBound::Preceding(None) => -1,
+            WindowFrameBound::Preceding(Some(n)) => {
+                idx.saturating_sub(offset(n))
+            }
+            WindowFrameBound::CurrentRow => current(peers.1),
+            WindowFrameBound::Following(Some(n)) => {
+                idx.saturating_add(offset(n))
+            }
+            WindowFrameBound::Following(None) => last,
+        }
+        .min(last);
+
+        (start <= end).then_some((start as usize, end as usize))
+    }
+}
+
+/// An aggregate function evaluated over a window, e.g.
+/// `sum(a) OVER (PARTITION BY b ORDER BY c)`.
+#[derive(Debug, Clone)]
+pub struct WindowExpr {
+    pub aggr: AggregateExpr,
+    pub partition_by: Vec<Expr>,
+    pub order_by: Vec<SortExpr>,
+    /// The frame specified by the user, [`WindowFrame::default()`] will be
+    /// used if it is `None`.
+    pub frame: Option<WindowFrame>,
+}
+
+impl std::fmt::Display for WindowExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} OVER (", self.aggr)?;
+        let mut delim = "";
+        if !self.partition_by.is_empty() {
+            write!(f, "PARTITION BY ")?;
+            for (idx, expr) in self.partition_by.iter().enumerate() {
+                if idx != 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{expr}")?;
+            }
+            delim = " ";
+        }
+        if !self.order_by.is_empty() {
+            write!(f, "{delim}ORDER BY ")?;
+            for (idx, expr) in self.order_by.iter().enumerate() {
+                if idx != 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{expr}")?;
+            }
+            delim = " ";
+        }
+        if let Some(frame) = self.frame {
+            write!(f, "{delim}{frame}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl WindowExpr {
+    /// Return the datatype of this window function against an input of `schema`.
+    pub fn datatype(&self, schema: &Schema) -> Result<DataType> {
+        self.aggr.datatype(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn rows_frame_bounds() {
+        let frame = WindowFrame {
+            units: WindowFrameUnits::Rows,
+            start: WindowFrameBound::Preceding(Some(1)),
+            end: WindowFrameBound::Following(Some(1)),
+        };
+        assert_eq!(frame.bounds(0, 3, (0, 0)), Some((0, 1)));
+        assert_eq!(frame.bounds(1, 3, (1, 1)), Some((0, 2)));
+        assert_eq!(frame.bounds(2, 3, (2, 2)), Some((1, 2)));
+
+        let frame = WindowFrame {
+            units: WindowFrameUnits::Rows,
+            start: WindowFrameBound::Following(Some(1)),
+            end: WindowFrameBound::Following(None),
+        };
+        assert_eq!(frame.bounds(0, 3, (0, 0)), Some((1, 2)));
+        assert_eq!(frame.bounds(2, 3, (2, 2)), None);
+    }
+
+    #[test]
+    fn range_frame_bounds() {
+        let frame = WindowFrame::default();
+        assert_eq!(frame.bounds(0, 4, (0, 1)), Some((0, 1)));
+        assert_eq!(frame.bounds(3, 4, (2, 3)), Some((0, 3)));
+        assert_eq!(
+            frame.to_string(),
+            "RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW"
+        );
+    }
+}
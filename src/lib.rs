@@ -12,18 +12,33 @@ pub mod ctx;
 pub mod data;
 pub mod error;
 pub mod expr;
+pub mod function;
 pub mod logical_plan;
 pub mod meta_cmd;
+pub mod migration;
+pub mod mvcc;
+pub mod optimizer;
+pub mod pattern;
 pub mod physical_plan;
 pub mod plan;
 pub mod repl;
+pub mod sequence;
 pub mod storage_engine;
+pub mod transaction;
 #[macro_use]
 pub mod utils;
 
 mod sqllogictest;
 
-use crate::{ctx::Context, data::tuple::Tuple, error::Result};
+use crate::{
+    ctx::Context,
+    data::tuple::Tuple,
+    error::Result,
+    function::{
+        aggregate::{Accumulator, AggregateFunction},
+        scalar::{ScalarFunction, ScalarImpl},
+    },
+};
 use camino::Utf8Path;
 use derive_more::{Deref, DerefMut};
 use std::ops::Deref;
@@ -40,6 +55,11 @@ impl VinylDB {
         Self(Context::new(data_path).unwrap())
     }
 
+    /// Start another session on the same data, see [`Context::new_session()`].
+    pub fn session(&self) -> VinylDB {
+        Self(self.new_session())
+    }
+
     /// Execute SQL and return the result.
     pub fn sql<S: AsRef<str>>(&mut self, sql: S) -> Result<Vec<Tuple>> {
         let logical_plan = self.create_logical_plan(sql)?;
@@ -48,4 +68,38 @@ impl VinylDB {
 
         Ok(result)
     }
+
+    /// Register a user-defined aggregate function, it can be used in SQL just
+    /// like the built-in ones once registered.
+    pub fn register_aggregate_udf<S, A>(
+        &mut self,
+        name: S,
+        accumulator: A,
+    ) -> Result<()>
+    where
+        S: Into<String>,
+        A: Accumulator + 'static,
+    {
+        let func = AggregateFunction::new(name, accumulator);
+        self.catalog.register_aggregate_function(func)?;
+
+        Ok(())
+    }
+
+    /// Register a user-defined scalar function, it can be used in SQL just
+    /// like the built-in ones once registered.
+    pub fn register_scalar_udf<S, I>(
+        &mut self,
+        name: S,
+        implementation: I,
+    ) -> Result<()>
+    where
+        S: Into<String>,
+        I: ScalarImpl + 'static,
+    {
+        let func = ScalarFunction::new(name, implementation);
+        self.catalog.register_scalar_function(func)?;
+
+        Ok(())
+    }
 }
@@ -1,10 +1,22 @@
-use crate::{catalog::schema::Schema, data::tuple::Tuple, expr::Expr};
+use crate::{
+    catalog::{schema::Schema, Catalog},
+    data::enum_type::EnumType,
+    error::Result,
+    expr::{Expr, SortExpr},
+    function::{aggregate::AggregateExpr, window::WindowExpr},
+    sequence::SequenceOptions,
+    transaction::TransactionControl,
+};
 use std::num::NonZeroUsize;
 
 #[derive(Debug)]
 pub enum LogicalPlan {
     /// A dummy table to implement `SELECT` without `FROM`.
     OneRowPlaceholder,
+    /// A relation that produces no rows, e.g., a filter that is always false.
+    EmptyRelation {
+        schema: Schema,
+    },
     Explain {
         plan: Box<LogicalPlan>,
     },
@@ -13,6 +25,19 @@ pub enum LogicalPlan {
         schema: Schema,
         pk: usize,
         sql: String,
+        /// The sequences implied by `SERIAL` and identity columns.
+        sequences: Vec<(String, SequenceOptions)>,
+    },
+    /// `CREATE SEQUENCE [IF NOT EXISTS] name ...`
+    CreateSequence {
+        name: String,
+        options: SequenceOptions,
+        if_not_exists: bool,
+    },
+    /// `CREATE TYPE name AS ENUM (...)`
+    CreateType {
+        enum_type: EnumType,
+        sql: String,
     },
     Filter {
         predicate: Expr,
@@ -21,13 +46,42 @@ pub enum LogicalPlan {
     TableScan {
         name: String,
     },
+    /// A relation of rows of constant expressions, e.g.,
+    /// `UNNEST(ARRAY[1, 2])`, which are evaluated on execution.
+    Values {
+        rows: Vec<Vec<Expr>>,
+        schema: Schema,
+    },
     ShowTables,
+    /// `SET name = value`
+    SetVariable {
+        name: String,
+        value: String,
+    },
+    /// `BEGIN`, `COMMIT`, `ROLLBACK` or the savepoint statements
+    Transaction {
+        control: TransactionControl,
+    },
+    /// `SHOW name`
+    ShowVariable {
+        name: String,
+    },
     DescribeTable {
         name: String,
     },
+    /// Insert the rows of `input` into `table`, the `i`th column of an
+    /// inserted row is `values[i]`.
     Insert {
         table: String,
-        rows: Vec<Tuple>,
+        values: Vec<InsertValue>,
+        on_conflict: OnConflict,
+        returning: Option<Returning>,
+        input: Box<LogicalPlan>,
+    },
+    /// `ORDER BY`, rows that are equal are kept in the order of `input`.
+    Sort {
+        order_by: Vec<SortExpr>,
+        input: Box<LogicalPlan>,
     },
     Limit {
         offset: Option<NonZeroUsize>,
@@ -39,4 +93,104 @@ pub enum LogicalPlan {
         schema: Schema,
         input: Box<LogicalPlan>,
     },
+    /// Group rows by `group_by` and evaluate `aggr` for every group, output
+    /// the grouping columns followed by the aggregated columns.
+    Aggregate {
+        group_by: Vec<Expr>,
+        aggr: Vec<AggregateExpr>,
+        schema: Schema,
+        input: Box<LogicalPlan>,
+    },
+    /// Evaluate `window` for every row, output the input columns followed by
+    /// a column for every window function.
+    Window {
+        window: Vec<WindowExpr>,
+        schema: Schema,
+        input: Box<LogicalPlan>,
+    },
+}
+
+/// `RETURNING expr, ...` of a DML statement, `exprs` are evaluated against
+/// every affected row, as it is written.
+#[derive(Debug, Clone)]
+pub struct Returning {
+    pub exprs: Vec<Expr>,
+    /// The schema of the returned rows.
+    pub schema: Schema,
+}
+
+/// A column of a row inserted by `INSERT`.
+#[derive(Debug, Clone)]
+pub enum InsertValue {
+    /// The column of the input row at this index, which is converted to the
+    /// type of the inserted column.
+    Input(usize),
+    /// The `DEFAULT` of the inserted column, which is evaluated for every
+    /// row.
+    Default(Expr),
+}
+
+/// What `INSERT` does with a row whose primary key already exists.
+#[derive(Debug, Clone)]
+pub enum OnConflict {
+    /// Fail with a unique violation, this is the default.
+    Error,
+    /// `ON CONFLICT DO NOTHING` or `INSERT OR IGNORE`, skip the row.
+    DoNothing,
+    /// `INSERT OR REPLACE`, replace the existing row with it.
+    Replace,
+    /// `ON CONFLICT (pk) DO UPDATE SET column = expr, ... [WHERE predicate]`,
+    /// update the existing row if `selection` is true.
+    ///
+    /// Expressions are evaluated against `schema`, which has the columns of
+    /// the existing row, named both `column` and `table.column`, followed by
+    /// the ones of the inserted row, named `excluded.column`.
+    DoUpdate {
+        /// (column index, value)
+        assignments: Vec<(usize, Expr)>,
+        selection: Option<Expr>,
+        schema: Schema,
+    },
+}
+
+impl LogicalPlan {
+    /// Return the schema of the rows produced by this plan.
+    ///
+    /// Statements that are not queries, e.g., `CREATE TABLE` and `SHOW TABLES`,
+    /// cannot be the input of other plans, an empty schema is returned for
+    /// them.
+    pub fn schema(&self, catalog: &Catalog) -> Result<Schema> {
+        let schema = match self {
+            LogicalPlan::OneRowPlaceholder
+            | LogicalPlan::Explain { .. }
+            | LogicalPlan::CreateTable { .. }
+            | LogicalPlan::CreateSequence { .. }
+            | LogicalPlan::CreateType { .. }
+            | LogicalPlan::ShowTables
+            | LogicalPlan::SetVariable { .. }
+            | LogicalPlan::Transaction { .. }
+            | LogicalPlan::ShowVariable { .. }
+            | LogicalPlan::DescribeTable { .. }
+            | LogicalPlan::Insert {
+                returning: None, ..
+            } => Schema::empty(),
+            LogicalPlan::Insert {
+                returning: Some(returning),
+                ..
+            } => returning.schema.clone(),
+            LogicalPlan::TableScan { name } => {
+                catalog.get_table(name)?.schema().clone()
+            }
+            LogicalPlan::Filter { input, .. }
+            | LogicalPlan::Sort { input, .. }
+            | LogicalPlan::Limit { input, .. } => input.schema(catalog)?,
+            LogicalPlan::EmptyRelation { schema }
+            | LogicalPlan::Values { schema, .. }
+            | LogicalPlan::Projection { schema, .. }
+            | LogicalPlan::Aggregate { schema, .. }
+            | LogicalPlan::Window { schema, .. } => schema.clone(),
+        };
+
+        Ok(schema)
+    }
 }
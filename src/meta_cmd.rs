@@ -24,6 +24,8 @@ pub enum MetaCmd {
     /// If enabled, the AST of the following parsed SQLs will be printed out.
     Ast(bool),
     Timer(bool),
+    /// Remove the row versions that are no longer visible.
+    Vacuum,
 }
 
 impl FromStr for MetaCmd {
@@ -69,6 +71,10 @@ impl FromStr for MetaCmd {
             return Ok(MetaCmd::Timer(bool));
         }
 
+        if cmd == Self::VACUUM {
+            return Ok(Self::Vacuum);
+        }
+
         Err(MetaCmdError::UnknownCommand {
             cmd: cmd.to_string(),
         })
@@ -79,10 +85,12 @@ impl MetaCmd {
     const HELP: &'static str = ".help";
     const AST: &'static str = ".ast";
     const TIMER: &'static str = ".timer";
+    const VACUUM: &'static str = ".vacuum";
 
     const HELP_USAGE: &'static str = ".help";
     const AST_USAGE: &'static str = ".ast on | off";
     const TIMER_USAGE: &'static str = ".timer on | off";
+    const VACUUM_USAGE: &'static str = ".vacuum";
 
     /// Return the output of `.help`.
     pub fn help_str(&self) -> String {
@@ -104,6 +112,7 @@ impl MetaCmd {
             MetaCmd::Help => Self::HELP_USAGE,
             MetaCmd::Ast(_) => Self::AST_USAGE,
             MetaCmd::Timer(_) => Self::TIMER_USAGE,
+            MetaCmd::Vacuum => Self::VACUUM_USAGE,
         }
     }
 
@@ -113,6 +122,7 @@ impl MetaCmd {
             MetaCmd::Help => "Show help text",
             MetaCmd::Ast(_) => "Print AST for each SQL if enabled",
             MetaCmd::Timer(_) => "Turn SQL timer on or off",
+            MetaCmd::Vacuum => "Remove obsolete row versions",
         }
     }
 
@@ -124,6 +134,10 @@ impl MetaCmd {
             }
             MetaCmd::Ast(val) => ctx.config.show_ast = *val,
             MetaCmd::Timer(val) => ctx.config.timer = *val,
+            MetaCmd::Vacuum => {
+                let removed = ctx.collect_garbage()?;
+                println!("{removed} obsolete row versions removed");
+            }
         }
 
         Ok(())
@@ -0,0 +1,182 @@
+//! Upgrading the on-disk data written by older versions of VinylDB.
+//!
+//! The format version is stored in the default tree of sled, a database
+//! without it is either a new one or a one written before versioning was
+//! introduced (version 0).
+
+use crate::{
+    catalog::Catalog,
+    data::{
+        tuple::Tuple,
+        types::{Data, DataType},
+    },
+    error::{Error, Result},
+    mvcc::version_key,
+    storage_engine::StorageEngine,
+};
+use derive_more::{Display, Error};
+use sled::{transaction::TransactionError, Transactional, Tree};
+
+/// The current format version.
+///
+/// * 0: timestamps are stored in seconds
+/// * 1: timestamps are stored in microseconds
+/// * 2: rows are versioned, see [`crate::mvcc`]
+pub const FORMAT_VERSION: u32 = 2;
+
+/// The key of the format version in the default tree.
+const FORMAT_VERSION_KEY: &str = "format_version";
+
+#[derive(Error, Display, Debug, Copy, Clone)]
+pub enum MigrationError {
+    #[display(
+        fmt = "Data format version {found} is not supported, the latest supported version is {supported}"
+    )]
+    UnsupportedFormatVersion { found: u32, supported: u32 },
+}
+
+/// Upgrade the data in `storage` to [`FORMAT_VERSION`].
+pub fn migrate(storage: &StorageEngine, catalog: &Catalog) -> Result<()> {
+    let default_tree: &Tree = storage.db();
+    let version = match default_tree.get(FORMAT_VERSION_KEY)? {
+        Some(bytes) => u32::from_be_bytes(
+            bytes.as_ref().try_into().expect("should be a u32"),
+        ),
+        // No user tables, this is a new database.
+        None if catalog.tables().len() == 1 => FORMAT_VERSION,
+        None => 0,
+    };
+    if version > FORMAT_VERSION {
+        return Err(Error::MigrationError(
+            MigrationError::UnsupportedFormatVersion {
+                found: version,
+                supported: FORMAT_VERSION,
+            },
+        ));
+    }
+
+    // Tables whose rows are not versioned.
+    let mut tables = Vec::new();
+    if version < 2 {
+        for table in catalog.tables() {
+            if table.name() != crate::catalog::vinyl_table::TABLE_NAME {
+                let tree = storage.get_tree_of_table(table.name())?;
+                tables.push((table, tree));
+            }
+        }
+    }
+
+    // Rows are read in advance, as reading a tree inside a transaction
+    // blocks forever.
+    let mut rows = Vec::with_capacity(tables.len());
+    for (table, tree) in tables.iter() {
+        // Timestamps stored in seconds.
+        let in_seconds = version == 0
+            && table
+                .schema()
+                .column_datatypes()
+                .any(|datatype| datatype == &DataType::Timestamp);
+        let mut table_rows = Vec::new();
+        for res in tree.iter() {
+            let (key, value) = res?;
+            if !in_seconds {
+                table_rows.push((key.clone(), key, value));
+                continue;
+            }
+
+            let tuple = Tuple::decode(value, table.schema());
+            let tuple = Tuple::new(tuple.iter().map(|data| match data {
+                Data::Timestamp(secs) => {
+                    Data::Timestamp(secs.saturating_mul(1_000_000))
+                }
+                data => data.clone(),
+            }));
+            let pk = match tuple.get(table.pk()).unwrap() {
+                pk @ Data::Timestamp(_) => pk.encode().as_ref().into(),
+                _ => key.clone(),
+            };
+            table_rows.push((key, pk, tuple.encode().into()));
+        }
+        rows.push(table_rows);
+    }
+
+    // Upgrade all the tables and the version in one transaction, so that
+    // tables are never upgraded twice.
+    let mut trees = vec![default_tree];
+    trees.extend(tables.iter().map(|(_, tree)| tree));
+    let res: std::result::Result<(), TransactionError<()>> =
+        trees.as_slice().transaction(|trees| {
+            for (table_rows, tx_tree) in rows.iter().zip(&trees[1..]) {
+                // Old keys are all removed first, so that none of the new
+                // keys is removed.
+                for (key, _, _) in table_rows {
+                    tx_tree.remove(key)?;
+                }
+                // Existing rows are committed at timestamp 0.
+                for (_, pk, value) in table_rows {
+                    tx_tree.insert(version_key(pk, 0), value)?;
+                }
+            }
+            trees[0].insert(
+                FORMAT_VERSION_KEY,
+                &FORMAT_VERSION.to_be_bytes()[..],
+            )?;
+
+            Ok(())
+        });
+
+    res.map_err(|err| match err {
+        TransactionError::Storage(err) => Error::SledError(err),
+        TransactionError::Abort(()) => unreachable!("never aborted"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VinylDB;
+    use camino_tempfile::Utf8TempDir;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn migrate_timestamps_in_seconds() {
+        let dir = Utf8TempDir::new().unwrap();
+        let mut db = VinylDB::new(dir.path());
+        db.sql("CREATE TABLE t (ts TIMESTAMP, id INT64)").unwrap();
+        // Make it look like a version 0 database, which stores
+        // '2024-01-01 00:00:01' in seconds.
+        db.storage.db().remove(FORMAT_VERSION_KEY).unwrap();
+        let row = Tuple::new([Data::Timestamp(1704067201), Data::Int64(1)]);
+        db.storage
+            .get_tree_of_table("t")
+            .unwrap()
+            .insert(row.get(0).unwrap().encode().as_ref(), row.encode())
+            .unwrap();
+
+        let micros = 1_704_067_201_000_000;
+        let expected = vec![Tuple::new([Data::Timestamp(micros)])];
+        migrate(&db.storage, &db.catalog).unwrap();
+        assert_eq!(db.sql("SELECT ts FROM t").unwrap(), expected);
+
+        // Migrated data are not migrated again.
+        migrate(&db.storage, &db.catalog).unwrap();
+        assert_eq!(db.sql("SELECT ts FROM t").unwrap(), expected);
+    }
+
+    #[test]
+    fn newer_format_version() {
+        let dir = Utf8TempDir::new().unwrap();
+        let db = VinylDB::new(dir.path());
+        db.storage
+            .db()
+            .insert(FORMAT_VERSION_KEY, &(FORMAT_VERSION + 1).to_be_bytes())
+            .unwrap();
+
+        assert!(matches!(
+            migrate(&db.storage, &db.catalog),
+            Err(Error::MigrationError(
+                MigrationError::UnsupportedFormatVersion { .. }
+            ))
+        ));
+    }
+}
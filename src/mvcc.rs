@@ -0,0 +1,303 @@
+//! Multi-version concurrency control.
+//!
+//! A row is never overwritten in place, every commit writes a new version of
+//! it, keyed by its primary key followed by the commit timestamp. Versions of
+//! a row are adjacent in a tree, from the newest to the oldest.
+//!
+//! Reads are done against a [`Snapshot`], which sees the latest versions
+//! committed at or before its timestamp, so a scan is never affected by
+//! commits made while it is running.
+//!
+//! Versions that are shadowed by a newer one visible to all the snapshots are
+//! obsolete, they are removed by [`collect_garbage()`].
+
+use sled::{IVec, Tree};
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+};
+
+/// Commit timestamps, data migrated from unversioned tables are committed at
+/// 0.
+pub type Timestamp = u64;
+
+/// The key of the latest commit timestamp in the default tree.
+pub const TIMESTAMP_KEY: &str = "mvcc_timestamp";
+
+/// Return the key of the version of `pk` committed at `ts`.
+///
+/// The timestamp is inverted and stored in big-endian, so that newer versions
+/// come first.
+pub fn version_key(pk: &[u8], ts: Timestamp) -> IVec {
+    let mut key = Vec::with_capacity(pk.len() + std::mem::size_of::<u64>());
+    key.extend_from_slice(pk);
+    key.extend_from_slice(&(Timestamp::MAX - ts).to_be_bytes());
+    key.into()
+}
+
+/// Split a key returned by [`version_key()`] into the primary key and the
+/// commit timestamp.
+pub fn split_version_key(key: &[u8]) -> (&[u8], Timestamp) {
+    let (pk, ts) = key.split_at(key.len() - std::mem::size_of::<u64>());
+    let ts = Timestamp::from_be_bytes(ts.try_into().expect("should be a u64"));
+
+    (pk, Timestamp::MAX - ts)
+}
+
+/// Return the latest version of `pk` committed at or before `ts`.
+pub fn get_version(
+    tree: &Tree,
+    pk: &[u8],
+    ts: Timestamp,
+) -> sled::Result<Option<(Timestamp, IVec)>> {
+    let Some((key, value)) =
+        tree.range(version_key(pk, ts)..).next().transpose()?
+    else {
+        return Ok(None);
+    };
+    let (found, ts) = split_version_key(&key);
+
+    Ok((found == pk).then_some((ts, value)))
+}
+
+/// Timestamps shared by all the transactions.
+#[derive(Debug)]
+pub struct Mvcc {
+    /// The latest commit timestamp, whose data are all written.
+    latest: AtomicU64,
+    /// Commits are serialized by this lock.
+    commit: Mutex<()>,
+    /// Timestamps of the snapshots in use => how many of them.
+    snapshots: Mutex<BTreeMap<Timestamp, usize>>,
+}
+
+impl Mvcc {
+    /// Create a new [`Mvcc`], whose latest commit is at `latest`.
+    pub fn new(latest: Timestamp) -> Self {
+        Self {
+            latest: AtomicU64::new(latest),
+            commit: Mutex::new(()),
+            snapshots: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Take a snapshot of the latest commit.
+    pub fn snapshot(self: &Arc<Self>) -> Snapshot {
+        let mut snapshots = self.snapshots.lock().unwrap();
+        let ts = self.latest.load(Ordering::SeqCst);
+        *snapshots.entry(ts).or_default() += 1;
+
+        Snapshot {
+            mvcc: Arc::clone(self),
+            ts,
+        }
+    }
+
+    /// Start a commit, the returned guard has to be held until the commit is
+    /// either done, see [`Mvcc::publish()`], or abandoned.
+    pub fn start_commit(&self) -> (MutexGuard<'_, ()>, Timestamp) {
+        let guard = self.commit.lock().unwrap();
+        let ts = self.latest.load(Ordering::SeqCst) + 1;
+
+        (guard, ts)
+    }
+
+    /// Make the data committed at `ts` visible to new snapshots, it should be
+    /// called with the guard returned by [`Mvcc::start_commit()`].
+    pub fn publish(&self, _guard: MutexGuard<'_, ()>, ts: Timestamp) {
+        self.latest.store(ts, Ordering::SeqCst);
+    }
+}
+
+/// A consistent view of the data committed at or before a timestamp.
+///
+/// Versions it can see are kept until it is dropped.
+#[derive(Debug)]
+pub struct Snapshot {
+    mvcc: Arc<Mvcc>,
+    ts: Timestamp,
+}
+
+impl Snapshot {
+    pub fn ts(&self) -> Timestamp {
+        self.ts
+    }
+
+    /// Scan `tree` in this snapshot, return the visible version of every row
+    /// as `(primary key, value)`.
+    pub fn scan(self, tree: &Tree) -> SnapshotIter {
+        SnapshotIter {
+            iter: tree.iter(),
+            snapshot: self,
+            last_pk: None,
+        }
+    }
+}
+
+impl Clone for Snapshot {
+    fn clone(&self) -> Self {
+        let mut snapshots = self.mvcc.snapshots.lock().unwrap();
+        *snapshots.get_mut(&self.ts).expect("should be registered") += 1;
+
+        Self {
+            mvcc: Arc::clone(&self.mvcc),
+            ts: self.ts,
+        }
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        let mut snapshots = self.mvcc.snapshots.lock().unwrap();
+        let count = snapshots.get_mut(&self.ts).expect("should be registered");
+        *count -= 1;
+        if *count == 0 {
+            snapshots.remove(&self.ts);
+        }
+    }
+}
+
+/// See [`Snapshot::scan()`].
+pub struct SnapshotIter {
+    iter: sled::Iter,
+    snapshot: Snapshot,
+    /// The primary key whose visible version has been returned.
+    last_pk: Option<Vec<u8>>,
+}
+
+impl std::fmt::Debug for SnapshotIter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapshotIter")
+            .field("snapshot", &self.snapshot)
+            .field("last_pk", &self.last_pk)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Iterator for SnapshotIter {
+    type Item = sled::Result<(IVec, IVec)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (key, value) = match self.iter.next()? {
+                Ok(kv) => kv,
+                Err(e) => return Some(Err(e)),
+            };
+            let (pk, ts) = split_version_key(&key);
+            if ts > self.snapshot.ts || self.last_pk.as_deref() == Some(pk) {
+                continue;
+            }
+            self.last_pk = Some(pk.to_vec());
+
+            return Some(Ok((IVec::from(pk), value)));
+        }
+    }
+}
+
+/// Remove the obsolete versions in `trees`, return how many versions are
+/// removed.
+///
+/// A version is obsolete if it is not the newest one of its row, and it is
+/// not visible to any snapshot in use.
+pub fn collect_garbage<'a>(
+    mvcc: &Mvcc,
+    trees: impl IntoIterator<Item = &'a Tree>,
+) -> sled::Result<usize> {
+    // Snapshots taken from now on see the newest versions, which are kept.
+    let snapshots = mvcc.snapshots.lock().unwrap().clone();
+    let mut removed = 0;
+    for tree in trees {
+        // The primary key and the timestamp of the previous, i.e., newer,
+        // version.
+        let mut newer: Option<(Vec<u8>, Timestamp)> = None;
+        for res in tree.iter().keys() {
+            let key = res?;
+            let (pk, ts) = split_version_key(&key);
+            match &newer {
+                Some((newer_pk, newer_ts))
+                    if newer_pk == pk
+                        && snapshots.range(ts..*newer_ts).next().is_none() =>
+                {
+                    tree.remove(&key)?;
+                    removed += 1;
+                }
+                _ => {}
+            }
+            newer = Some((pk.to_vec(), ts));
+        }
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn versions_are_ordered_from_the_newest() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        for (pk, ts) in [("a", 1), ("a", 3), ("b", 2), ("a", 2)] {
+            db.insert(
+                version_key(pk.as_bytes(), ts),
+                format!("{pk}{ts}").as_bytes(),
+            )
+            .unwrap();
+        }
+        let keys = db
+            .iter()
+            .keys()
+            .map(|key| {
+                let key = key.unwrap();
+                let (pk, ts) = split_version_key(&key);
+                (String::from_utf8(pk.to_vec()).unwrap(), ts)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            [
+                ("a".into(), 3),
+                ("a".into(), 2),
+                ("a".into(), 1),
+                ("b".into(), 2)
+            ]
+        );
+
+        let get = |pk: &str, ts| {
+            get_version(&db, pk.as_bytes(), ts)
+                .unwrap()
+                .map(|(ts, value)| {
+                    (ts, String::from_utf8(value.to_vec()).unwrap())
+                })
+        };
+        assert_eq!(get("a", 0), None);
+        assert_eq!(get("a", 2), Some((2, "a2".into())));
+        assert_eq!(get("a", 10), Some((3, "a3".into())));
+        assert_eq!(get("b", 1), None);
+
+        let mvcc = Arc::new(Mvcc::new(3));
+        let scan = |snapshot: Snapshot| {
+            snapshot
+                .scan(&db)
+                .map(|res| String::from_utf8(res.unwrap().1.to_vec()).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(scan(mvcc.snapshot()), ["a3", "b2"]);
+
+        // versions visible to a snapshot are kept
+        let old = Snapshot {
+            mvcc: Arc::clone(&mvcc),
+            ts: 1,
+        };
+        mvcc.snapshots.lock().unwrap().insert(1, 1);
+        assert_eq!(collect_garbage(&mvcc, [&*db]).unwrap(), 1);
+        assert_eq!(scan(old), ["a1"]);
+        assert_eq!(collect_garbage(&mvcc, [&*db]).unwrap(), 1);
+        assert_eq!(db.len(), 2);
+        assert_eq!(scan(mvcc.snapshot()), ["a3", "b2"]);
+    }
+}
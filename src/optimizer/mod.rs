@@ -0,0 +1,13 @@
+//! Rewriting a [`LogicalPlan`] to an equivalent one that is cheaper to execute.
+
+pub mod simplify;
+
+use crate::{catalog::Catalog, error::Result, logical_plan::LogicalPlan};
+
+/// Apply all the optimizations to `plan`.
+pub(crate) fn optimize(
+    catalog: &Catalog,
+    plan: LogicalPlan,
+) -> Result<LogicalPlan> {
+    simplify::simplify_plan(catalog, plan)
+}
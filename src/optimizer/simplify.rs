@@ -0,0 +1,287 @@
+//! Simplifying the expressions in a [`LogicalPlan`] so that they won't be
+//! evaluated over and over again for every row.
+
+use crate::{
+    catalog::Catalog,
+    data::types::Data,
+    error::Result,
+    expr::{Expr, Operator, SortExpr},
+    logical_plan::LogicalPlan,
+};
+
+/// Simplify all the expressions in `plan`, then:
+///
+/// 1. remove filters that are always true
+/// 2. replace filters that are always false with an empty relation
+pub fn simplify_plan(
+    catalog: &Catalog,
+    plan: LogicalPlan,
+) -> Result<LogicalPlan> {
+    let plan = match plan {
+        LogicalPlan::Explain { plan } => LogicalPlan::Explain {
+            plan: Box::new(simplify_plan(catalog, Box::into_inner(plan))?),
+        },
+        LogicalPlan::Filter { predicate, input } => {
+            let input = simplify_plan(catalog, Box::into_inner(input))?;
+            match simplify_expr(predicate)? {
+                Expr::Literal(Data::Bool(true)) => input,
+                Expr::Literal(Data::Bool(false)) => {
+                    LogicalPlan::EmptyRelation {
+                        schema: input.schema(catalog)?,
+                    }
+                }
+                predicate => LogicalPlan::Filter {
+                    predicate,
+                    input: Box::new(input),
+                },
+            }
+        }
+        LogicalPlan::Sort { order_by, input } => LogicalPlan::Sort {
+            order_by: simplify_sort_exprs(order_by)?,
+            input: Box::new(simplify_plan(catalog, Box::into_inner(input))?),
+        },
+        LogicalPlan::Limit {
+            offset,
+            limit,
+            input,
+        } => LogicalPlan::Limit {
+            offset,
+            limit,
+            input: Box::new(simplify_plan(catalog, Box::into_inner(input))?),
+        },
+        LogicalPlan::Projection {
+            expr,
+            schema,
+            input,
+        } => LogicalPlan::Projection {
+            expr: simplify_exprs(expr)?,
+            schema,
+            input: Box::new(simplify_plan(catalog, Box::into_inner(input))?),
+        },
+        LogicalPlan::Aggregate {
+            group_by,
+            mut aggr,
+            schema,
+            input,
+        } => {
+            for aggr in aggr.iter_mut() {
+                aggr.args = simplify_exprs(std::mem::take(&mut aggr.args))?;
+            }
+
+            LogicalPlan::Aggregate {
+                group_by: simplify_exprs(group_by)?,
+                aggr,
+                schema,
+                input: Box::new(simplify_plan(
+                    catalog,
+                    Box::into_inner(input),
+                )?),
+            }
+        }
+        LogicalPlan::Window {
+            mut window,
+            schema,
+            input,
+        } => {
+            for window in window.iter_mut() {
+                window.aggr.args =
+                    simplify_exprs(std::mem::take(&mut window.aggr.args))?;
+                window.partition_by =
+                    simplify_exprs(std::mem::take(&mut window.partition_by))?;
+                window.order_by =
+                    simplify_sort_exprs(std::mem::take(&mut window.order_by))?;
+            }
+
+            LogicalPlan::Window {
+                window,
+                schema,
+                input: Box::new(simplify_plan(
+                    catalog,
+                    Box::into_inner(input),
+                )?),
+            }
+        }
+        LogicalPlan::Insert {
+            table,
+            values,
+            on_conflict,
+            returning,
+            input,
+        } => LogicalPlan::Insert {
+            table,
+            values,
+            on_conflict,
+            returning,
+            input: Box::new(simplify_plan(catalog, Box::into_inner(input))?),
+        },
+        plan @ (LogicalPlan::OneRowPlaceholder
+        | LogicalPlan::EmptyRelation { .. }
+        | LogicalPlan::CreateTable { .. }
+        | LogicalPlan::CreateSequence { .. }
+        | LogicalPlan::CreateType { .. }
+        | LogicalPlan::Transaction { .. }
+        | LogicalPlan::TableScan { .. }
+        | LogicalPlan::Values { .. }
+        | LogicalPlan::ShowTables
+        | LogicalPlan::SetVariable { .. }
+        | LogicalPlan::ShowVariable { .. }
+        | LogicalPlan::DescribeTable { .. }) => plan,
+    };
+
+    Ok(plan)
+}
+
+fn simplify_exprs(exprs: Vec<Expr>) -> Result<Vec<Expr>> {
+    exprs.into_iter().map(simplify_expr).collect()
+}
+
+fn simplify_sort_exprs(sort_exprs: Vec<SortExpr>) -> Result<Vec<SortExpr>> {
+    sort_exprs
+        .into_iter()
+        .map(|sort| {
+            Ok(SortExpr {
+                expr: simplify_expr(sort.expr)?,
+                asc: sort.asc,
+            })
+        })
+        .collect()
+}
+
+/// Simplify `expr` bottom-up:
+///
+/// 1. fold constant subexpressions into literals, unless they are volatile,
+///    `COLLATE` is kept as a literal has no collation
+/// 2. `x AND true` and `x OR false` are simplified to `x`
+pub fn simplify_expr(expr: Expr) -> Result<Expr> {
+    let expr = expr.map_children(simplify_expr)?;
+    if !matches!(expr, Expr::Literal(_) | Expr::Collate { .. })
+        && expr.is_constant()
+        && !expr.is_volatile()
+    {
+        return Ok(Expr::Literal(expr.evaluate_constant_expr()?));
+    }
+
+    let expr = match expr {
+        Expr::BinaryExpr { left, op, right } => {
+            let identity = match op {
+                Operator::And => Some(Data::Bool(true)),
+                Operator::Or => Some(Data::Bool(false)),
+                _ => None,
+            };
+            match (identity, Box::into_inner(left), Box::into_inner(right)) {
+                (Some(identity), Expr::Literal(data), other)
+                | (Some(identity), other, Expr::Literal(data))
+                    if data == identity =>
+                {
+                    other
+                }
+                (_, left, right) => Expr::BinaryExpr {
+                    left: Box::new(left),
+                    op,
+                    right: Box::new(right),
+                },
+            }
+        }
+        expr => expr,
+    };
+
+    Ok(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::function::{scalar::ScalarFunctionExpr, FunctionRegistry};
+    use pretty_assertions::assert_eq;
+
+    fn binary(left: Expr, op: Operator, right: Expr) -> Expr {
+        Expr::BinaryExpr {
+            left: Box::new(left),
+            op,
+            right: Box::new(right),
+        }
+    }
+
+    fn column() -> Expr {
+        Expr::Column("a".into())
+    }
+
+    fn bool(bool: bool) -> Expr {
+        Expr::Literal(Data::Bool(bool))
+    }
+
+    fn int(int: i64) -> Expr {
+        Expr::Literal(Data::Int64(int))
+    }
+
+    #[test]
+    fn fold_constants() {
+        // a > 1 + 2
+        let expr = binary(
+            column(),
+            Operator::Gt,
+            binary(int(1), Operator::Plus, int(2)),
+        );
+        assert_eq!(simplify_expr(expr).unwrap().to_string(), "a > 3");
+
+        // 1 + 2 = 3 AND 4 > 5
+        let expr = binary(
+            binary(
+                binary(int(1), Operator::Plus, int(2)),
+                Operator::Eq,
+                int(3),
+            ),
+            Operator::And,
+            binary(int(4), Operator::Gt, int(5)),
+        );
+        assert_eq!(simplify_expr(expr).unwrap().to_string(), "false");
+    }
+
+    #[test]
+    fn simplify_boolean_identity() {
+        // a AND true
+        let expr = binary(column(), Operator::And, bool(true));
+        assert_eq!(simplify_expr(expr).unwrap().to_string(), "a");
+
+        // false OR a
+        let expr = binary(bool(false), Operator::Or, column());
+        assert_eq!(simplify_expr(expr).unwrap().to_string(), "a");
+
+        // a AND false
+        let expr = binary(column(), Operator::And, bool(false));
+        assert_eq!(simplify_expr(expr).unwrap().to_string(), "false");
+
+        // true OR a
+        let expr = binary(bool(true), Operator::Or, column());
+        assert_eq!(simplify_expr(expr).unwrap().to_string(), "true");
+
+        // a OR true AND false, i.e., a OR (true AND false)
+        let expr = binary(
+            column(),
+            Operator::Or,
+            binary(bool(true), Operator::And, bool(false)),
+        );
+        assert_eq!(simplify_expr(expr).unwrap().to_string(), "a");
+    }
+
+    #[test]
+    fn volatile_exprs_are_not_folded() {
+        let functions = FunctionRegistry::default();
+        let uuid = || {
+            Expr::ScalarFunction(ScalarFunctionExpr {
+                func: functions
+                    .scalar_function("gen_random_uuid")
+                    .unwrap()
+                    .clone(),
+                args: vec![],
+            })
+        };
+
+        // gen_random_uuid() = gen_random_uuid()
+        let expr = binary(uuid(), Operator::Eq, uuid());
+        assert_eq!(
+            simplify_expr(expr).unwrap().to_string(),
+            "gen_random_uuid() = gen_random_uuid()"
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! String pattern matching: `LIKE`, `ILIKE`, `SIMILAR TO` and POSIX regular
+//! expressions.
+//!
+//! All of them are translated to a [`Regex`], which is compiled only once
+//! during planning.
+
+use crate::plan::error::{PlanError, PlanResult};
+use regex::{Regex, RegexBuilder};
+use std::fmt::{Display, Formatter};
+
+/// The default escape character of `LIKE` and `SIMILAR TO`.
+const DEFAULT_ESCAPE: char = '\\';
+
+/// Kinds of patterns.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PatternKind {
+    /// `LIKE`, `%` matches any sequence of characters and `_` matches any single
+    /// character.
+    Like,
+    /// Case-insensitive `LIKE`
+    ILike,
+    /// `SIMILAR TO`, the `LIKE` wildcards plus regular expression operators.
+    SimilarTo,
+    /// `~`, POSIX regular expression.
+    Regex,
+    /// `~*`, case-insensitive POSIX regular expression.
+    IRegex,
+}
+
+impl PatternKind {
+    /// Return the SQL operator.
+    pub fn operator(&self, negated: bool) -> &'static str {
+        match (self, negated) {
+            (PatternKind::Like, false) => "LIKE",
+            (PatternKind::Like, true) => "NOT LIKE",
+            (PatternKind::ILike, false) => "ILIKE",
+            (PatternKind::ILike, true) => "NOT ILIKE",
+            (PatternKind::SimilarTo, false) => "SIMILAR TO",
+            (PatternKind::SimilarTo, true) => "NOT SIMILAR TO",
+            (PatternKind::Regex, false) => "~",
+            (PatternKind::Regex, true) => "!~",
+            (PatternKind::IRegex, false) => "~*",
+            (PatternKind::IRegex, true) => "!~*",
+        }
+    }
+}
+
+/// A compiled pattern.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    kind: PatternKind,
+    /// The pattern written by the user.
+    pattern: String,
+    /// The escape character specified by `ESCAPE`.
+    escape: Option<char>,
+    regex: Regex,
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}'", self.pattern.replace('\'', "''"))?;
+        if let Some(escape) = self.escape {
+            write!(f, " ESCAPE '{escape}'")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Pattern {
+    /// Compile a [`Pattern`].
+    pub fn new(
+        kind: PatternKind,
+        pattern: String,
+        escape: Option<char>,
+    ) -> PlanResult<Self> {
+        let invalid = |reason: String| PlanError::InvalidPattern {
+            pattern: pattern.clone(),
+            reason,
+        };
+
+        let escape_char = escape.unwrap_or(DEFAULT_ESCAPE);
+        let regex = match kind {
+            PatternKind::Like | PatternKind::ILike => {
+                like_to_regex(&pattern, escape_char).map_err(invalid)?
+            }
+            PatternKind::SimilarTo => {
+                similar_to_to_regex(&pattern, escape_char).map_err(invalid)?
+            }
+            PatternKind::Regex | PatternKind::IRegex => pattern.clone(),
+        };
+        let regex = RegexBuilder::new(&regex)
+            .case_insensitive(matches!(
+                kind,
+                PatternKind::ILike | PatternKind::IRegex
+            ))
+            .dot_matches_new_line(true)
+            .build()
+            .map_err(|e| invalid(e.to_string()))?;
+
+        Ok(Self {
+            kind,
+            pattern,
+            escape,
+            regex,
+        })
+    }
+
+    pub fn kind(&self) -> PatternKind {
+        self.kind
+    }
+
+    /// Return true if `str` matches this pattern.
+    ///
+    /// `LIKE` and `SIMILAR TO` have to match the whole string, while regular
+    /// expressions can match any substring.
+    pub fn is_match(&self, str: &str) -> bool {
+        self.regex.is_match(str)
+    }
+}
+
+/// Translate a `LIKE` pattern to an anchored regular expression.
+fn like_to_regex(pattern: &str, escape: char) -> Result<String, String> {
+    let mut regex = String::from("^");
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c == escape => {
+                let escaped = chars.next().ok_or_else(|| {
+                    String::from("pattern must not end with escape character")
+                })?;
+                regex
+                    .push_str(&regex::escape(escaped.encode_utf8(&mut [0; 4])));
+            }
+            '%' => regex.push_str(".*"),
+            '_' => regex.push('.'),
+            c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    regex.push('$');
+
+    Ok(regex)
+}
+
+/// Translate a `SIMILAR TO` pattern to an anchored regular expression.
+///
+/// Besides the `LIKE` wildcards, `|`, `*`, `+`, `?`, `{m,n}`, `()` and bracket
+/// expressions are regular expression operators, everything else (including
+/// `.`) matches itself.
+fn similar_to_to_regex(pattern: &str, escape: char) -> Result<String, String> {
+    let mut regex = String::from("^(?:");
+    let mut in_bracket = false;
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c == escape => {
+                let escaped = chars.next().ok_or_else(|| {
+                    String::from("pattern must not end with escape character")
+                })?;
+                regex
+                    .push_str(&regex::escape(escaped.encode_utf8(&mut [0; 4])));
+            }
+            c if in_bracket => {
+                if c == ']' {
+                    in_bracket = false;
+                }
+                regex.push(c);
+            }
+            '[' => {
+                in_bracket = true;
+                regex.push(c);
+            }
+            '%' => regex.push_str(".*"),
+            '_' => regex.push('.'),
+            '|' | '*' | '+' | '?' | '{' | '}' | '(' | ')' => regex.push(c),
+            c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    if in_bracket {
+        return Err(String::from("unterminated bracket expression"));
+    }
+    regex.push_str(")$");
+
+    Ok(regex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(kind: PatternKind, pattern: &str, str: &str) -> bool {
+        Pattern::new(kind, pattern.to_string(), None)
+            .unwrap()
+            .is_match(str)
+    }
+
+    #[test]
+    fn like() {
+        assert!(matches(PatternKind::Like, "abc", "abc"));
+        assert!(!matches(PatternKind::Like, "abc", "abcd"));
+        assert!(matches(PatternKind::Like, "a%", "abcd"));
+        assert!(matches(PatternKind::Like, "%", ""));
+        assert!(matches(PatternKind::Like, "_b_", "abc"));
+        assert!(!matches(PatternKind::Like, "_b_", "ab"));
+        assert!(matches(PatternKind::Like, "a.c", "a.c"));
+        assert!(!matches(PatternKind::Like, "a.c", "abc"));
+        assert!(matches(PatternKind::Like, "a%", "a\nb"));
+        assert!(!matches(PatternKind::Like, "A%", "abc"));
+        assert!(matches(PatternKind::ILike, "A%", "abc"));
+    }
+
+    #[test]
+    fn like_escape() {
+        assert!(matches(PatternKind::Like, "100\\%", "100%"));
+        assert!(!matches(PatternKind::Like, "100\\%", "1000"));
+
+        let pattern =
+            Pattern::new(PatternKind::Like, "a#_%".into(), Some('#')).unwrap();
+        assert!(pattern.is_match("a_b"));
+        assert!(!pattern.is_match("ab"));
+
+        assert!(
+            Pattern::new(PatternKind::Like, "a#".into(), Some('#')).is_err()
+        );
+    }
+
+    #[test]
+    fn similar_to() {
+        assert!(matches(PatternKind::SimilarTo, "abc", "abc"));
+        assert!(matches(PatternKind::SimilarTo, "a", "a"));
+        assert!(matches(PatternKind::SimilarTo, "%(b|d)%", "abc"));
+        assert!(!matches(PatternKind::SimilarTo, "(b|c)%", "abc"));
+        assert!(matches(PatternKind::SimilarTo, "a[0-9]+", "a123"));
+        assert!(!matches(PatternKind::SimilarTo, "a.c", "abc"));
+        assert!(
+            Pattern::new(PatternKind::SimilarTo, "[a".into(), None).is_err()
+        );
+    }
+
+    #[test]
+    fn regex() {
+        assert!(matches(PatternKind::Regex, "b", "abc"));
+        assert!(matches(PatternKind::Regex, "^a.c$", "abc"));
+        assert!(!matches(PatternKind::Regex, "^A", "abc"));
+        assert!(matches(PatternKind::IRegex, "^A", "abc"));
+        assert!(Pattern::new(PatternKind::Regex, "(".into(), None).is_err());
+    }
+}
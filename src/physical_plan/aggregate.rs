@@ -0,0 +1,113 @@
+use crate::{
+    catalog::schema::Schema,
+    ctx::Context,
+    data::{
+        tuple::{Tuple, TupleStream},
+        types::Data,
+    },
+    error::Result,
+    expr::Expr,
+    function::aggregate::AggregateExpr,
+    physical_plan::Executor,
+};
+use indexmap::IndexMap;
+use std::ops::Deref;
+
+#[derive(Debug)]
+pub struct AggregateExec {
+    group_by: Vec<Expr>,
+    aggr: Vec<AggregateExpr>,
+    schema: Schema,
+    input: Box<dyn Executor>,
+}
+
+impl AggregateExec {
+    pub fn new(
+        group_by: Vec<Expr>,
+        aggr: Vec<AggregateExpr>,
+        schema: Schema,
+        input: Box<dyn Executor>,
+    ) -> Self {
+        Self {
+            group_by,
+            aggr,
+            schema,
+            input,
+        }
+    }
+
+    fn init_states(&self) -> Vec<Vec<Data>> {
+        self.aggr
+            .iter()
+            .map(|aggr| aggr.func.accumulator().init())
+            .collect()
+    }
+}
+
+impl Executor for AggregateExec {
+    fn schema(&self) -> Schema {
+        self.schema.clone()
+    }
+
+    fn execute(&self, ctx: &mut Context) -> Result<TupleStream> {
+        let input_schema = self.input.schema();
+        let stream = self.input.execute(ctx)?;
+
+        // Groups are keyed by the encoded grouping columns under their
+        // collations, and ordered by their first appearances, which are the
+        // values of the grouping columns.
+        let collations = self
+            .group_by
+            .iter()
+            .map(|expr| expr.collation(&input_schema))
+            .collect::<Vec<_>>();
+        let mut groups = IndexMap::new();
+        for tuple in stream {
+            let tuple = tuple?;
+            let key = Tuple::new(Expr::evaluate_batch(
+                &self.group_by,
+                &input_schema,
+                &tuple,
+            )?);
+            let encoded = Tuple::new(
+                key.iter()
+                    .zip(collations.iter())
+                    .map(|(data, collation)| collation.apply(data.clone())),
+            )
+            .encode();
+            let (_, states) = groups
+                .entry(encoded)
+                .or_insert_with(|| (key, self.init_states()));
+
+            for (aggr, state) in self.aggr.iter().zip(states.iter_mut()) {
+                let args =
+                    Expr::evaluate_batch(&aggr.args, &input_schema, &tuple)?;
+                aggr.func.accumulator().update(state, &args)?;
+            }
+        }
+
+        // Without `GROUP BY`, there is always exactly 1 group even though the
+        // input is empty.
+        if groups.is_empty() && self.group_by.is_empty() {
+            groups.insert(
+                Tuple::empty().encode(),
+                (Tuple::empty(), self.init_states()),
+            );
+        }
+
+        let mut ret = Vec::with_capacity(groups.len());
+        for (_, (key, states)) in groups {
+            let mut row = key.iter().cloned().collect::<Vec<_>>();
+            for (aggr, state) in self.aggr.iter().zip(states.iter()) {
+                row.push(aggr.func.accumulator().finalize(state)?);
+            }
+            ret.push(Tuple::new(row));
+        }
+
+        Ok(Box::new(ret.into_iter().map(Ok)))
+    }
+
+    fn next(&self) -> Option<&dyn Executor> {
+        Some(self.input.deref())
+    }
+}
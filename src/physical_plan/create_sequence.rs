@@ -0,0 +1,46 @@
+use crate::{
+    catalog::schema::Schema, ctx::Context, data::tuple::TupleStream,
+    error::Result, physical_plan::Executor, sequence::SequenceOptions,
+};
+
+#[derive(Debug)]
+pub struct CreateSequenceExec {
+    name: String,
+    options: SequenceOptions,
+    if_not_exists: bool,
+}
+
+impl CreateSequenceExec {
+    pub fn new(
+        name: String,
+        options: SequenceOptions,
+        if_not_exists: bool,
+    ) -> Self {
+        Self {
+            name,
+            options,
+            if_not_exists,
+        }
+    }
+}
+
+impl Executor for CreateSequenceExec {
+    fn schema(&self) -> Schema {
+        Schema::empty()
+    }
+
+    /// Sequences are not transactional, so it is allowed in a transaction,
+    /// and it is not undone by a rollback.
+    fn execute(&self, ctx: &mut Context) -> Result<TupleStream> {
+        let sequences = ctx.storage.sequences();
+        if !(self.if_not_exists && sequences.contains(&self.name)?) {
+            sequences.create(&self.name, self.options)?;
+        }
+
+        Ok(Box::new(std::iter::empty()))
+    }
+
+    fn next(&self) -> Option<&dyn Executor> {
+        None
+    }
+}
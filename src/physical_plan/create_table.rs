@@ -1,13 +1,16 @@
 use crate::{
-    catalog::{schema::Schema, Table},
+    catalog::{error::CatalogError, schema::Schema, Table},
     ctx::Context,
     data::{
         tuple::{Tuple, TupleStream},
         types::Data,
     },
-    error::Result,
+    error::{Error, Result},
     physical_plan::Executor,
+    sequence::SequenceOptions,
+    transaction::TransactionError,
 };
+use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct CreateTableExec {
@@ -15,15 +18,23 @@ pub struct CreateTableExec {
     schema: Schema,
     pk: usize,
     sql: String,
+    sequences: Vec<(String, SequenceOptions)>,
 }
 
 impl CreateTableExec {
-    pub fn new(name: String, schema: Schema, pk: usize, sql: String) -> Self {
+    pub fn new(
+        name: String,
+        schema: Schema,
+        pk: usize,
+        sql: String,
+        sequences: Vec<(String, SequenceOptions)>,
+    ) -> Self {
         Self {
             name,
             schema,
             pk,
             sql,
+            sequences,
         }
     }
 }
@@ -34,13 +45,61 @@ impl Executor for CreateTableExec {
     }
 
     fn execute(&self, ctx: &mut Context) -> Result<TupleStream> {
+        if ctx.in_transaction() {
+            return Err(Error::TransactionError(
+                TransactionError::NotAllowedInTransaction {
+                    statement: "CREATE TABLE",
+                },
+            ));
+        }
+        // check catalog first
+        if ctx.catalog.contains_table(&self.name) {
+            return Err(Error::CatalogError(CatalogError::TableExists {
+                name: self.name.clone(),
+            }));
+        }
+
+        // the implied sequences are dropped if the table is not created, so
+        // that it can be created again
+        let sequences = Arc::clone(ctx.storage.sequences());
+        let mut created = Vec::with_capacity(self.sequences.len());
+        let res = self
+            .sequences
+            .iter()
+            .try_for_each(|(name, options)| {
+                sequences.create(name, *options)?;
+                created.push(name);
+                Ok(())
+            })
+            .and_then(|()| self.create_table(ctx));
+        if res.is_err() {
+            for name in created {
+                sequences.remove(name)?;
+            }
+        }
+        res?;
+
+        Ok(Box::new(std::iter::empty()))
+    }
+
+    fn next(&self) -> Option<&dyn Executor> {
+        None
+    }
+}
+
+impl CreateTableExec {
+    /// Add the table to the catalog and the storage, which are shared by all
+    /// the sessions, nothing is left if it fails.
+    fn create_table(&self, ctx: &mut Context) -> Result<()> {
         let table_name = self.name.clone();
         let table = Table::new(self.name.clone(), self.schema.clone(), self.pk);
-        // check catalog first
         ctx.catalog.add_table(table)?;
 
         // create disk files
-        ctx.storage.add_table(table_name.clone())?;
+        if let Err(e) = ctx.storage.add_table(table_name.clone()) {
+            ctx.catalog.remove_table(&table_name);
+            return Err(e);
+        }
 
         // insert it into the `vinyl_table` table
         let tuple = Tuple::new([
@@ -51,12 +110,14 @@ impl Executor for CreateTableExec {
             .storage
             .get_tree_of_table(crate::catalog::vinyl_table::TABLE_NAME)
             .unwrap();
-        vinyl_table_tree.insert(table_name, tuple.encode())?;
-
-        Ok(Box::new(std::iter::empty()))
-    }
+        if let Err(e) =
+            vinyl_table_tree.insert(table_name.clone(), tuple.encode())
+        {
+            ctx.catalog.remove_table(&table_name);
+            ctx.storage.remove_table(&table_name)?;
+            return Err(e.into());
+        }
 
-    fn next(&self) -> Option<&dyn Executor> {
-        None
+        Ok(())
     }
 }
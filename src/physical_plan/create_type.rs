@@ -0,0 +1,60 @@
+use crate::{
+    catalog::{schema::Schema, vinyl_type},
+    ctx::Context,
+    data::{
+        enum_type::EnumType,
+        tuple::{Tuple, TupleStream},
+        types::Data,
+    },
+    error::{Error, Result},
+    physical_plan::Executor,
+    transaction::TransactionError,
+};
+
+#[derive(Debug)]
+pub struct CreateTypeExec {
+    enum_type: EnumType,
+    sql: String,
+}
+
+impl CreateTypeExec {
+    pub fn new(enum_type: EnumType, sql: String) -> Self {
+        Self { enum_type, sql }
+    }
+}
+
+impl Executor for CreateTypeExec {
+    fn schema(&self) -> Schema {
+        Schema::empty()
+    }
+
+    fn execute(&self, ctx: &mut Context) -> Result<TupleStream> {
+        if ctx.in_transaction() {
+            return Err(Error::TransactionError(
+                TransactionError::NotAllowedInTransaction {
+                    statement: "CREATE TYPE",
+                },
+            ));
+        }
+        let name = self.enum_type.name().to_string();
+        // check catalog first
+        ctx.catalog.add_type(self.enum_type.clone())?;
+
+        // insert it into the `vinyl_type` table
+        let tuple = Tuple::new([
+            Data::String(name.clone()),
+            Data::String(self.sql.clone()),
+        ]);
+        let vinyl_type_tree = ctx
+            .storage
+            .get_tree_of_table(vinyl_type::TABLE_NAME)
+            .unwrap();
+        vinyl_type_tree.insert(name, tuple.encode())?;
+
+        Ok(Box::new(std::iter::empty()))
+    }
+
+    fn next(&self) -> Option<&dyn Executor> {
+        None
+    }
+}
@@ -45,7 +45,7 @@ impl Executor for DescribeTableExec {
             ret.push(Tuple::new([column_name, column_type, null, key]));
         }
 
-        Ok(Box::new(ret.into_iter()))
+        Ok(Box::new(ret.into_iter().map(Ok)))
     }
 
     fn next(&self) -> Option<&dyn Executor> {
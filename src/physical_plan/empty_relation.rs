@@ -0,0 +1,29 @@
+use crate::{
+    catalog::schema::Schema, ctx::Context, data::tuple::TupleStream,
+    physical_plan::Executor,
+};
+
+#[derive(Debug)]
+pub struct EmptyRelationExec {
+    schema: Schema,
+}
+
+impl EmptyRelationExec {
+    pub fn new(schema: Schema) -> Self {
+        Self { schema }
+    }
+}
+
+impl Executor for EmptyRelationExec {
+    fn schema(&self) -> Schema {
+        self.schema.clone()
+    }
+
+    fn execute(&self, _ctx: &mut Context) -> crate::error::Result<TupleStream> {
+        Ok(Box::new(std::iter::empty()))
+    }
+
+    fn next(&self) -> Option<&dyn Executor> {
+        None
+    }
+}
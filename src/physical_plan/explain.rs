@@ -42,7 +42,7 @@ impl Executor for ExplainExec {
         Ok(Box::new(
             execs
                 .into_iter()
-                .map(|name| Tuple::new([Data::String(name)])),
+                .map(|name| Ok(Tuple::new([Data::String(name)]))),
         ))
     }
 
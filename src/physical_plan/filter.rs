@@ -29,14 +29,13 @@ impl Executor for FilterExec {
         let schema = self.schema();
         let predicate = self.predicate.clone();
         let stream = self.input.execute(ctx)?;
-
-        Ok(Box::new(stream.filter(move |tuple| {
-            as_variant!(
-                Data::Bool,
-                predicate.evaluate(&schema, tuple).expect(
-                    "should not fail to evaluate an `Expr` during execution"
-                )
-            )
+        // an error, e.g., on overflow, is passed on as a row
+        Ok(Box::new(stream.filter_map(move |tuple| {
+            let matched = tuple.and_then(|tuple| {
+                let data = predicate.evaluate(&schema, &tuple)?;
+                Ok(as_variant!(Data::Bool, data).then_some(tuple))
+            });
+            matched.transpose()
         })))
     }
 
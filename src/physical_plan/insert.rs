@@ -1,44 +1,229 @@
 use crate::{
-    catalog::schema::Schema,
+    as_variant,
+    catalog::{error::CatalogError, schema::Schema, Catalog, Table},
     ctx::Context,
-    data::tuple::{Tuple, TupleStream},
-    error::Result,
+    data::{
+        tuple::{Tuple, TupleStream},
+        types::Data,
+    },
+    error::{Error, Result},
+    expr::Expr,
+    logical_plan::{InsertValue, OnConflict, Returning},
     physical_plan::Executor,
+    plan::values2tuples::coerce_to_column,
 };
+use indexmap::IndexMap;
+use sled::IVec;
 
 #[derive(Debug)]
 pub struct InsertExec {
     table: String,
-    rows: Vec<Tuple>,
+    values: Vec<InsertValue>,
+    on_conflict: OnConflict,
+    returning: Option<Returning>,
+    input: Box<dyn Executor>,
 }
 
 impl InsertExec {
-    pub fn new(table: String, rows: Vec<Tuple>) -> Self {
-        Self { table, rows }
+    pub fn new(
+        table: String,
+        values: Vec<InsertValue>,
+        on_conflict: OnConflict,
+        returning: Option<Returning>,
+        input: Box<dyn Executor>,
+    ) -> Self {
+        Self {
+            table,
+            values,
+            on_conflict,
+            returning,
+            input,
+        }
+    }
+
+    /// Build the row to insert into `table` from a row of the input.
+    fn to_inserted_row(
+        &self,
+        catalog: &Catalog,
+        table: &Table,
+        input: Tuple,
+    ) -> Result<Tuple> {
+        let row = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| match value {
+                InsertValue::Input(input_idx) => {
+                    let data = input.get(*input_idx).expect("type checked");
+                    coerce_to_column(catalog, table, idx, data.clone())
+                }
+                InsertValue::Default(expr) => {
+                    let data = expr.evaluate_constant_expr()?;
+                    coerce_to_column(catalog, table, idx, data)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Tuple::new(row))
+    }
+}
+
+/// Return `existing` updated by `ON CONFLICT DO UPDATE`, with `inserted` as
+/// `excluded`, `None` will be returned if it should not be updated.
+fn update_on_conflict(
+    catalog: &Catalog,
+    table: &Table,
+    assignments: &[(usize, Expr)],
+    selection: Option<&Expr>,
+    update_schema: &Schema,
+    existing: &Tuple,
+    inserted: &Tuple,
+) -> Result<Option<Tuple>> {
+    let input = Tuple::new(
+        existing
+            .iter()
+            .chain(existing.iter())
+            .chain(inserted.iter())
+            .cloned(),
+    );
+    if let Some(selection) = selection {
+        if !as_variant!(Data::Bool, selection.evaluate(update_schema, &input)?)
+        {
+            return Ok(None);
+        }
+    }
+
+    let mut updated = existing.iter().cloned().collect::<Vec<_>>();
+    for (idx, expr) in assignments {
+        let data = expr.evaluate(update_schema, &input)?;
+        updated[*idx] = coerce_to_column(catalog, table, *idx, data)?;
     }
+
+    Ok(Some(Tuple::new(updated)))
 }
 
 impl Executor for InsertExec {
     fn schema(&self) -> Schema {
-        Schema::empty()
+        match &self.returning {
+            Some(returning) => returning.schema.clone(),
+            None => Schema::empty(),
+        }
     }
 
     fn execute(&self, ctx: &mut Context) -> Result<TupleStream> {
         let table_name = &self.table;
+        let input = self.input.execute(ctx)?;
         let table_catalog = ctx.catalog.get_table(table_name)?;
+        let rows = input
+            .map(|row| self.to_inserted_row(&ctx.catalog, &table_catalog, row?))
+            .collect::<Result<Vec<_>>>()?;
+        let schema = table_catalog.schema().clone();
         let pk = table_catalog.pk();
+        let pk_column = schema
+            .column_names()
+            .nth(pk)
+            .expect("pk should be a column")
+            .to_string();
+        // primary keys are encoded under their collation, so that keys equal
+        // under it are duplicate
+        let pk_collation = schema.column_collation(&pk_column);
 
-        let tree = ctx.storage.get_tree_of_table(&self.table)?;
+        let returned =
+            ctx.write_in_transaction(|catalog, storage, transaction| {
+                let table_catalog = catalog.get_table(table_name)?;
+                let tree = storage.get_tree_of_table(table_name)?;
+                // the rows to write, rows are resolved one by one, a row
+                // conflicts with the ones before it as well, and none of them is
+                // written until all are resolved, so that the statement is
+                // all-or-nothing, a row committed by another transaction
+                // meanwhile is detected on commit
+                let mut writes: IndexMap<IVec, Tuple> =
+                    IndexMap::with_capacity(rows.len());
+                for row in rows.iter() {
+                    let pk = row.get(pk).unwrap();
+                    let key = pk_collation.apply(pk.clone());
+                    let key = IVec::from(key.encode().as_ref());
+                    // written by a row before it in this statement
+                    let written = writes.get(&key).cloned();
+                    let affected = written.is_some();
+                    let existing = match written {
+                        Some(existing) => Some(existing),
+                        None => transaction
+                            .get(table_name, &tree, &key)?
+                            .map(|value| Tuple::decode(&value, &schema)),
+                    };
+                    let row = match (existing, &self.on_conflict) {
+                        (None, _) | (Some(_), OnConflict::Replace) => {
+                            row.clone()
+                        }
+                        (Some(_), OnConflict::Error) => {
+                            return Err(Error::CatalogError(
+                                CatalogError::UniqueViolation {
+                                    table: table_name.clone(),
+                                    column: pk_column.clone(),
+                                    key: pk.clone(),
+                                },
+                            ));
+                        }
+                        (Some(_), OnConflict::DoNothing) => continue,
+                        // like PostgreSQL, as the result would depend on the
+                        // order of the rows
+                        (Some(_), OnConflict::DoUpdate { .. }) if affected => {
+                            return Err(Error::CatalogError(
+                                CatalogError::RowAffectedTwice,
+                            ));
+                        }
+                        (
+                            Some(existing),
+                            OnConflict::DoUpdate {
+                                assignments,
+                                selection,
+                                schema: update_schema,
+                            },
+                        ) => match update_on_conflict(
+                            catalog,
+                            &table_catalog,
+                            assignments,
+                            selection.as_ref(),
+                            update_schema,
+                            &existing,
+                            row,
+                        )? {
+                            Some(updated) => updated,
+                            None => continue,
+                        },
+                    };
+                    writes.insert(key, row);
+                }
 
-        for row in self.rows.iter() {
-            let pk = row.get(pk).unwrap();
-            assert!(tree.insert(pk.encode(), row.encode())?.is_none());
-        }
+                // evaluated before anything is written, as it could fail
+                let mut returned = Vec::new();
+                if let Some(returning) = &self.returning {
+                    returned.reserve(writes.len());
+                    for row in writes.values() {
+                        let data = returning
+                            .exprs
+                            .iter()
+                            .map(|expr| expr.evaluate(&schema, row))
+                            .collect::<Result<Vec<_>>>()?;
+                        returned.push(Tuple::new(data));
+                    }
+                }
+                for (key, row) in writes {
+                    transaction.insert(
+                        table_name,
+                        key,
+                        IVec::from(row.encode()),
+                    );
+                }
+
+                Ok(returned)
+            })?;
 
-        Ok(Box::new(std::iter::empty()))
+        Ok(Box::new(returned.into_iter().map(Ok)))
     }
 
     fn next(&self) -> Option<&dyn Executor> {
-        None
+        Some(self.input.as_ref())
     }
 }
@@ -1,13 +1,23 @@
+pub mod aggregate;
+pub mod create_sequence;
 pub mod create_table;
+pub mod create_type;
 pub mod describe_table;
+pub mod empty_relation;
 pub mod explain;
 pub mod filter;
 pub mod insert;
 pub mod limit;
 pub mod one_row_placeholder;
 pub mod projection;
+pub mod set_variable;
 pub mod show_tables;
+pub mod show_variable;
+pub mod sort;
 pub mod table_scan;
+pub mod transaction;
+pub mod values;
+pub mod window;
 
 use crate::{
     catalog::schema::Schema, ctx::Context, data::tuple::TupleStream,
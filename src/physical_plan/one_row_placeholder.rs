@@ -14,7 +14,7 @@ impl Executor for OneRowPlaceholderExec {
     }
 
     fn execute(&self, _ctx: &mut Context) -> crate::error::Result<TupleStream> {
-        let iter = std::iter::once(Tuple::empty());
+        let iter = std::iter::once(Ok(Tuple::empty()));
 
         Ok(Box::new(iter))
     }
@@ -36,17 +36,12 @@ impl Executor for ProjectionExec {
 
     fn execute(&self, ctx: &mut Context) -> Result<TupleStream> {
         let input_schema = self.input.schema();
+        let expr = self.expr.clone();
         let stream = self.input.execute(ctx)?;
-        let exprs = self.expr.clone();
-        let projected_stream = stream.map(move |tuple| {
-            Tuple::new(
-                Expr::evaluate_batch(&exprs, &input_schema, &tuple).expect(
-                    "should not fail to evaluate an Expr during execution",
-                ),
-            )
-        });
 
-        Ok(Box::new(projected_stream))
+        Ok(Box::new(stream.map(move |tuple| {
+            Expr::evaluate_batch(&expr, &input_schema, &tuple?).map(Tuple::new)
+        })))
     }
 
     fn next(&self) -> Option<&dyn Executor> {
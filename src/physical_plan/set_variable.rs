@@ -0,0 +1,32 @@
+use crate::{
+    catalog::schema::Schema, ctx::Context, data::tuple::TupleStream,
+    error::Result, physical_plan::Executor,
+};
+
+#[derive(Debug)]
+pub struct SetVariableExec {
+    name: String,
+    value: String,
+}
+
+impl SetVariableExec {
+    pub fn new(name: String, value: String) -> Self {
+        Self { name, value }
+    }
+}
+
+impl Executor for SetVariableExec {
+    fn schema(&self) -> Schema {
+        Schema::empty()
+    }
+
+    fn execute(&self, ctx: &mut Context) -> Result<TupleStream> {
+        ctx.catalog.settings_mut().set(&self.name, &self.value)?;
+
+        Ok(Box::new(std::iter::empty()))
+    }
+
+    fn next(&self) -> Option<&dyn Executor> {
+        None
+    }
+}
@@ -25,13 +25,13 @@ impl Executor for ShowTablesExec {
         let tables = ctx.catalog.tables();
         let iter = tables
             .iter()
-            .filter(|(name, _)| {
-                name != &crate::catalog::vinyl_table::TABLE_NAME
+            .filter(|table| {
+                table.name() != crate::catalog::vinyl_table::TABLE_NAME
             })
-            .map(|(name, _)| Tuple::new([Data::String(name.to_owned())]))
+            .map(|table| Tuple::new([Data::String(table.name().to_owned())]))
             .collect::<Vec<_>>();
 
-        Ok(Box::new(iter.into_iter()))
+        Ok(Box::new(iter.into_iter().map(Ok)))
     }
 
     fn next(&self) -> Option<&dyn Executor> {
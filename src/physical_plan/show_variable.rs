@@ -0,0 +1,43 @@
+use crate::{
+    catalog::schema::Schema,
+    ctx::Context,
+    data::{
+        tuple::{Tuple, TupleStream},
+        types::{Data, DataType},
+    },
+    error::Result,
+    physical_plan::Executor,
+};
+
+#[derive(Debug)]
+pub struct ShowVariableExec {
+    name: String,
+}
+
+impl ShowVariableExec {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+impl Executor for ShowVariableExec {
+    fn schema(&self) -> Schema {
+        Schema::new_with_duplicate_check([(
+            self.name.to_lowercase(),
+            DataType::String,
+        )])
+        .unwrap()
+    }
+
+    fn execute(&self, ctx: &mut Context) -> Result<TupleStream> {
+        let value = ctx.catalog.settings().get(&self.name)?;
+
+        Ok(Box::new(std::iter::once(Ok(Tuple::new([Data::String(
+            value,
+        )])))))
+    }
+
+    fn next(&self) -> Option<&dyn Executor> {
+        None
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{
+    catalog::schema::Schema,
+    ctx::Context,
+    data::tuple::{Tuple, TupleStream},
+    error::Result,
+    expr::{Expr, SortExpr},
+    physical_plan::Executor,
+};
+use std::ops::Deref;
+
+#[derive(Debug)]
+pub struct SortExec {
+    order_by: Vec<SortExpr>,
+    input: Box<dyn Executor>,
+}
+
+impl SortExec {
+    pub fn new(order_by: Vec<SortExpr>, input: Box<dyn Executor>) -> Self {
+        Self { order_by, input }
+    }
+}
+
+impl Executor for SortExec {
+    fn schema(&self) -> Schema {
+        self.input.schema()
+    }
+
+    fn execute(&self, ctx: &mut Context) -> Result<TupleStream> {
+        let schema = self.input.schema();
+        let exprs = self
+            .order_by
+            .iter()
+            .map(|sort| sort.expr.clone())
+            .collect::<Vec<_>>();
+        // keys are compared under the collations of their exprs
+        let mut rows = self
+            .input
+            .execute(ctx)?
+            .map(|tuple| {
+                let tuple = tuple?;
+                let keys = Expr::evaluate_batch(&exprs, &schema, &tuple)?
+                    .into_iter()
+                    .zip(&exprs)
+                    .map(|(key, expr)| expr.collation(&schema).apply(key))
+                    .collect::<Vec<_>>();
+                Ok((keys, tuple))
+            })
+            .collect::<Result<Vec<(_, Tuple)>>>()?;
+        rows.sort_by(|(lhs, _), (rhs, _)| {
+            SortExpr::compare(&self.order_by, lhs, rhs)
+        });
+
+        Ok(Box::new(rows.into_iter().map(|(_, tuple)| Ok(tuple))))
+    }
+
+    fn next(&self) -> Option<&dyn Executor> {
+        Some(self.input.deref())
+    }
+}
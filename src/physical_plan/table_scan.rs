@@ -24,15 +24,11 @@ impl Executor for TableScanExec {
     }
 
     fn execute(&self, ctx: &mut Context) -> Result<TupleStream> {
-        let storage_engine = &ctx.storage;
-        let tree = storage_engine.get_tree_of_table(&self.table)?;
         let schema = self.schema.clone();
 
-        let iter = tree
-            .iter()
-            .values()
-            .map(|res_data| res_data.unwrap())
-            .map(move |data| Tuple::decode(&data, &schema));
+        let iter = ctx
+            .scan_table(&self.table)?
+            .map(move |res| Ok(Tuple::decode(&res?.1, &schema)));
 
         Ok(Box::new(iter))
     }
@@ -0,0 +1,44 @@
+use crate::{
+    catalog::schema::Schema, ctx::Context, data::tuple::TupleStream,
+    error::Result, physical_plan::Executor, transaction::TransactionControl,
+};
+
+#[derive(Debug, Clone)]
+pub struct TransactionExec {
+    control: TransactionControl,
+}
+
+impl TransactionExec {
+    pub fn new(control: TransactionControl) -> Self {
+        Self { control }
+    }
+}
+
+impl Executor for TransactionExec {
+    fn schema(&self) -> Schema {
+        Schema::empty()
+    }
+
+    fn execute(&self, ctx: &mut Context) -> Result<TupleStream> {
+        match &self.control {
+            TransactionControl::Begin => ctx.begin()?,
+            TransactionControl::Commit => ctx.commit()?,
+            TransactionControl::Rollback => ctx.rollback()?,
+            TransactionControl::Savepoint { name } => {
+                ctx.savepoint(name.clone())?
+            }
+            TransactionControl::RollbackToSavepoint { name } => {
+                ctx.rollback_to_savepoint(name)?
+            }
+            TransactionControl::ReleaseSavepoint { name } => {
+                ctx.release_savepoint(name)?
+            }
+        }
+
+        Ok(Box::new(std::iter::empty()))
+    }
+
+    fn next(&self) -> Option<&dyn Executor> {
+        None
+    }
+}
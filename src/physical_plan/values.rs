@@ -0,0 +1,41 @@
+use crate::{
+    catalog::schema::Schema,
+    ctx::Context,
+    data::tuple::{Tuple, TupleStream},
+    error::Result,
+    expr::Expr,
+    physical_plan::Executor,
+};
+
+#[derive(Debug)]
+pub struct ValuesExec {
+    rows: Vec<Vec<Expr>>,
+    schema: Schema,
+}
+
+impl ValuesExec {
+    pub fn new(rows: Vec<Vec<Expr>>, schema: Schema) -> Self {
+        Self { rows, schema }
+    }
+}
+
+impl Executor for ValuesExec {
+    fn schema(&self) -> Schema {
+        self.schema.clone()
+    }
+
+    fn execute(&self, _ctx: &mut Context) -> Result<TupleStream> {
+        let rows = self.rows.clone();
+
+        Ok(Box::new(rows.into_iter().map(|row| {
+            row.iter()
+                .map(Expr::evaluate_constant_expr)
+                .collect::<Result<Vec<_>>>()
+                .map(Tuple::new)
+        })))
+    }
+
+    fn next(&self) -> Option<&dyn Executor> {
+        None
+    }
+}
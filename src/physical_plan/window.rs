@@ -0,0 +1,150 @@
+use crate::{
+    catalog::schema::Schema,
+    ctx::Context,
+    data::{
+        tuple::{Tuple, TupleStream},
+        types::Data,
+    },
+    error::Result,
+    expr::{Expr, SortExpr},
+    function::window::WindowExpr,
+    physical_plan::Executor,
+};
+use indexmap::IndexMap;
+use std::{cmp::Ordering, ops::Deref};
+
+#[derive(Debug)]
+pub struct WindowExec {
+    window: Vec<WindowExpr>,
+    schema: Schema,
+    input: Box<dyn Executor>,
+}
+
+impl WindowExec {
+    pub fn new(
+        window: Vec<WindowExpr>,
+        schema: Schema,
+        input: Box<dyn Executor>,
+    ) -> Self {
+        Self {
+            window,
+            schema,
+            input,
+        }
+    }
+}
+
+/// Evaluate `window` for every row in `rows`, results are in the same order
+/// as `rows`.
+fn evaluate_window(
+    window: &WindowExpr,
+    schema: &Schema,
+    rows: &[Tuple],
+) -> Result<Vec<Data>> {
+    let accumulator = window.aggr.func.accumulator();
+    let frame = window.frame.unwrap_or_default();
+    let order_by = window
+        .order_by
+        .iter()
+        .map(|sort| sort.expr.clone())
+        .collect::<Vec<_>>();
+    // keys are compared under the collations of their exprs
+    let collate = |exprs: &[Expr], tuple: &Tuple| -> Result<Vec<Data>> {
+        let keys = Expr::evaluate_batch(exprs, schema, tuple)?;
+        Ok(keys
+            .into_iter()
+            .zip(exprs)
+            .map(|(key, expr)| expr.collation(schema).apply(key))
+            .collect())
+    };
+
+    // partition key => [(row index, sort key)]
+    let mut partitions: IndexMap<_, Vec<(usize, Vec<Data>)>> = IndexMap::new();
+    for (idx, tuple) in rows.iter().enumerate() {
+        let key = Tuple::new(collate(&window.partition_by, tuple)?);
+        let sort_key = collate(&order_by, tuple)?;
+        partitions
+            .entry(key.encode())
+            .or_default()
+            .push((idx, sort_key));
+    }
+
+    let mut result = vec![None; rows.len()];
+    for (_, mut partition) in partitions {
+        partition.sort_by(|(_, lhs), (_, rhs)| {
+            SortExpr::compare(&window.order_by, lhs, rhs)
+        });
+        let len = partition.len();
+
+        // the inclusive range of peers of every row
+        let mut peers = vec![(0, 0); len];
+        let mut start = 0;
+        for end in 0..len {
+            let is_last_peer = end + 1 == len
+                || SortExpr::compare(
+                    &window.order_by,
+                    &partition[end].1,
+                    &partition[end + 1].1,
+                ) != Ordering::Equal;
+            if is_last_peer {
+                peers[start..=end].fill((start, end));
+                start = end + 1;
+            }
+        }
+
+        let args = partition
+            .iter()
+            .map(|(idx, _)| {
+                Expr::evaluate_batch(&window.aggr.args, schema, &rows[*idx])
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for (pos, (idx, _)) in partition.iter().enumerate() {
+            let mut state = accumulator.init();
+            if let Some((start, end)) = frame.bounds(pos, len, peers[pos]) {
+                for args in args[start..=end].iter() {
+                    accumulator.update(&mut state, args)?;
+                }
+            }
+            result[*idx] = Some(accumulator.finalize(&state)?);
+        }
+    }
+
+    Ok(result
+        .into_iter()
+        .map(|data| data.expect("every row belongs to a partition"))
+        .collect())
+}
+
+impl Executor for WindowExec {
+    fn schema(&self) -> Schema {
+        self.schema.clone()
+    }
+
+    fn execute(&self, ctx: &mut Context) -> Result<TupleStream> {
+        let input_schema = self.input.schema();
+        let rows = self.input.execute(ctx)?.collect::<Result<Vec<_>>>()?;
+
+        let columns = self
+            .window
+            .iter()
+            .map(|window| evaluate_window(window, &input_schema, &rows))
+            .collect::<Result<Vec<_>>>()?;
+
+        let ret =
+            rows.into_iter()
+                .enumerate()
+                .map(|(idx, tuple)| {
+                    Tuple::new(tuple.iter().cloned().chain(
+                        columns.iter().map(|column| column[idx].clone()),
+                    ))
+                })
+                .collect::<Vec<_>>();
+
+        Ok(Box::new(ret.into_iter().map(Ok)))
+    }
+
+    fn next(&self) -> Option<&dyn Executor> {
+        Some(self.input.deref())
+    }
+}
@@ -0,0 +1,102 @@
+//! Planning aggregations, i.e., `GROUP BY`, `HAVING` and aggregate functions.
+
+use super::error::PlanError;
+use crate::{
+    catalog::schema::Schema,
+    error::{Error, Result},
+    expr::Expr,
+    function::aggregate::AggregateExpr,
+    logical_plan::LogicalPlan,
+};
+
+/// Build an aggregation on top of `input`, whose schema is `schema`.
+///
+/// `projection` and `having` will be rewritten to reference the output columns
+/// of the aggregation, which are named after the grouping expressions and the
+/// aggregate function calls.
+///
+/// Return the new plan, its schema and the rewritten `projection`.
+pub(crate) fn plan_aggregate(
+    input: LogicalPlan,
+    schema: &Schema,
+    group_by: Vec<Expr>,
+    projection: Vec<Expr>,
+    having: Option<Expr>,
+) -> Result<(LogicalPlan, Schema, Vec<Expr>)> {
+    let mut group_by_names: Vec<String> = Vec::with_capacity(group_by.len());
+    let mut group_by_exprs = Vec::with_capacity(group_by.len());
+    for expr in group_by {
+        if expr.contains_aggregate() {
+            return Err(Error::PlanError(PlanError::MisplacedAggregate {
+                clause: "GROUP BY",
+            }));
+        }
+        let name = expr.to_string();
+        if !group_by_names.contains(&name) {
+            group_by_names.push(name);
+            group_by_exprs.push(expr);
+        }
+    }
+
+    let mut aggr = Vec::new();
+    let projection = projection
+        .into_iter()
+        .map(|expr| rewrite(expr, &group_by_names, &mut aggr))
+        .collect::<Result<Vec<_>>>()?;
+    let having = having
+        .map(|expr| rewrite(expr, &group_by_names, &mut aggr))
+        .transpose()?;
+
+    let mut columns = Vec::with_capacity(group_by_exprs.len() + aggr.len());
+    let mut collations = Vec::with_capacity(group_by_exprs.len());
+    for (name, expr) in group_by_names.into_iter().zip(group_by_exprs.iter()) {
+        columns.push((name.clone(), expr.datatype(schema)?));
+        collations.push((name, expr.collation(schema)));
+    }
+    for aggr in aggr.iter() {
+        columns.push((aggr.to_string(), aggr.datatype(schema)?));
+    }
+    let output_schema = Schema::new(columns).with_collations(collations);
+
+    let mut plan = LogicalPlan::Aggregate {
+        group_by: group_by_exprs,
+        aggr,
+        schema: output_schema.clone(),
+        input: Box::new(input),
+    };
+    if let Some(having) = having {
+        plan = LogicalPlan::Filter {
+            predicate: having,
+            input: Box::new(plan),
+        };
+    }
+
+    Ok((plan, output_schema, projection))
+}
+
+/// Replace grouping expressions and aggregate function calls in `expr` with
+/// references to the output columns of the aggregation, aggregate function
+/// calls are collected into `aggr`.
+fn rewrite(
+    expr: Expr,
+    group_by: &[String],
+    aggr: &mut Vec<AggregateExpr>,
+) -> Result<Expr> {
+    let name = expr.to_string();
+    if group_by.contains(&name) {
+        return Ok(Expr::Column(name));
+    }
+
+    match expr {
+        Expr::Aggregate(aggr_expr) => {
+            if !aggr.iter().any(|a| a.to_string() == name) {
+                aggr.push(aggr_expr);
+            }
+            Ok(Expr::Column(name))
+        }
+        Expr::Column(column) => {
+            Err(Error::PlanError(PlanError::ColumnNotInGroupBy { column }))
+        }
+        expr => expr.map_children(|child| rewrite(child, group_by, aggr)),
+    }
+}
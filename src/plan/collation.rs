@@ -0,0 +1,14 @@
+//! For converting `COLLATE name`.
+
+use super::error::{PlanError, PlanResult};
+use crate::data::collation::Collation;
+use sqlparser::ast::ObjectName;
+
+/// Convert the `name` in `COLLATE name` to a [`Collation`].
+pub(crate) fn convert_collation(name: &ObjectName) -> PlanResult<Collation> {
+    match name.0.as_slice() {
+        [ident] => ident.value.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| PlanError::CollationDoesNotExist { name: name.clone() })
+}
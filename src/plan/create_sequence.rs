@@ -0,0 +1,184 @@
+//! For converting `Statement::CreateSequence`, and the sequence options of
+//! identity columns.
+
+use crate::{
+    catalog::Catalog,
+    data::types::{Data, DataType},
+    error::{Error, Result},
+    logical_plan::LogicalPlan,
+    plan::{
+        datatype::convert_datatype,
+        error::{PlanError, UnimplementedFeature},
+        expr::convert_expr,
+        object_name_to_table_name::object_name_to_table_name,
+    },
+    sequence::SequenceOptions,
+};
+use sqlparser::ast::{
+    Expr as SqlExpr, SequenceOptions as SqlSequenceOptions, Statement,
+};
+
+/// Convert the options of a sequence of `datatype`, the unspecified ones are
+/// defaulted like PostgreSQL.
+pub(crate) fn convert_sequence_options(
+    catalog: &Catalog,
+    datatype: &DataType,
+    options: Vec<SqlSequenceOptions>,
+) -> Result<SequenceOptions> {
+    let (type_min, type_max) = match datatype {
+        DataType::Int16 => (i16::MIN as i64, i16::MAX as i64),
+        DataType::Int32 => (i32::MIN as i64, i32::MAX as i64),
+        DataType::Int64 => (i64::MIN, i64::MAX),
+        datatype => {
+            return Err(Error::PlanError(PlanError::NonIntegerSequence {
+                datatype: datatype.clone(),
+            }))
+        }
+    };
+    let mut increment = 1;
+    let (mut min, mut max, mut start) = (None, None, None);
+    let mut cycle = false;
+    for option in options {
+        match option {
+            SqlSequenceOptions::IncrementBy(expr, _) => {
+                increment = option_value(catalog, expr)?;
+            }
+            SqlSequenceOptions::MinValue(expr) => {
+                min =
+                    expr.map(|expr| option_value(catalog, expr)).transpose()?;
+            }
+            SqlSequenceOptions::MaxValue(expr) => {
+                max =
+                    expr.map(|expr| option_value(catalog, expr)).transpose()?;
+            }
+            SqlSequenceOptions::StartWith(expr, _) => {
+                start = Some(option_value(catalog, expr)?);
+            }
+            // values are not cached
+            SqlSequenceOptions::Cache(_) => {}
+            SqlSequenceOptions::Cycle(no) => cycle = !no,
+        }
+    }
+    let (min, max) = if increment > 0 {
+        (min.unwrap_or(1), max.unwrap_or(type_max))
+    } else {
+        (min.unwrap_or(type_min), max.unwrap_or(-1))
+    };
+    for value in [min, max] {
+        if !(type_min..=type_max).contains(&value) {
+            return Err(Error::PlanError(PlanError::ValueOutOfRange {
+                value: Data::Int64(value),
+                datatype: datatype.clone(),
+            }));
+        }
+    }
+    let default = SequenceOptions::new(increment, min, max);
+    let options = SequenceOptions {
+        start: start.unwrap_or(default.start),
+        cycle,
+        ..default
+    };
+    options.check()?;
+
+    Ok(options)
+}
+
+/// Evaluate the value of a sequence option.
+fn option_value(catalog: &Catalog, expr: SqlExpr) -> Result<i64> {
+    let expr = convert_expr(catalog, expr)?;
+    if expr.is_constant() {
+        if let Data::Int64(value) = expr.evaluate_constant_expr()? {
+            return Ok(value);
+        }
+    }
+
+    Err(Error::PlanError(PlanError::NonIntegerSequenceOption {
+        expr,
+    }))
+}
+
+pub(crate) fn convert(
+    catalog: &Catalog,
+    statement: Statement,
+) -> Result<LogicalPlan> {
+    match statement.clone() {
+        Statement::CreateSequence {
+            temporary: false,
+            if_not_exists,
+            name,
+            data_type,
+            sequence_options,
+            owned_by: None,
+        } => {
+            let name = object_name_to_table_name(name)?;
+            let datatype = match data_type {
+                Some(data_type) => {
+                    convert_datatype(&catalog.types(), data_type)?
+                }
+                None => DataType::Int64,
+            };
+            let options =
+                convert_sequence_options(catalog, &datatype, sequence_options)?;
+
+            Ok(LogicalPlan::CreateSequence {
+                name,
+                options,
+                if_not_exists,
+            })
+        }
+        _ => Err(Error::PlanError(PlanError::Unimplemented(
+            UnimplementedFeature::Statement { statement },
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data::{tuple::Tuple, types::Data},
+        sequence::LOG_VALS,
+        VinylDB,
+    };
+    use camino_tempfile::Utf8TempDir;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn sequences_are_persisted() {
+        let dir = Utf8TempDir::new().unwrap();
+        {
+            let mut db = VinylDB::new(dir.path());
+            db.sql("CREATE SEQUENCE s INCREMENT BY 2").unwrap();
+            db.sql("SELECT nextval('s')").unwrap();
+            db.sql("CREATE TABLE t (id SERIAL, name STRING)").unwrap();
+            db.sql("INSERT INTO t (name) VALUES ('a'), ('b')").unwrap();
+        }
+
+        let mut db = VinylDB::new(dir.path());
+        // currval() is per session
+        assert!(db.sql("SELECT currval('s')").is_err());
+        // the values fetched but not returned before the restart are skipped
+        let skipped = LOG_VALS as i64;
+        let rows = db.sql("SELECT nextval('s')").unwrap();
+        assert_eq!(rows, vec![Tuple::new([Data::Int64(1 + 2 * skipped)])]);
+        db.sql("INSERT INTO t (name) VALUES ('c')").unwrap();
+        let rows = db.sql("SELECT id FROM t WHERE name = 'c'").unwrap();
+        assert_eq!(rows, vec![Tuple::new([Data::Int64(1 + skipped)])]);
+    }
+
+    #[test]
+    fn currval_is_per_session() {
+        let dir = Utf8TempDir::new().unwrap();
+        let mut s1 = VinylDB::new(dir.path());
+        let mut s2 = s1.session();
+        s1.sql("CREATE SEQUENCE s").unwrap();
+
+        s1.sql("SELECT nextval('s')").unwrap();
+        assert!(s2.sql("SELECT currval('s')").is_err());
+        let rows = s2.sql("SELECT nextval('s')").unwrap();
+        assert_eq!(rows, vec![Tuple::new([Data::Int64(2)])]);
+        let rows = s1.sql("SELECT currval('s')").unwrap();
+        assert_eq!(rows, vec![Tuple::new([Data::Int64(1)])]);
+        let rows = s2.sql("SELECT currval('s')").unwrap();
+        assert_eq!(rows, vec![Tuple::new([Data::Int64(2)])]);
+    }
+}
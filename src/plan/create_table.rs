@@ -1,32 +1,44 @@
 //! For converting `Statement::CreateTable`.
 
 use crate::{
-    catalog::{error::CatalogError, schema::Schema, Catalog},
+    catalog::{error::CatalogError, schema::Schema, Catalog, Table},
+    data::{enum_type::EnumType, types::DataType},
     error::{Error, Result},
     logical_plan::LogicalPlan,
-    plan::object_name_to_table_name::object_name_to_table_name,
+    plan::{
+        collation::convert_collation,
+        create_sequence::convert_sequence_options,
+        datatype::convert_datatype,
+        error::{PlanError, UnimplementedFeature},
+        object_name_to_table_name::object_name_to_table_name,
+        values2tuples::check_default,
+    },
+    storage_engine::INTERNAL_TREES,
 };
-use sqlparser::ast::Statement;
+use indexmap::IndexMap;
+use sqlparser::ast::{
+    ColumnDef, ColumnOption, DataType as SQLDataType, Expr as SqlExpr,
+    Function, FunctionArg, FunctionArgExpr, GeneratedAs, Ident, ObjectName,
+    SequenceOptions as SqlSequenceOptions, Statement, Value,
+};
+use std::sync::Arc;
 
 /// Helper function to parse a `CreateTable` statement, and return the table name
-/// and its schema.
+/// and its schema, `types` are the user-defined types.
 ///
 /// # Undefined Behavior
 ///
 /// The caller should ensure the passed `statement` should be a
 /// `Statement::CreateTable`, or this function will be a UB.
 pub(crate) unsafe fn create_table_to_name_schema(
+    types: &IndexMap<String, Arc<EnumType>>,
     statement: Statement,
 ) -> Result<(String, Schema)> {
     match statement {
         Statement::CreateTable { name, columns, .. } => {
             let name = object_name_to_table_name(name)?;
-
-            let mut cols = Vec::with_capacity(columns.len());
-            for column in columns {
-                cols.push((column.name.value, column.data_type.try_into()?));
-            }
-            let schema = Schema::new_with_duplicate_check(cols)?;
+            // the implied sequences have been created with the table
+            let (schema, _) = columns_to_schema(types, &name, columns)?;
 
             Ok((name, schema))
         }
@@ -34,6 +46,132 @@ pub(crate) unsafe fn create_table_to_name_schema(
     }
 }
 
+/// A sequence implied by a `SERIAL` or identity column, which generates the
+/// `DEFAULT`s of it.
+#[derive(Debug)]
+struct ImpliedSequence {
+    name: String,
+    datatype: DataType,
+    options: Vec<SqlSequenceOptions>,
+}
+
+/// Return the type of `ty` if it is `SMALLSERIAL`, `SERIAL` or `BIGSERIAL`.
+fn serial_type(ty: &SQLDataType) -> Option<DataType> {
+    let SQLDataType::Custom(name, modifiers) = ty else {
+        return None;
+    };
+    if !modifiers.is_empty() {
+        return None;
+    }
+    match name.to_string().to_lowercase().as_str() {
+        "smallserial" | "serial2" => Some(DataType::Int16),
+        "serial" | "serial4" => Some(DataType::Int32),
+        "bigserial" | "serial8" => Some(DataType::Int64),
+        _ => None,
+    }
+}
+
+/// Return `nextval('sequence')`.
+fn nextval(sequence: &str) -> SqlExpr {
+    SqlExpr::Function(Function {
+        name: ObjectName(vec![Ident::new("nextval")]),
+        args: vec![FunctionArg::Unnamed(FunctionArgExpr::Expr(
+            SqlExpr::Value(Value::SingleQuotedString(sequence.to_string())),
+        ))],
+        filter: None,
+        null_treatment: None,
+        over: None,
+        distinct: false,
+        special: false,
+        order_by: Vec::new(),
+    })
+}
+
+/// Build the schema of `columns` of table `table`, with their collations and
+/// `DEFAULT` expressions, the sequences implied by them are returned as well.
+fn columns_to_schema(
+    types: &IndexMap<String, Arc<EnumType>>,
+    table: &str,
+    columns: Vec<ColumnDef>,
+) -> Result<(Schema, Vec<ImpliedSequence>)> {
+    let mut cols = Vec::with_capacity(columns.len());
+    let mut collations = Vec::new();
+    let mut defaults = Vec::new();
+    let mut generated_always = Vec::new();
+    let mut sequences = Vec::new();
+    for column in columns {
+        let serial = serial_type(&column.data_type);
+        let datatype = match serial.clone() {
+            Some(datatype) => datatype,
+            None => convert_datatype(types, column.data_type)?,
+        };
+        // named like PostgreSQL
+        let sequence = format!("{table}_{}_seq", column.name.value);
+        let mut default = serial.map(|datatype| {
+            sequences.push(ImpliedSequence {
+                name: sequence.clone(),
+                datatype,
+                options: Vec::new(),
+            });
+            nextval(&sequence)
+        });
+        if let Some(collation) = column.collation {
+            let collation = convert_collation(&collation)?;
+            // only strings can be collated
+            if !datatype.is_string() {
+                return Err(Error::PlanError(
+                    PlanError::CollationOnNonString { datatype },
+                ));
+            }
+            collations.push((column.name.value.clone(), collation));
+        }
+        for option in column.options {
+            let expr = match option.option {
+                ColumnOption::Default(expr) => expr,
+                ColumnOption::Generated {
+                    generated_as:
+                        generated_as
+                        @ (GeneratedAs::Always | GeneratedAs::ByDefault),
+                    sequence_options,
+                    generation_expr: None,
+                    ..
+                } => {
+                    if generated_as == GeneratedAs::Always {
+                        generated_always.push(column.name.value.clone());
+                    }
+                    sequences.push(ImpliedSequence {
+                        name: sequence.clone(),
+                        datatype: datatype.clone(),
+                        options: sequence_options.unwrap_or_default(),
+                    });
+                    nextval(&sequence)
+                }
+                option @ ColumnOption::Generated { .. } => {
+                    return Err(Error::PlanError(PlanError::Unimplemented(
+                        UnimplementedFeature::ColumnOption { option },
+                    )));
+                }
+                _ => continue,
+            };
+            if default.replace(expr).is_some() {
+                return Err(Error::PlanError(PlanError::MultipleDefaults {
+                    column: column.name.value,
+                }));
+            }
+        }
+        if let Some(default) = default {
+            defaults.push((column.name.value.clone(), default));
+        }
+        cols.push((column.name.value, datatype));
+    }
+    let schema = Schema::new_with_duplicate_check(cols)?
+        .with_collations(collations)
+        .with_defaults(defaults)
+        .with_generated_always(generated_always);
+
+    Ok((schema, sequences))
+}
+
 pub(crate) fn convert(
     catalog: &Catalog,
     statement: Statement,
@@ -42,22 +180,45 @@ pub(crate) fn convert(
     match statement {
         Statement::CreateTable { name, columns, .. } => {
             let name = object_name_to_table_name(name)?;
+            if INTERNAL_TREES.contains(&name.as_str()) {
+                return Err(Error::CatalogError(
+                    CatalogError::ReservedTableName { name },
+                ));
+            }
             if catalog.contains_table(&name) {
                 return Err(Error::CatalogError(CatalogError::TableExists {
                     name,
                 }));
             }
-            let mut cols = Vec::with_capacity(columns.len());
-            for column in columns {
-                cols.push((column.name.value, column.data_type.try_into()?));
+            let (schema, sequences) =
+                columns_to_schema(&catalog.types(), &name, columns)?;
+            let sequences = sequences
+                .into_iter()
+                .map(|sequence| {
+                    let options = convert_sequence_options(
+                        catalog,
+                        &sequence.datatype,
+                        sequence.options,
+                    )?;
+                    Ok((sequence.name, options))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            // `DEFAULT`s are evaluated so that they won't fail on `INSERT`
+            let table = Table::new(name.clone(), schema.clone(), 0);
+            for idx in 0..table.n_columns() {
+                let column =
+                    schema.column_names().nth(idx).expect("should be a column");
+                if schema.column_default(column).is_some() {
+                    check_default(catalog, &table, idx)?;
+                }
             }
-            let schema = Schema::new_with_duplicate_check(cols)?;
 
             Ok(LogicalPlan::CreateTable {
                 name: name.to_string(),
                 schema,
                 pk: 0,
                 sql,
+                sequences,
             })
         }
 
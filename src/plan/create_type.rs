@@ -0,0 +1,122 @@
+//! For converting `Statement::CreateType`.
+
+use crate::{
+    catalog::{error::CatalogError, Catalog},
+    data::{enum_type::EnumType, types::DataType},
+    error::{Error, Result},
+    logical_plan::LogicalPlan,
+    plan::{
+        error::{PlanError, UnimplementedFeature},
+        object_name_to_table_name::object_name_to_table_name,
+    },
+};
+use sqlparser::{
+    ast::{
+        DataType as SQLDataType, Ident, Statement,
+        UserDefinedTypeRepresentation,
+    },
+    dialect::PostgreSqlDialect,
+    parser::Parser,
+};
+
+/// Convert `CREATE TYPE name AS ENUM (...)` to the [`EnumType`], which is
+/// parsed as a composite type of a single `ENUM(...)` attribute, see
+/// [`crate::ctx::parse_statement()`].
+pub(crate) fn create_type_to_enum_type(
+    statement: Statement,
+) -> Result<EnumType> {
+    let unsupported = |statement| {
+        Error::PlanError(PlanError::Unimplemented(
+            UnimplementedFeature::Statement { statement },
+        ))
+    };
+    let Statement::CreateType {
+        name,
+        representation: UserDefinedTypeRepresentation::Composite { attributes },
+    } = &statement
+    else {
+        return Err(unsupported(statement));
+    };
+    let [attribute] = attributes.as_slice() else {
+        return Err(unsupported(statement));
+    };
+    let SQLDataType::Enum(labels) = &attribute.data_type else {
+        return Err(unsupported(statement));
+    };
+    let name = object_name_to_table_name(name.clone())?;
+    // built-in types cannot be shadowed
+    let builtin = Parser::new(&PostgreSqlDialect {})
+        .try_with_sql(&name)
+        .and_then(|mut parser| parser.parse_data_type())
+        .is_ok_and(|ty| DataType::try_from(ty).is_ok());
+    if builtin {
+        return Err(Error::CatalogError(CatalogError::TypeExists { name }));
+    }
+    for (idx, label) in labels.iter().enumerate() {
+        if labels[..idx].contains(label) {
+            return Err(Error::PlanError(PlanError::DuplicateEnumLabel {
+                label: label.clone(),
+            }));
+        }
+    }
+
+    Ok(EnumType::new(name, labels.clone()))
+}
+
+/// Return the SQL that creates `enum_type`, which is stored in the catalog.
+fn create_type_sql(enum_type: &EnumType) -> String {
+    let labels = enum_type
+        .labels()
+        .iter()
+        .map(|label| format!("'{}'", label.replace('\'', "''")))
+        .collect::<Vec<_>>();
+
+    format!(
+        "CREATE TYPE {} AS ENUM ({})",
+        Ident::with_quote('"', enum_type.name()),
+        labels.join(", ")
+    )
+}
+
+pub(crate) fn convert(
+    catalog: &Catalog,
+    statement: Statement,
+) -> Result<LogicalPlan> {
+    let enum_type = create_type_to_enum_type(statement)?;
+    if catalog.types().contains_key(enum_type.name()) {
+        return Err(Error::CatalogError(CatalogError::TypeExists {
+            name: enum_type.name().to_string(),
+        }));
+    }
+    let sql = create_type_sql(&enum_type);
+
+    Ok(LogicalPlan::CreateType { enum_type, sql })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data::{tuple::Tuple, types::Data},
+        VinylDB,
+    };
+    use camino_tempfile::Utf8TempDir;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn types_are_persisted() {
+        let dir = Utf8TempDir::new().unwrap();
+        {
+            let mut db = VinylDB::new(dir.path());
+            db.sql(r#"CREATE TYPE "My Mood" AS ENUM ('sad', 'it''s ok')"#)
+                .unwrap();
+            db.sql(r#"CREATE TABLE t (m "My Mood", id INT64)"#).unwrap();
+            db.sql("INSERT INTO t VALUES ('it''s ok', 1)").unwrap();
+        }
+
+        let mut db = VinylDB::new(dir.path());
+        let labels = db.catalog.types()["My Mood"].labels().to_vec();
+        assert_eq!(labels, ["sad", "it's ok"]);
+        let rows = db.sql("SELECT id FROM t WHERE m > 'sad'").unwrap();
+        assert_eq!(rows, vec![Tuple::new([Data::Int64(1)])]);
+    }
+}
@@ -1,22 +1,168 @@
-use super::error::{PlanError, UnimplementedFeature};
-use crate::data::types::DataType;
-use sqlparser::ast::DataType as SQLDataType;
+use super::error::{PlanError, PlanResult, UnimplementedFeature};
+use crate::data::{
+    decimal::MAX_PRECISION, enum_type::EnumType, types::DataType,
+};
+use indexmap::IndexMap;
+use sqlparser::ast::{
+    ArrayElemTypeDef, CharacterLength, DataType as SQLDataType,
+    ExactNumberInfo, TimezoneInfo,
+};
+use std::sync::Arc;
+
+/// Convert `ty` to a [`DataType`], names that are not built-in types are
+/// looked up in the user-defined `types`.
+pub(crate) fn convert_datatype(
+    types: &IndexMap<String, Arc<EnumType>>,
+    ty: SQLDataType,
+) -> PlanResult<DataType> {
+    match ty {
+        SQLDataType::Custom(ref name, ref modifiers)
+            if modifiers.is_empty() && name.0.len() == 1 =>
+        {
+            match types.get(&name.0[0].value) {
+                Some(enum_type) => Ok(DataType::Enum(enum_type.clone())),
+                None => DataType::try_from(ty),
+            }
+        }
+        SQLDataType::Array(
+            ArrayElemTypeDef::SquareBracket(element)
+            | ArrayElemTypeDef::AngleBracket(element),
+        ) => Ok(DataType::List(Box::new(convert_datatype(
+            types,
+            Box::into_inner(element),
+        )?))),
+        ty => DataType::try_from(ty),
+    }
+}
 
 impl TryFrom<SQLDataType> for DataType {
     type Error = PlanError;
     fn try_from(value: SQLDataType) -> Result<Self, Self::Error> {
         match value {
-            SQLDataType::Bool => Ok(Self::Bool),
-            SQLDataType::Int64 => Ok(Self::Int64),
-            SQLDataType::Float64 => Ok(Self::Float64),
-            SQLDataType::Timestamp(_, _) => Ok(Self::Timestamp),
-            SQLDataType::String(_) => Ok(Self::String),
+            SQLDataType::Bool | SQLDataType::Boolean => Ok(Self::Bool),
+            SQLDataType::SmallInt(_) | SQLDataType::Int2(_) => Ok(Self::Int16),
+            SQLDataType::Int(_)
+            | SQLDataType::Integer(_)
+            | SQLDataType::Int4(_) => Ok(Self::Int32),
+            SQLDataType::Int64
+            | SQLDataType::BigInt(_)
+            | SQLDataType::Int8(_) => Ok(Self::Int64),
+            // `FLOAT(p)` is `REAL` if `p` is at most 24, like PostgreSQL.
+            SQLDataType::Real | SQLDataType::Float4 => Ok(Self::Float32),
+            SQLDataType::Float(Some(precision)) if precision <= 24 => {
+                Ok(Self::Float32)
+            }
+            SQLDataType::Float64
+            | SQLDataType::Float(_)
+            | SQLDataType::Float8
+            | SQLDataType::Double
+            | SQLDataType::DoublePrecision => Ok(Self::Float64),
+            SQLDataType::Decimal(info)
+            | SQLDataType::Numeric(info)
+            | SQLDataType::Dec(info) => decimal_type(info),
+            SQLDataType::Timestamp(
+                _,
+                TimezoneInfo::None | TimezoneInfo::WithoutTimeZone,
+            ) => Ok(Self::Timestamp),
+            SQLDataType::Timestamp(
+                _,
+                TimezoneInfo::WithTimeZone | TimezoneInfo::Tz,
+            ) => Ok(Self::TimestampTz),
+            SQLDataType::Date => Ok(Self::Date),
+            SQLDataType::Time(_, _) => Ok(Self::Time),
+            SQLDataType::Interval => Ok(Self::Interval),
+            SQLDataType::String(_) | SQLDataType::Text => Ok(Self::String),
+            SQLDataType::Bytea
+            | SQLDataType::Blob(_)
+            | SQLDataType::Bytes(_)
+            | SQLDataType::Varbinary(_) => Ok(Self::Binary),
+            SQLDataType::JSON | SQLDataType::JSONB => Ok(Self::Json),
+            SQLDataType::Uuid => Ok(Self::Uuid),
+            // `INT64[]` or `ARRAY<INT64>`
+            SQLDataType::Array(
+                ArrayElemTypeDef::SquareBracket(element)
+                | ArrayElemTypeDef::AngleBracket(element),
+            ) => Ok(Self::List(Box::new(Self::try_from(*element)?))),
+            SQLDataType::Varchar(ref length)
+            | SQLDataType::CharVarying(ref length)
+            | SQLDataType::CharacterVarying(ref length) => {
+                match character_length(length, &value)? {
+                    Some(length) => Ok(Self::Varchar { length }),
+                    None => Ok(Self::String),
+                }
+            }
+            // `CHAR` is `CHAR(1)`
+            SQLDataType::Char(ref length)
+            | SQLDataType::Character(ref length) => {
+                let length = match length {
+                    None => 1,
+                    Some(_) => character_length(length, &value)?
+                        .ok_or_else(|| unsupported(value.clone()))?,
+                };
+                Ok(Self::Char { length })
+            }
+            // Our own names, which are used to display the types.
+            SQLDataType::Custom(ref name, ref modifiers)
+                if modifiers.is_empty() =>
+            {
+                match name.to_string().to_lowercase().as_str() {
+                    "int16" => Ok(Self::Int16),
+                    "int32" => Ok(Self::Int32),
+                    "float32" => Ok(Self::Float32),
+                    _ => Err(unsupported(value)),
+                }
+            }
 
-            ty => {
-                Err(PlanError::Unimplemented(UnimplementedFeature::DataType {
-                    ty,
-                }))
+            ty => Err(unsupported(ty)),
+        }
+    }
+}
+
+fn unsupported(ty: SQLDataType) -> PlanError {
+    PlanError::Unimplemented(UnimplementedFeature::DataType { ty })
+}
+
+/// Return the length of `CHAR(n)` or `VARCHAR(n)`, `None` if it is not
+/// limited.
+fn character_length(
+    length: &Option<CharacterLength>,
+    ty: &SQLDataType,
+) -> Result<Option<u32>, PlanError> {
+    match length {
+        None | Some(CharacterLength::Max) => Ok(None),
+        Some(CharacterLength::IntegerLength { length, unit: None }) => {
+            match u32::try_from(*length) {
+                Ok(length) if length > 0 => Ok(Some(length)),
+                _ => Err(PlanError::InvalidCharacterLength { ty: ty.clone() }),
             }
         }
+        // `VARCHAR(n OCTETS)`
+        Some(CharacterLength::IntegerLength { .. }) => {
+            Err(unsupported(ty.clone()))
+        }
     }
 }
+
+/// `DECIMAL` is `DECIMAL(38, 0)` and `DECIMAL(p)` is `DECIMAL(p, 0)`.
+fn decimal_type(info: ExactNumberInfo) -> Result<DataType, PlanError> {
+    let (precision, scale) = match info {
+        ExactNumberInfo::None => (u64::from(MAX_PRECISION), 0),
+        ExactNumberInfo::Precision(precision) => (precision, 0),
+        ExactNumberInfo::PrecisionAndScale(precision, scale) => {
+            (precision, scale)
+        }
+    };
+    if !(1..=u64::from(MAX_PRECISION)).contains(&precision) || scale > precision
+    {
+        return Err(PlanError::InvalidDecimalType {
+            precision,
+            scale,
+            max: MAX_PRECISION,
+        });
+    }
+
+    Ok(DataType::Decimal {
+        precision: precision as u8,
+        scale: scale as u8,
+    })
+}
@@ -1,11 +1,14 @@
 use crate::{
-    data::types::{Data, DataType},
+    data::{
+        collation::Collation,
+        types::{Data, DataType},
+    },
     expr::{Expr, Operator},
 };
 use derive_more::{Display, Error};
 use sqlparser::ast::{
-    BinaryOperator, DataType as SQLDataType, Expr as SQLExpr, ObjectName,
-    SelectItem, Statement, Value,
+    BinaryOperator, ColumnOption, DataType as SQLDataType, Expr as SQLExpr,
+    JsonOperator, ObjectName, SelectItem, Statement, Value,
 };
 
 /// Features that have not been supported by VinylDB.
@@ -20,6 +23,8 @@ pub enum UnimplementedFeature {
     DataType { ty: SQLDataType },
     #[display(fmt = "Unsupported SQL binary operator {op}")]
     Operator { op: BinaryOperator },
+    #[display(fmt = "Unsupported JSON operator {op}")]
+    JsonOperator { op: JsonOperator },
     #[display(fmt = "Unsupported SQL Expr {expr}")]
     Expr { expr: SQLExpr },
     #[display(fmt = "Unsupported multi-level table {object_name}")]
@@ -34,6 +39,10 @@ pub enum UnimplementedFeature {
     ProjectionQualifiedWildcard { select_item: SelectItem },
     #[display(fmt = "NULL is not supported")]
     Null,
+    #[display(fmt = "Unsupported window frame {frame}")]
+    WindowFrame { frame: String },
+    #[display(fmt = "Unsupported column option {option}")]
+    ColumnOption { option: ColumnOption },
 }
 
 #[derive(Debug, Display, Error, Clone)]
@@ -50,6 +59,67 @@ pub enum ExprEvaluationError {
     UnsupportedTypeForOp { datatype: DataType, op: Operator },
     #[display(fmt = "{expr} needs to be a constant but it is not")]
     ExprIsNotConstant { expr: Expr },
+    #[display(fmt = "{expr} cannot be evaluated against a single row")]
+    NotScalar { expr: Expr },
+    #[display(
+        fmt = "function '{func}' takes {expected} arguments but {found} were supplied"
+    )]
+    WrongNumberOfArguments {
+        func: String,
+        expected: usize,
+        found: usize,
+    },
+    #[display(
+        fmt = "function '{func}' cannot be applied to type '{datatype}'"
+    )]
+    UnsupportedTypeForFunction { func: String, datatype: DataType },
+    #[display(fmt = "pattern matching cannot be done on type '{datatype}'")]
+    UnsupportedTypeForPattern { datatype: DataType },
+    #[display(fmt = "CASE types '{lhs}' and '{rhs}' cannot be matched")]
+    MismatchedCaseTypes { lhs: DataType, rhs: DataType },
+    #[display(
+        fmt = "argument of CASE/WHEN must be type 'BOOL', not type '{datatype}'"
+    )]
+    NonBoolCaseCondition { datatype: DataType },
+    #[display(fmt = "date/time value out of range")]
+    DateTimeOutOfRange,
+    #[display(fmt = "numeric field overflow")]
+    NumericOverflow,
+    #[display(fmt = "negative substring length not allowed")]
+    NegativeSubstringLength,
+    #[display(fmt = "unrecognized encoding: '{encoding}'")]
+    UnrecognizedEncoding { encoding: String },
+    #[display(fmt = "invalid {encoding} data")]
+    InvalidEncodedData { encoding: String },
+    #[display(fmt = "unit '{field}' not recognized")]
+    UnrecognizedDateTimeField { field: String },
+    #[display(fmt = "unit '{field}' not supported for type '{datatype}'")]
+    UnsupportedDateTimeField { field: String, datatype: DataType },
+    #[display(fmt = "time zone '{zone}' not recognized")]
+    UnrecognizedTimeZone { zone: String },
+    #[display(fmt = "cannot get array length of a non-array")]
+    JsonArrayLengthOfNonArray,
+    #[display(fmt = "cannot determine type of empty array")]
+    EmptyArray,
+    #[display(fmt = "ARRAY types '{lhs}' and '{rhs}' cannot be matched")]
+    MismatchedArrayTypes { lhs: DataType, rhs: DataType },
+    #[display(
+        fmt = "array subscript {index} out of range for an array of {length} elements"
+    )]
+    ArraySubscriptOutOfRange { index: i64, length: usize },
+    #[display(fmt = "op ANY (array) requires an array on the right side")]
+    AnyOfNonArray,
+    #[display(fmt = "invalid input value for enum {name}: '{label}'")]
+    InvalidEnumLabel { name: String, label: String },
+    #[display(
+        fmt = "collation mismatch between {} collations '{lhs}' and '{rhs}'",
+        r#"if *explicit { "explicit" } else { "implicit" }"#
+    )]
+    CollationMismatch {
+        lhs: Collation,
+        rhs: Collation,
+        explicit: bool,
+    },
 }
 
 /// Errors that could happen while converting an SQL AST to a [`LogicalPlan`].
@@ -78,6 +148,16 @@ pub enum PlanError {
     },
     #[display(fmt = "could not convert {val} to {to}")]
     ConversionError { val: Value, to: DataType },
+    #[display(fmt = "value {value} is out of range for type {datatype}")]
+    ValueOutOfRange { value: Data, datatype: DataType },
+    #[display(fmt = "value too long for type {datatype}")]
+    ValueTooLong { datatype: DataType },
+    #[display(fmt = "length for type {ty} must be at least 1")]
+    InvalidCharacterLength { ty: SQLDataType },
+    #[display(
+        fmt = "DECIMAL precision {precision} must be between 1 and {max}, scale {scale} must be between 0 and the precision"
+    )]
+    InvalidDecimalType { precision: u64, scale: u64, max: u8 },
     #[display(fmt = "could not evaluate {_0}")]
     ExprEvaluationError(ExprEvaluationError),
     #[display(
@@ -91,6 +171,97 @@ pub enum PlanError {
     WildcardWithoutFrom,
     #[display(fmt = "Non-cnostant expr {expr} without FROM clause")]
     NonConstantExprWithoutFrom { expr: Expr },
+    #[display(fmt = "expression {expr} in VALUES should be a constant")]
+    NonConstantValue { expr: Expr },
+    #[display(fmt = "function '{name}' does not exist")]
+    FunctionDoesNotExist { name: String },
+    #[display(fmt = "ORDER BY position {position} is not in select list")]
+    OrderByPositionOutOfRange { position: usize },
+    #[display(fmt = "aggregate functions are not allowed in {clause}")]
+    MisplacedAggregate { clause: &'static str },
+    #[display(fmt = "window functions are not allowed in {clause}")]
+    MisplacedWindow { clause: &'static str },
+    #[display(
+        fmt = "column '{column}' must appear in the GROUP BY clause or be used in an aggregate function"
+    )]
+    ColumnNotInGroupBy { column: String },
+    #[display(fmt = "invalid window frame: {frame}")]
+    InvalidWindowFrame { frame: String },
+    #[display(fmt = "pattern should be a string literal, found: {expr}")]
+    NonLiteralPattern { expr: Expr },
+    #[display(fmt = "invalid pattern '{pattern}': {reason}")]
+    InvalidPattern { pattern: String, reason: String },
+    #[display(fmt = "unit should be a string literal, found: {expr}")]
+    NonLiteralDateTimeField { expr: Expr },
+    #[display(fmt = "time zone should be a string literal, found: {expr}")]
+    NonLiteralTimeZone { expr: Expr },
+    #[display(fmt = "encoding should be a string literal, found: {expr}")]
+    NonLiteralEncoding { expr: Expr },
+    #[display(
+        fmt = "JSON path should be a text array literal like '{{a,0}}', found: {expr}"
+    )]
+    InvalidJsonPath { expr: Expr },
+    #[display(
+        fmt = "array dimension should be an integer literal within the dimensions of the array, found: {expr}"
+    )]
+    InvalidArrayDimension { expr: Expr },
+    #[display(fmt = "enum label '{label}' used more than once")]
+    DuplicateEnumLabel { label: String },
+    #[display(fmt = "UNNEST only supports constant arrays, found: {expr}")]
+    NonConstantUnnest { expr: Expr },
+    #[display(fmt = "collation '{name}' does not exist")]
+    CollationDoesNotExist { name: ObjectName },
+    #[display(fmt = "collations are not supported by type '{datatype}'")]
+    CollationOnNonString { datatype: DataType },
+    #[display(
+        fmt = "there is no unique or exclusion constraint matching the ON CONFLICT specification"
+    )]
+    NoMatchingConflictTarget,
+    #[display(
+        fmt = "ON CONFLICT DO UPDATE requires inference specification or constraint name"
+    )]
+    ConflictTargetRequired,
+    #[display(
+        fmt = "primary key column '{column}' cannot be updated by ON CONFLICT DO UPDATE"
+    )]
+    UpdatePrimaryKeyOnConflict { column: String },
+    #[display(fmt = "INSERT has more expressions than target columns")]
+    MoreExpressionsThanColumns,
+    #[display(fmt = "INSERT has more target columns than expressions")]
+    MoreColumnsThanExpressions,
+    #[display(fmt = "column '{column}' specified more than once")]
+    DuplicateInsertColumn { column: String },
+    #[display(
+        fmt = "column '{column}' is not supplied and has no default value"
+    )]
+    NoDefaultValue { column: String },
+    #[display(
+        fmt = "cannot use column reference in DEFAULT expression of column '{column}'"
+    )]
+    NonConstantDefault { column: String },
+    #[display(
+        fmt = "type of a sequence must be INT16, INT32 or INT64, found {datatype}"
+    )]
+    NonIntegerSequence { datatype: DataType },
+    #[display(fmt = "sequence option {expr} should be an integer constant")]
+    NonIntegerSequenceOption { expr: Expr },
+    #[display(
+        fmt = "cannot insert a non-DEFAULT value into column '{column}', which is GENERATED ALWAYS"
+    )]
+    GeneratedAlwaysColumn { column: String },
+    #[display(fmt = "cannot modify catalog table '{table}'")]
+    ModifyCatalogTable { table: String },
+    #[display(fmt = "multiple default values specified for column '{column}'")]
+    MultipleDefaults { column: String },
+    #[display(fmt = "multiple assignments to same column '{column}'")]
+    MultipleAssignments { column: String },
+    #[display(
+        fmt = "argument of {clause} must be type 'BOOL', not type '{datatype}'"
+    )]
+    NonBoolPredicate {
+        clause: &'static str,
+        datatype: DataType,
+    },
 }
 
 pub type PlanResult<T> = Result<T, PlanError>;
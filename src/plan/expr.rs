@@ -1,21 +1,192 @@
 use super::{
+    collation::convert_collation,
     error::{PlanError, PlanResult},
-    op::convert_op,
-    value2data::value_to_data,
+    function::convert_function,
+    op::{convert_op, convert_pattern_op},
+    value2data::{
+        interval_to_data, string_to_data, typed_string_to_data, value_to_data,
+    },
+};
+use crate::{
+    catalog::Catalog,
+    data::{
+        datetime::DateTimeField,
+        decimal::Decimal,
+        types::{Data, DataType},
+    },
+    expr::{Expr, SortExpr},
+    function::scalar::ScalarFunctionExpr,
+    pattern::{Pattern, PatternKind},
+    plan::error::{ExprEvaluationError, UnimplementedFeature},
+};
+use sqlparser::ast::{
+    BinaryOperator, Expr as SqlExpr, Interval as SqlInterval, JsonOperator,
+    OrderByExpr, UnaryOperator,
 };
-use crate::{expr::Expr, plan::error::UnimplementedFeature};
-use sqlparser::ast::Expr as SqlExpr;
 
-pub fn convert_expr(sql_expr: SqlExpr) -> PlanResult<Expr> {
+pub fn convert_expr(catalog: &Catalog, sql_expr: SqlExpr) -> PlanResult<Expr> {
     match sql_expr {
         SqlExpr::Identifier(iden) => Ok(Expr::Column(iden.value)),
+        // A qualified column `q.c` is named `q.c`, which is only found in
+        // schemas having such columns, e.g., the one of `ON CONFLICT DO
+        // UPDATE`. Unquoted qualifiers are case-insensitive.
+        SqlExpr::CompoundIdentifier(idens) => {
+            let (column, qualifiers) = idens.split_last().unwrap();
+            let mut name = String::new();
+            for qualifier in qualifiers {
+                match qualifier.quote_style {
+                    Some(_) => name.push_str(&qualifier.value),
+                    None => name.push_str(&qualifier.value.to_lowercase()),
+                }
+                name.push('.');
+            }
+            name.push_str(&column.value);
+
+            Ok(Expr::Column(name))
+        }
         SqlExpr::Value(val) => {
             let data = value_to_data(val)?;
             Ok(Expr::Literal(data))
         }
+        SqlExpr::TypedString { data_type, value } => {
+            Ok(Expr::Literal(typed_string_to_data(
+                data_type,
+                value,
+                catalog.settings().time_zone,
+            )?))
+        }
+        SqlExpr::Interval(interval) => match interval.value.as_ref() {
+            SqlExpr::BinaryOp { .. } => {
+                convert_expr(catalog, split_interval(interval))
+            }
+            _ => Ok(Expr::Literal(interval_to_data(interval)?)),
+        },
+        // `extract(field FROM expr)` is `date_part('field', expr)`.
+        SqlExpr::Extract { field, expr } => {
+            let field = field.to_string().to_lowercase();
+            if field.parse::<DateTimeField>().is_err() {
+                return Err(PlanError::ExprEvaluationError(
+                    ExprEvaluationError::UnrecognizedDateTimeField { field },
+                ));
+            }
+            let func = catalog
+                .functions()
+                .scalar_function("date_part")
+                .expect("date_part() is a built-in function");
+
+            Ok(Expr::ScalarFunction(ScalarFunctionExpr {
+                func: func.clone(),
+                args: vec![
+                    Expr::Literal(Data::String(field)),
+                    convert_expr(catalog, Box::into_inner(expr))?,
+                ],
+            }))
+        }
+        // `SUBSTRING(expr FROM start FOR count)` is
+        // `substring(expr, start, count)`.
+        SqlExpr::Substring {
+            expr,
+            substring_from,
+            substring_for,
+            ..
+        } => {
+            let func = catalog
+                .functions()
+                .scalar_function("substring")
+                .expect("substring() is a built-in function");
+            let mut args = vec![convert_expr(catalog, Box::into_inner(expr))?];
+            // `SUBSTRING(expr FOR count)` starts from 1
+            args.push(match substring_from {
+                Some(start) => convert_expr(catalog, Box::into_inner(start))?,
+                None => Expr::Literal(Data::Int64(1)),
+            });
+            if let Some(count) = substring_for {
+                args.push(convert_expr(catalog, Box::into_inner(count))?);
+            }
+
+            Ok(Expr::ScalarFunction(ScalarFunctionExpr {
+                func: func.clone(),
+                args,
+            }))
+        }
+        // `timestamp AT TIME ZONE zone` is `timezone('zone', timestamp)`.
+        SqlExpr::AtTimeZone {
+            timestamp,
+            time_zone,
+        } => {
+            let func = catalog
+                .functions()
+                .scalar_function("timezone")
+                .expect("timezone() is a built-in function");
+
+            Ok(Expr::ScalarFunction(ScalarFunctionExpr {
+                func: func.clone(),
+                args: vec![
+                    Expr::Literal(Data::String(time_zone)),
+                    convert_expr(catalog, Box::into_inner(timestamp))?,
+                ],
+            }))
+        }
+        SqlExpr::Nested(expr) => convert_expr(catalog, Box::into_inner(expr)),
+        SqlExpr::Collate { expr, collation } => Ok(Expr::Collate {
+            expr: Box::new(convert_expr(catalog, Box::into_inner(expr))?),
+            collation: convert_collation(&collation)?,
+        }),
+        // Only signed numeric literals are supported, e.g., `-1`.
+        SqlExpr::UnaryOp {
+            op: op @ (UnaryOperator::Plus | UnaryOperator::Minus),
+            expr,
+        } => {
+            let negate = op == UnaryOperator::Minus;
+            let data =
+                match convert_expr(catalog, Box::into_inner(expr.clone()))? {
+                    Expr::Literal(Data::Int64(int)) if negate => {
+                        int.checked_neg().map(Data::Int64)
+                    }
+                    Expr::Literal(Data::Float64(float)) if negate => {
+                        Some(Data::Float64(-float))
+                    }
+                    Expr::Literal(Data::Decimal(decimal)) if negate => {
+                        Some(Data::Decimal(Decimal::new(
+                            -decimal.mantissa(),
+                            decimal.scale(),
+                        )))
+                    }
+                    Expr::Literal(
+                        data @ (Data::Int64(_)
+                        | Data::Float64(_)
+                        | Data::Decimal(_)),
+                    ) => Some(data),
+                    _ => None,
+                };
+
+            data.map(Expr::Literal).ok_or_else(|| {
+                PlanError::Unimplemented(UnimplementedFeature::Expr {
+                    expr: SqlExpr::UnaryOp { op, expr },
+                })
+            })
+        }
         SqlExpr::BinaryOp { left, op, right } => {
-            let left = convert_expr(Box::into_inner(left))?;
-            let right = convert_expr(Box::into_inner(right))?;
+            let SqlExpr::BinaryOp { left, op, right } = rotate_binary_op(
+                Box::into_inner(left),
+                op,
+                Box::into_inner(right),
+            ) else {
+                unreachable!("rotate_binary_op() returns a binary operation")
+            };
+            if let Some((kind, negated)) = convert_pattern_op(&op) {
+                return convert_match(
+                    catalog,
+                    Box::into_inner(left),
+                    kind,
+                    negated,
+                    Box::into_inner(right),
+                    None,
+                );
+            }
+
+            let left = convert_expr(catalog, Box::into_inner(left))?;
+            let right = convert_expr(catalog, Box::into_inner(right))?;
             let op = convert_op(op)?;
 
             Ok(Expr::BinaryExpr {
@@ -24,8 +195,654 @@ pub fn convert_expr(sql_expr: SqlExpr) -> PlanResult<Expr> {
                 right: Box::new(right),
             })
         }
+        SqlExpr::Like {
+            negated,
+            expr,
+            pattern,
+            escape_char,
+        } => convert_match(
+            catalog,
+            Box::into_inner(expr),
+            PatternKind::Like,
+            negated,
+            Box::into_inner(pattern),
+            escape_char,
+        ),
+        SqlExpr::ILike {
+            negated,
+            expr,
+            pattern,
+            escape_char,
+        } => convert_match(
+            catalog,
+            Box::into_inner(expr),
+            PatternKind::ILike,
+            negated,
+            Box::into_inner(pattern),
+            escape_char,
+        ),
+        SqlExpr::SimilarTo {
+            negated,
+            expr,
+            pattern,
+            escape_char,
+        } => convert_match(
+            catalog,
+            Box::into_inner(expr),
+            PatternKind::SimilarTo,
+            negated,
+            Box::into_inner(pattern),
+            escape_char,
+        ),
+        SqlExpr::Between {
+            expr,
+            negated,
+            low,
+            high,
+        } => Ok(Expr::Between {
+            expr: Box::new(convert_expr(catalog, Box::into_inner(expr))?),
+            negated,
+            low: Box::new(convert_expr(catalog, Box::into_inner(low))?),
+            high: Box::new(convert_expr(catalog, Box::into_inner(high))?),
+        }),
+        SqlExpr::InList {
+            expr,
+            list,
+            negated,
+        } => Ok(Expr::InList {
+            expr: Box::new(convert_expr(catalog, Box::into_inner(expr))?),
+            list: list
+                .into_iter()
+                .map(|item| convert_expr(catalog, item))
+                .collect::<PlanResult<_>>()?,
+            negated,
+        }),
+        SqlExpr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => {
+            // Without `ELSE`, a `CASE` evaluates to NULL if no branch matches.
+            let Some(else_result) = else_result else {
+                return Err(PlanError::Unimplemented(
+                    UnimplementedFeature::Null,
+                ));
+            };
+            let operand = operand
+                .map(|operand| {
+                    convert_expr(catalog, Box::into_inner(operand))
+                        .map(Box::new)
+                })
+                .transpose()?;
+            let when_then = conditions
+                .into_iter()
+                .zip(results)
+                .map(|(when, then)| {
+                    Ok((
+                        convert_expr(catalog, when)?,
+                        convert_expr(catalog, then)?,
+                    ))
+                })
+                .collect::<PlanResult<_>>()?;
+
+            Ok(Expr::Case {
+                operand,
+                when_then,
+                else_expr: Box::new(convert_expr(
+                    catalog,
+                    Box::into_inner(else_result),
+                )?),
+            })
+        }
+        SqlExpr::JsonAccess {
+            left,
+            operator,
+            right,
+        } => match rotate_json_access(
+            Box::into_inner(left),
+            operator,
+            Box::into_inner(right),
+        ) {
+            SqlExpr::JsonAccess {
+                left,
+                operator,
+                right,
+            } => convert_json_access(
+                catalog,
+                Box::into_inner(left),
+                operator,
+                Box::into_inner(right),
+            ),
+            expr => convert_expr(catalog, expr),
+        },
+        // `ARRAY[1, 2]` and `[1, 2]` are `make_array(1, 2)`.
+        SqlExpr::Array(array) => {
+            let func = catalog
+                .functions()
+                .scalar_function("make_array")
+                .expect("make_array() is a built-in function");
+
+            Ok(Expr::ScalarFunction(ScalarFunctionExpr {
+                func: func.clone(),
+                args: array
+                    .elem
+                    .into_iter()
+                    .map(|elem| convert_expr(catalog, elem))
+                    .collect::<PlanResult<_>>()?,
+            }))
+        }
+        // `a[1][2]` is `array_extract(array_extract(a, 1), 2)`.
+        SqlExpr::ArrayIndex { obj, indexes } => {
+            let func = catalog
+                .functions()
+                .scalar_function("array_extract")
+                .expect("array_extract() is a built-in function");
+            let array = convert_expr(catalog, Box::into_inner(obj))?;
+            indexes.into_iter().try_fold(array, |array, index| {
+                Ok(Expr::ScalarFunction(ScalarFunctionExpr {
+                    func: func.clone(),
+                    args: vec![array, convert_expr(catalog, index)?],
+                }))
+            })
+        }
+        SqlExpr::AnyOp {
+            left,
+            compare_op,
+            right,
+        } if matches!(
+            compare_op,
+            BinaryOperator::Eq
+                | BinaryOperator::NotEq
+                | BinaryOperator::Lt
+                | BinaryOperator::LtEq
+                | BinaryOperator::Gt
+                | BinaryOperator::GtEq
+        ) =>
+        {
+            Ok(Expr::Any {
+                left: Box::new(convert_expr(catalog, Box::into_inner(left))?),
+                op: convert_op(compare_op)?,
+                right: Box::new(convert_expr(catalog, Box::into_inner(right))?),
+            })
+        }
+        SqlExpr::Function(function) => convert_function(catalog, function),
         expr => Err(PlanError::Unimplemented(UnimplementedFeature::Expr {
             expr,
         })),
     }
 }
+
+/// The parser takes everything after a JSON operator as its right operand,
+/// e.g., `a -> 'k' = b` is parsed as `a -> ('k' = b)`, this moves the
+/// leftmost operand of the right operand into the JSON operation, which binds
+/// more tightly than all the other operators, e.g., `(a -> 'k') = b`.
+fn rotate_json_access(
+    left: SqlExpr,
+    operator: JsonOperator,
+    right: SqlExpr,
+) -> SqlExpr {
+    let rotate = |left, inner: Box<SqlExpr>| {
+        Box::new(rotate_json_access(left, operator, Box::into_inner(inner)))
+    };
+    match right {
+        SqlExpr::BinaryOp {
+            left: inner_left,
+            op,
+            right,
+        } => SqlExpr::BinaryOp {
+            left: rotate(left, inner_left),
+            op,
+            right,
+        },
+        // `a -> 'b' -> 'c'` is `(a -> 'b') -> 'c'`
+        SqlExpr::JsonAccess {
+            left: inner_left,
+            operator: inner_operator,
+            right,
+        } => rotate_json_access(
+            Box::into_inner(rotate(left, inner_left)),
+            inner_operator,
+            Box::into_inner(right),
+        ),
+        SqlExpr::Like {
+            negated,
+            expr,
+            pattern,
+            escape_char,
+        } => SqlExpr::Like {
+            negated,
+            expr: rotate(left, expr),
+            pattern,
+            escape_char,
+        },
+        SqlExpr::ILike {
+            negated,
+            expr,
+            pattern,
+            escape_char,
+        } => SqlExpr::ILike {
+            negated,
+            expr: rotate(left, expr),
+            pattern,
+            escape_char,
+        },
+        SqlExpr::SimilarTo {
+            negated,
+            expr,
+            pattern,
+            escape_char,
+        } => SqlExpr::SimilarTo {
+            negated,
+            expr: rotate(left, expr),
+            pattern,
+            escape_char,
+        },
+        SqlExpr::Between {
+            expr,
+            negated,
+            low,
+            high,
+        } => SqlExpr::Between {
+            expr: rotate(left, expr),
+            negated,
+            low,
+            high,
+        },
+        SqlExpr::InList {
+            expr,
+            list,
+            negated,
+        } => SqlExpr::InList {
+            expr: rotate(left, expr),
+            list,
+            negated,
+        },
+        right => SqlExpr::JsonAccess {
+            left: Box::new(left),
+            operator,
+            right: Box::new(right),
+        },
+    }
+}
+
+/// Convert `left operator right` to a call of the function of the JSON
+/// operator:
+///
+/// * `json -> key` and `json #> '{key,0}'` are `json_extract_path()`
+/// * `json ->> key` and `json #>> '{key,0}'` are `json_extract_path_text()`
+/// * `json @> json` and `json <@ json` are `jsonb_contains()`
+/// * `json ? key` is `jsonb_exists()`, see `rewrite_exists_operator()` for how
+///   it is parsed
+fn convert_json_access(
+    catalog: &Catalog,
+    left: SqlExpr,
+    operator: JsonOperator,
+    right: SqlExpr,
+) -> PlanResult<Expr> {
+    let left = convert_expr(catalog, left)?;
+    let right = convert_expr(catalog, right)?;
+    // String literals are JSON values in `@>` and `<@`.
+    let json_operand = |expr: Expr| match expr {
+        Expr::Literal(Data::String(str)) => Ok(Expr::Literal(string_to_data(
+            str,
+            DataType::Json,
+            catalog.settings().time_zone,
+        )?)),
+        expr => Ok(expr),
+    };
+    let (name, args) = match operator {
+        JsonOperator::Arrow => ("json_extract_path", vec![left, right]),
+        JsonOperator::LongArrow => {
+            ("json_extract_path_text", vec![left, right])
+        }
+        JsonOperator::HashArrow | JsonOperator::HashLongArrow => {
+            let path = match &right {
+                Expr::Literal(Data::String(path)) => parse_text_array(path),
+                _ => None,
+            };
+            let Some(path) = path else {
+                return Err(PlanError::InvalidJsonPath { expr: right });
+            };
+            let name = if operator == JsonOperator::HashArrow {
+                "json_extract_path"
+            } else {
+                "json_extract_path_text"
+            };
+            let mut args = vec![left];
+            args.extend(
+                path.into_iter().map(|key| Expr::Literal(Data::String(key))),
+            );
+            (name, args)
+        }
+        JsonOperator::AtArrow => (
+            "jsonb_contains",
+            vec![json_operand(left)?, json_operand(right)?],
+        ),
+        JsonOperator::ArrowAt => (
+            "jsonb_contains",
+            vec![json_operand(right)?, json_operand(left)?],
+        ),
+        JsonOperator::AtQuestion => ("jsonb_exists", vec![left, right]),
+        operator => {
+            return Err(PlanError::Unimplemented(
+                UnimplementedFeature::JsonOperator { op: operator },
+            ))
+        }
+    };
+    let func = catalog
+        .functions()
+        .scalar_function(name)
+        .expect("JSON functions are built-in functions");
+
+    Ok(Expr::ScalarFunction(ScalarFunctionExpr {
+        func: func.clone(),
+        args,
+    }))
+}
+
+/// Parse a text array literal like `{a,"b c",0}`, `None` will be returned if
+/// it is invalid.
+fn parse_text_array(str: &str) -> Option<Vec<String>> {
+    let elements = str.trim().strip_prefix('{')?.strip_suffix('}')?;
+    if elements.trim().is_empty() {
+        return Some(Vec::new());
+    }
+
+    elements
+        .split(',')
+        .map(|element| {
+            let element = element.trim();
+            match element.strip_prefix('"') {
+                Some(quoted) => quoted.strip_suffix('"').map(str::to_string),
+                None if element.is_empty() => None,
+                None => Some(element.to_string()),
+            }
+        })
+        .collect()
+}
+
+/// The parser takes everything up to `AND`/`OR` after `INTERVAL` as the value
+/// of the interval, e.g., `INTERVAL '1 day' - INTERVAL '2 hours'` is parsed as
+/// an interval whose value is `'1 day' - INTERVAL '2 hours'`, this moves the
+/// leftmost operand into the interval and the operations out of it.
+fn split_interval(interval: SqlInterval) -> SqlExpr {
+    match Box::into_inner(interval.value) {
+        SqlExpr::BinaryOp { left, op, right } => SqlExpr::BinaryOp {
+            left: Box::new(split_interval(SqlInterval {
+                value: left,
+                ..interval
+            })),
+            op,
+            right,
+        },
+        value => SqlExpr::Interval(SqlInterval {
+            value: Box::new(value),
+            ..interval
+        }),
+    }
+}
+
+/// Return the precedence of `op` used by the parser, `None` if it is not
+/// known here.
+fn sql_precedence(op: &BinaryOperator) -> Option<u8> {
+    match op {
+        BinaryOperator::Or => Some(5),
+        BinaryOperator::And => Some(10),
+        BinaryOperator::Eq
+        | BinaryOperator::NotEq
+        | BinaryOperator::Lt
+        | BinaryOperator::LtEq
+        | BinaryOperator::Gt
+        | BinaryOperator::GtEq
+        | BinaryOperator::PGRegexMatch
+        | BinaryOperator::PGRegexIMatch
+        | BinaryOperator::PGRegexNotMatch
+        | BinaryOperator::PGRegexNotIMatch
+        | BinaryOperator::PGLikeMatch
+        | BinaryOperator::PGILikeMatch
+        | BinaryOperator::PGNotLikeMatch
+        | BinaryOperator::PGNotILikeMatch => Some(20),
+        BinaryOperator::Plus | BinaryOperator::Minus => Some(30),
+        BinaryOperator::Multiply
+        | BinaryOperator::Divide
+        | BinaryOperator::Modulo
+        | BinaryOperator::StringConcat => Some(40),
+        _ => None,
+    }
+}
+
+/// Fix the tree of `left op right` whose `right` comes from an interval
+/// that took too many operations, see [`split_interval()`].
+///
+/// Without parentheses, which are kept as `SqlExpr::Nested`, the right operand
+/// always binds more tightly than `op`, so a right operand that does not is
+/// rotated to the top, e.g., `a + (b > c)` is fixed to be `(a + b) > c`.
+fn rotate_binary_op(
+    left: SqlExpr,
+    op: BinaryOperator,
+    right: SqlExpr,
+) -> SqlExpr {
+    let right = match right {
+        SqlExpr::Interval(interval)
+            if matches!(interval.value.as_ref(), SqlExpr::BinaryOp { .. }) =>
+        {
+            split_interval(interval)
+        }
+        SqlExpr::BinaryOp {
+            left: inner_left,
+            op: inner_op,
+            right: inner_right,
+        } => rotate_binary_op(
+            Box::into_inner(inner_left),
+            inner_op,
+            Box::into_inner(inner_right),
+        ),
+        right => right,
+    };
+
+    match right {
+        SqlExpr::BinaryOp {
+            left: inner_left,
+            op: inner_op,
+            right: inner_right,
+        } if sql_precedence(&op).is_some_and(|precedence| {
+            sql_precedence(&inner_op).is_some_and(|inner| inner <= precedence)
+        }) =>
+        {
+            SqlExpr::BinaryOp {
+                left: Box::new(rotate_binary_op(
+                    left,
+                    op,
+                    Box::into_inner(inner_left),
+                )),
+                op: inner_op,
+                right: inner_right,
+            }
+        }
+        right => SqlExpr::BinaryOp {
+            left: Box::new(left),
+            op,
+            right: Box::new(right),
+        },
+    }
+}
+
+/// Convert a pattern matching, the pattern has to be a string literal so that
+/// it can be compiled here, only once.
+fn convert_match(
+    catalog: &Catalog,
+    expr: SqlExpr,
+    kind: PatternKind,
+    negated: bool,
+    pattern: SqlExpr,
+    escape: Option<char>,
+) -> PlanResult<Expr> {
+    let expr = convert_expr(catalog, expr)?;
+    let pattern = match convert_expr(catalog, pattern)? {
+        Expr::Literal(Data::String(pattern)) => pattern,
+        expr => return Err(PlanError::NonLiteralPattern { expr }),
+    };
+
+    Ok(Expr::Match {
+        expr: Box::new(expr),
+        pattern: Pattern::new(kind, pattern, escape)?,
+        negated,
+    })
+}
+
+/// Convert an `ORDER BY` item.
+///
+/// `NULLS FIRST/LAST` is ignored as we don't support NULL.
+pub fn convert_sort_expr(
+    catalog: &Catalog,
+    order_by: OrderByExpr,
+) -> PlanResult<SortExpr> {
+    Ok(SortExpr {
+        expr: convert_expr(catalog, order_by.expr)?,
+        asc: order_by.asc.unwrap_or(true),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use sqlparser::{dialect::PostgreSqlDialect, parser::Parser};
+
+    fn parse(sql: &str) -> Expr {
+        let sql_expr = Parser::new(&PostgreSqlDialect {})
+            .try_with_sql(sql)
+            .unwrap()
+            .parse_expr()
+            .unwrap();
+        convert_expr(&Catalog::default(), sql_expr).unwrap()
+    }
+
+    /// Display `sql` after converting it to an `Expr`, and check that the
+    /// displayed SQL is converted to the same `Expr`.
+    fn round_trip(sql: &str) -> String {
+        let expr = parse(sql);
+        let displayed = expr.to_string();
+        assert_eq!(
+            format!("{:?}", parse(&displayed)),
+            format!("{expr:?}"),
+            "{sql} is displayed as {displayed}"
+        );
+
+        displayed
+    }
+
+    #[test]
+    fn nested_exprs() {
+        assert_eq!(
+            round_trip("(a = 1 OR b = 2) AND c = 3"),
+            "(a = 1 OR b = 2) AND c = 3"
+        );
+        assert_eq!(
+            round_trip("a = 1 OR (b = 2 AND c = 3)"),
+            "a = 1 OR b = 2 AND c = 3"
+        );
+        assert_eq!(round_trip("((a))"), "a");
+        assert_eq!(round_trip("a - (b - c)"), "a - (b - c)");
+        assert_eq!(round_trip("(a - b) - c"), "a - b - c");
+        assert_eq!(round_trip("(a < b) = (c <= d)"), "a < b = (c <= d)");
+    }
+
+    #[test]
+    fn nested_special_exprs() {
+        assert_eq!(round_trip("(a LIKE 'x') = true"), "(a LIKE 'x') = true");
+        assert_eq!(round_trip("(a = b) LIKE 'x'"), "a = b LIKE 'x'");
+        assert_eq!(round_trip("a ~ ('^x')"), "a ~ '^x'");
+        assert_eq!(
+            round_trip("a BETWEEN (b = c) AND (1 + 2)"),
+            "a BETWEEN (b = c) AND 1 + 2"
+        );
+        assert_eq!(
+            round_trip("(a OR b) NOT IN (true, (c AND d))"),
+            "(a OR b) NOT IN (true, c AND d)"
+        );
+        assert_eq!(
+            round_trip("CASE (a) WHEN (1) THEN (b OR c) ELSE d END"),
+            "CASE a WHEN 1 THEN b OR c ELSE d END"
+        );
+        assert_eq!(round_trip("count(*) + sum((a))"), "count(*) + sum(a)");
+    }
+
+    #[test]
+    fn literals_and_identifiers() {
+        assert_eq!(round_trip("1.0 + -2.5"), "1.0 + -2.5");
+        assert_eq!(round_trip("a - -1"), "a - -1");
+        assert_eq!(round_trip("+1"), "1");
+        assert_eq!(round_trip("'it''s'"), "'it''s'");
+        assert_eq!(
+            round_trip("\"my col\" + \"A\"\"B\""),
+            "\"my col\" + \"A\"\"B\""
+        );
+        assert_eq!(round_trip("\"true\" AND true"), "\"true\" AND true");
+    }
+
+    #[test]
+    fn datetime_exprs() {
+        assert_eq!(
+            round_trip("a + INTERVAL '1 day' > b - INTERVAL '2' HOUR"),
+            "a + INTERVAL '1 day' > b - INTERVAL '02:00:00'"
+        );
+        assert_eq!(
+            round_trip("INTERVAL '1 day' - INTERVAL '1 hour' = c AND d"),
+            "INTERVAL '1 day' - INTERVAL '01:00:00' = c AND d"
+        );
+        assert_eq!(
+            round_trip("DATE '2024-01-01' + 1"),
+            "DATE '2024-01-01' + 1"
+        );
+        assert_eq!(round_trip("extract(year FROM a)"), "date_part('year', a)");
+        assert_eq!(
+            round_trip(
+                "date_trunc('day', a) = TIMESTAMP '2024-01-01 00:00:00'"
+            ),
+            "date_trunc('day', a) = TIMESTAMP '2024-01-01 00:00:00'"
+        );
+    }
+
+    #[test]
+    fn json_exprs() {
+        assert_eq!(
+            round_trip("a -> 'k' = b AND c ->> 0 LIKE 'x%'"),
+            "json_extract_path(a, 'k') = b AND json_extract_path_text(c, 0) LIKE 'x%'"
+        );
+        assert_eq!(
+            round_trip("a -> 'k' -> 1 #>> '{x, \"y z\"}'"),
+            "json_extract_path_text(json_extract_path(json_extract_path(a, 'k'), 1), 'x', 'y z')"
+        );
+        assert_eq!(
+            round_trip("'[1]' <@ a AND a @> (b -> 'c')"),
+            "jsonb_contains(a, JSON '[1]') AND jsonb_contains(a, json_extract_path(b, 'c'))"
+        );
+    }
+
+    #[test]
+    fn array_exprs() {
+        assert_eq!(
+            round_trip("ARRAY[1, a][2] = [b][1][1]"),
+            "array_extract(make_array(1, a), 2) = array_extract(array_extract(make_array(b), 1), 1)"
+        );
+        assert_eq!(
+            round_trip("(a + 1) = ANY(b) AND c < ANY(ARRAY[d])"),
+            "a + 1 = ANY(b) AND c < ANY(make_array(d))"
+        );
+        assert_eq!(round_trip("(a = ANY(b)) = true"), "a = ANY(b) = true");
+    }
+
+    #[test]
+    fn collate_exprs() {
+        assert_eq!(
+            round_trip("a COLLATE NoCase = (b COLLATE binary) COLLATE unicode"),
+            "a COLLATE nocase = (b COLLATE binary) COLLATE unicode"
+        );
+        assert_eq!(
+            round_trip("(a + b) COLLATE nocase < 'x' COLLATE nocase"),
+            "(a + b) COLLATE nocase < 'x' COLLATE nocase"
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! For converting function calls.
+
+use super::{
+    error::{PlanError, PlanResult, UnimplementedFeature},
+    expr::{convert_expr, convert_sort_expr},
+};
+use crate::{
+    catalog::Catalog,
+    data::types::Data,
+    expr::Expr,
+    function::{
+        aggregate::AggregateExpr,
+        scalar::ScalarFunctionExpr,
+        window::{WindowExpr, WindowFrame, WindowFrameBound, WindowFrameUnits},
+    },
+};
+use sqlparser::ast::{
+    Expr as SqlExpr, Function, FunctionArg, FunctionArgExpr,
+    WindowFrame as SqlWindowFrame, WindowFrameBound as SqlWindowFrameBound,
+    WindowFrameUnits as SqlWindowFrameUnits, WindowType,
+};
+
+pub(crate) fn convert_function(
+    catalog: &Catalog,
+    function: Function,
+) -> PlanResult<Expr> {
+    let unsupported = |function: Function| {
+        PlanError::Unimplemented(UnimplementedFeature::Expr {
+            expr: SqlExpr::Function(function),
+        })
+    };
+
+    let name = function.name.to_string();
+    if let Some(func) = catalog.functions().scalar_function(&name) {
+        if function.distinct
+            || function.filter.is_some()
+            || function.null_treatment.is_some()
+            || !function.order_by.is_empty()
+            || function.over.is_some()
+        {
+            return Err(unsupported(function));
+        }
+        let mut args = Vec::with_capacity(function.args.len());
+        for arg in function.args.iter().cloned() {
+            match arg {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => {
+                    args.push(convert_expr(catalog, expr)?)
+                }
+                _ => return Err(unsupported(function)),
+            }
+        }
+
+        return Ok(Expr::ScalarFunction(ScalarFunctionExpr {
+            func: func.clone(),
+            args,
+        }));
+    }
+    let Some(func) = catalog.functions().aggregate_function(&name) else {
+        return Err(PlanError::FunctionDoesNotExist { name });
+    };
+    if function.distinct
+        || function.filter.is_some()
+        || function.null_treatment.is_some()
+        || !function.order_by.is_empty()
+    {
+        return Err(unsupported(function));
+    }
+
+    let n_args = function.args.len();
+    let mut args = Vec::with_capacity(n_args);
+    for arg in function.args.iter().cloned() {
+        match arg {
+            FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => {
+                args.push(convert_expr(catalog, expr)?)
+            }
+            // `count(*)`
+            FunctionArg::Unnamed(FunctionArgExpr::Wildcard)
+                if func.name() == "count" && n_args == 1 => {}
+            _ => return Err(unsupported(function)),
+        }
+    }
+    let aggr = AggregateExpr {
+        func: func.clone(),
+        args,
+    };
+
+    match function.over {
+        None => {
+            if aggr.args.iter().any(|arg| arg.contains_aggregate()) {
+                return Err(PlanError::MisplacedAggregate {
+                    clause: "arguments of aggregate functions",
+                });
+            }
+            Ok(Expr::Aggregate(aggr))
+        }
+        Some(WindowType::WindowSpec(spec)) => {
+            let partition_by = spec
+                .partition_by
+                .into_iter()
+                .map(|expr| convert_expr(catalog, expr))
+                .collect::<PlanResult<Vec<_>>>()?;
+            let order_by = spec
+                .order_by
+                .into_iter()
+                .map(|order_by| convert_sort_expr(catalog, order_by))
+                .collect::<PlanResult<Vec<_>>>()?;
+            let frame = spec
+                .window_frame
+                .map(|frame| convert_window_frame(catalog, frame))
+                .transpose()?;
+
+            Ok(Expr::Window(Box::new(WindowExpr {
+                aggr,
+                partition_by,
+                order_by,
+                frame,
+            })))
+        }
+        Some(WindowType::NamedWindow(_)) => Err(unsupported(function)),
+    }
+}
+
+fn convert_window_frame(
+    catalog: &Catalog,
+    frame: SqlWindowFrame,
+) -> PlanResult<WindowFrame> {
+    let units = match frame.units {
+        SqlWindowFrameUnits::Rows => WindowFrameUnits::Rows,
+        SqlWindowFrameUnits::Range => WindowFrameUnits::Range,
+        SqlWindowFrameUnits::Groups => {
+            return Err(PlanError::Unimplemented(
+                UnimplementedFeature::WindowFrame {
+                    frame: display_frame(&frame),
+                },
+            ))
+        }
+    };
+
+    let convert_bound = |bound: &SqlWindowFrameBound| -> PlanResult<_> {
+        let offset = |expr: &Option<Box<SqlExpr>>| -> PlanResult<_> {
+            let Some(expr) = expr else {
+                return Ok(None);
+            };
+            if units == WindowFrameUnits::Range {
+                return Err(PlanError::Unimplemented(
+                    UnimplementedFeature::WindowFrame {
+                        frame: display_frame(&frame),
+                    },
+                ));
+            }
+            match convert_expr(catalog, expr.as_ref().clone())? {
+                Expr::Literal(Data::Int64(n)) if n >= 0 => Ok(Some(n as usize)),
+                _ => Err(PlanError::InvalidWindowFrame {
+                    frame: display_frame(&frame),
+                }),
+            }
+        };
+
+        Ok(match bound {
+            SqlWindowFrameBound::CurrentRow => WindowFrameBound::CurrentRow,
+            SqlWindowFrameBound::Preceding(n) => {
+                WindowFrameBound::Preceding(offset(n)?)
+            }
+            SqlWindowFrameBound::Following(n) => {
+                WindowFrameBound::Following(offset(n)?)
+            }
+        })
+    };
+
+    let start = convert_bound(&frame.start_bound)?;
+    let end = frame
+        .end_bound
+        .as_ref()
+        .map(convert_bound)
+        .transpose()?
+        .unwrap_or(WindowFrameBound::CurrentRow);
+    if start == WindowFrameBound::Following(None)
+        || end == WindowFrameBound::Preceding(None)
+    {
+        return Err(PlanError::InvalidWindowFrame {
+            frame: display_frame(&frame),
+        });
+    }
+
+    Ok(WindowFrame { units, start, end })
+}
+
+/// `sqlparser::ast::WindowFrame` does not implement `Display`.
+fn display_frame(frame: &SqlWindowFrame) -> String {
+    format!(
+        "{} BETWEEN {} AND {}",
+        frame.units,
+        frame.start_bound,
+        frame
+            .end_bound
+            .as_ref()
+            .unwrap_or(&SqlWindowFrameBound::CurrentRow)
+    )
+}
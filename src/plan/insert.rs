@@ -1,14 +1,25 @@
 use super::error::{PlanError, UnimplementedFeature};
 use crate::{
-    catalog::Catalog,
+    catalog::{schema::Schema, vinyl_table, Catalog, Table},
+    data::types::DataType,
     error::{Error, Result},
-    logical_plan::LogicalPlan,
+    expr::Expr,
+    logical_plan::{InsertValue, LogicalPlan, OnConflict, Returning},
     plan::{
+        expr::convert_expr,
         object_name_to_table_name::object_name_to_table_name,
-        values2tuples::values_to_tuples,
+        query,
+        values2tuples::{
+            check_column_type, check_not_generated_always,
+            check_number_of_values, convert_default, values_to_rows,
+        },
     },
 };
-use sqlparser::ast::{SetExpr, Statement};
+use sqlparser::ast::{
+    ConflictTarget, DoUpdate, Expr as SqlExpr, Ident,
+    OnConflict as SqlOnConflict, OnConflictAction, OnInsert, Query, SelectItem,
+    SetExpr, SqliteOnConflict, Statement,
+};
 
 pub(crate) fn convert(
     catalog: &Catalog,
@@ -16,27 +27,96 @@ pub(crate) fn convert(
 ) -> Result<LogicalPlan> {
     match statement.clone() {
         Statement::Insert {
-            table_name, source, ..
+            table_name,
+            columns,
+            source,
+            or,
+            on,
+            returning,
+            ..
         } => {
             let table_name = object_name_to_table_name(table_name)?;
+            // the catalog is only written by DDL, and it is not versioned
+            if table_name == vinyl_table::TABLE_NAME {
+                return Err(Error::PlanError(PlanError::ModifyCatalogTable {
+                    table: table_name,
+                }));
+            }
             // check catalog
-            catalog.get_table(&table_name)?;
-            let Some(source) = source else {
-                return Err(Error::PlanError(PlanError::Unimplemented(
-                    UnimplementedFeature::Statement { statement },
-                )));
+            let table = &catalog.get_table(&table_name)?;
+            let on_conflict = match (or, on) {
+                (None, None) => OnConflict::Error,
+                (Some(SqliteOnConflict::Ignore), None) => OnConflict::DoNothing,
+                (Some(SqliteOnConflict::Replace), None) => OnConflict::Replace,
+                (None, Some(OnInsert::OnConflict(on_conflict))) => {
+                    convert_on_conflict(catalog, table, on_conflict)?
+                        .ok_or_else(|| {
+                            Error::PlanError(PlanError::Unimplemented(
+                                UnimplementedFeature::Statement {
+                                    statement: statement.clone(),
+                                },
+                            ))
+                        })?
+                }
+                _ => {
+                    return Err(Error::PlanError(PlanError::Unimplemented(
+                        UnimplementedFeature::Statement { statement },
+                    )))
+                }
             };
-            let source = Box::into_inner(source);
-            let body = Box::into_inner(source.body);
-            let SetExpr::Values(values) = body else {
-                return Err(Error::PlanError(PlanError::Unimplemented(
-                    UnimplementedFeature::Statement { statement },
-                )));
+            let columns = convert_columns(table, columns)?;
+            let (input, values) = match source {
+                // `DEFAULT VALUES`
+                None => {
+                    let values = (0..table.n_columns())
+                        .map(|idx| {
+                            convert_default(catalog, table, idx)
+                                .map(InsertValue::Default)
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    (LogicalPlan::OneRowPlaceholder, values)
+                }
+                Some(source) => {
+                    let source = Box::into_inner(source);
+                    match Box::into_inner(source.body) {
+                        SetExpr::Values(values) => {
+                            let rows = values_to_rows(
+                                catalog,
+                                &table_name,
+                                columns.as_deref(),
+                                values,
+                            )?;
+                            values_plan(
+                                catalog,
+                                table,
+                                columns.as_deref(),
+                                rows,
+                            )?
+                        }
+                        body => {
+                            let query = Query {
+                                body: Box::new(body),
+                                ..source
+                            };
+                            convert_query(
+                                catalog,
+                                table,
+                                columns.as_deref(),
+                                query,
+                            )?
+                        }
+                    }
+                }
             };
-            let tuples = values_to_tuples(catalog, &table_name, values)?;
+            let returning = returning
+                .map(|items| convert_returning(catalog, table.schema(), items))
+                .transpose()?;
             Ok(LogicalPlan::Insert {
                 table: table_name,
-                rows: tuples,
+                values,
+                on_conflict,
+                returning,
+                input: Box::new(input),
             })
         }
         // SAFETY:
@@ -44,3 +124,275 @@ pub(crate) fn convert(
         _ => unsafe { std::hint::unreachable_unchecked() },
     }
 }
+
+/// Convert the target column list of an `INSERT` into `table` to column
+/// indexes, `None` will be returned if it is empty, i.e., all the columns are
+/// implied.
+fn convert_columns(
+    table: &Table,
+    columns: Vec<Ident>,
+) -> Result<Option<Vec<usize>>> {
+    if columns.is_empty() {
+        return Ok(None);
+    }
+    let mut indexes = Vec::with_capacity(columns.len());
+    for column in columns {
+        let idx = table.schema().index_of_column(&column.value)?;
+        if indexes.contains(&idx) {
+            return Err(Error::PlanError(PlanError::DuplicateInsertColumn {
+                column: column.value,
+            }));
+        }
+        indexes.push(idx);
+    }
+
+    Ok(Some(indexes))
+}
+
+/// Return the input of an `INSERT` of `rows`, which are the values of
+/// `columns` of `table`, the omitted columns are filled with their
+/// `DEFAULT`s.
+fn values_plan(
+    catalog: &Catalog,
+    table: &Table,
+    columns: Option<&[usize]>,
+    rows: Vec<Vec<Expr>>,
+) -> Result<(LogicalPlan, Vec<InsertValue>)> {
+    let all_columns = (0..table.n_columns()).collect::<Vec<_>>();
+    let columns = columns.unwrap_or(&all_columns);
+    let datatypes = table.schema().column_datatypes().collect::<Vec<_>>();
+    let names = table.schema().column_names().collect::<Vec<_>>();
+    let schema = Schema::new(
+        columns
+            .iter()
+            .map(|&idx| (names[idx].to_string(), datatypes[idx].clone()))
+            .collect::<Vec<_>>(),
+    );
+    let input = LogicalPlan::Values { rows, schema };
+
+    Ok((input, complete_values(catalog, table, columns)?))
+}
+
+/// Return the columns of the rows inserted into `table`, the `i`th column of
+/// the input is inserted into `columns[i]`, and the others are filled with
+/// their `DEFAULT`s.
+fn complete_values(
+    catalog: &Catalog,
+    table: &Table,
+    columns: &[usize],
+) -> Result<Vec<InsertValue>> {
+    let mut values = vec![None; table.n_columns()];
+    for (input_idx, &idx) in columns.iter().enumerate() {
+        values[idx] = Some(InsertValue::Input(input_idx));
+    }
+
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(idx, value)| match value {
+            Some(value) => Ok(value),
+            None => {
+                convert_default(catalog, table, idx).map(InsertValue::Default)
+            }
+        })
+        .collect()
+}
+
+/// Convert the `query` of `INSERT INTO table [(columns)] query`, every column
+/// of it is type-checked against the column it is inserted into, the omitted
+/// columns are filled with their `DEFAULT`s.
+fn convert_query(
+    catalog: &Catalog,
+    table: &Table,
+    columns: Option<&[usize]>,
+    query: Query,
+) -> Result<(LogicalPlan, Vec<InsertValue>)> {
+    let input = query::convert(catalog, Statement::Query(Box::new(query)))?;
+    let input_schema = input.schema(catalog)?;
+    check_number_of_values(table, columns, input_schema.n_columns())?;
+
+    let all_columns = (0..table.n_columns()).collect::<Vec<_>>();
+    let columns = columns.unwrap_or(&all_columns);
+    for (&idx, found) in columns.iter().zip(input_schema.column_datatypes()) {
+        check_not_generated_always(table, idx)?;
+        check_column_type(table, idx, found.clone())?;
+    }
+    let values = complete_values(catalog, table, columns)?;
+
+    Ok((input, values))
+}
+
+/// Convert the `RETURNING` list of a DML statement on a table of `schema`.
+pub(crate) fn convert_returning(
+    catalog: &Catalog,
+    schema: &Schema,
+    items: Vec<SelectItem>,
+) -> Result<Returning> {
+    let mut exprs = Vec::with_capacity(items.len());
+    let mut columns = Vec::with_capacity(items.len());
+    for item in items {
+        let (expr, name) = match item {
+            SelectItem::UnnamedExpr(expr) => {
+                let expr = convert_expr(catalog, expr)?;
+                let name = match &expr {
+                    Expr::Column(name) => name.clone(),
+                    expr => expr.to_string(),
+                };
+                (expr, name)
+            }
+            SelectItem::ExprWithAlias { expr, alias } => {
+                (convert_expr(catalog, expr)?, alias.value)
+            }
+            SelectItem::QualifiedWildcard(_, _) | SelectItem::Wildcard(_) => {
+                for (name, datatype) in schema.columns() {
+                    exprs.push(Expr::Column(name.clone()));
+                    columns.push((name.clone(), datatype.clone()));
+                }
+                continue;
+            }
+        };
+        if expr.contains_aggregate() {
+            return Err(Error::PlanError(PlanError::MisplacedAggregate {
+                clause: "RETURNING",
+            }));
+        }
+        if expr.contains_window() {
+            return Err(Error::PlanError(PlanError::MisplacedWindow {
+                clause: "RETURNING",
+            }));
+        }
+        // type-check it so that it won't fail during execution
+        columns.push((name, expr.datatype(schema)?));
+        exprs.push(expr);
+    }
+
+    Ok(Returning {
+        exprs,
+        schema: Schema::new(columns),
+    })
+}
+
+/// Convert the `ON CONFLICT` clause of an `INSERT` into `table`, `None` will
+/// be returned if it is not supported.
+fn convert_on_conflict(
+    catalog: &Catalog,
+    table: &Table,
+    on_conflict: SqlOnConflict,
+) -> Result<Option<OnConflict>> {
+    let schema = table.schema();
+    let pk_column = schema
+        .column_names()
+        .nth(table.pk())
+        .expect("pk should be a column");
+    match on_conflict.conflict_target {
+        // the primary key is the only unique constraint
+        Some(ConflictTarget::Columns(columns)) => {
+            if !matches!(columns.as_slice(), [column] if column.value == pk_column)
+            {
+                return Err(Error::PlanError(
+                    PlanError::NoMatchingConflictTarget,
+                ));
+            }
+        }
+        Some(ConflictTarget::OnConstraint(_)) => return Ok(None),
+        None if matches!(on_conflict.action, OnConflictAction::DoUpdate(_)) => {
+            return Err(Error::PlanError(PlanError::ConflictTargetRequired));
+        }
+        None => {}
+    }
+
+    let DoUpdate {
+        assignments,
+        selection,
+    } = match on_conflict.action {
+        OnConflictAction::DoNothing => return Ok(Some(OnConflict::DoNothing)),
+        OnConflictAction::DoUpdate(do_update) => do_update,
+    };
+    let update_schema = do_update_schema(table);
+    let mut converted: Vec<(usize, _)> = Vec::with_capacity(assignments.len());
+    for assignment in assignments {
+        let [column] = assignment.id.as_slice() else {
+            return Ok(None);
+        };
+        let column = &column.value;
+        let idx = schema.index_of_column(column)?;
+        if idx == table.pk() {
+            return Err(Error::PlanError(
+                PlanError::UpdatePrimaryKeyOnConflict {
+                    column: column.clone(),
+                },
+            ));
+        }
+        if converted.iter().any(|(assigned, _)| *assigned == idx) {
+            return Err(Error::PlanError(PlanError::MultipleAssignments {
+                column: column.clone(),
+            }));
+        }
+
+        let expr = convert_update_expr(catalog, assignment.value)?;
+        check_column_type(table, idx, expr.datatype(&update_schema)?)?;
+        converted.push((idx, expr));
+    }
+
+    let selection = match selection {
+        Some(selection) => {
+            let selection = convert_update_expr(catalog, selection)?;
+            let datatype = selection.datatype(&update_schema)?;
+            if datatype != DataType::Bool {
+                return Err(Error::PlanError(PlanError::NonBoolPredicate {
+                    clause: "WHERE",
+                    datatype,
+                }));
+            }
+            Some(selection)
+        }
+        None => None,
+    };
+
+    Ok(Some(OnConflict::DoUpdate {
+        assignments: converted,
+        selection,
+        schema: update_schema,
+    }))
+}
+
+/// Convert an expression of `ON CONFLICT DO UPDATE`.
+fn convert_update_expr(catalog: &Catalog, expr: SqlExpr) -> Result<Expr> {
+    let expr = convert_expr(catalog, expr)?;
+    if expr.contains_aggregate() {
+        return Err(Error::PlanError(PlanError::MisplacedAggregate {
+            clause: "ON CONFLICT DO UPDATE",
+        }));
+    }
+    if expr.contains_window() {
+        return Err(Error::PlanError(PlanError::MisplacedWindow {
+            clause: "ON CONFLICT DO UPDATE",
+        }));
+    }
+
+    Ok(expr)
+}
+
+/// Return the schema that `ON CONFLICT DO UPDATE` is evaluated against, see
+/// [`OnConflict::DoUpdate`].
+fn do_update_schema(table: &Table) -> Schema {
+    let schema = table.schema();
+    let qualifiers = [None, Some(table.name()), Some("excluded")];
+    let name = |qualifier: Option<&str>, column: &str| match qualifier {
+        Some(qualifier) => format!("{qualifier}.{column}"),
+        None => column.to_string(),
+    };
+
+    let columns = qualifiers.iter().flat_map(|qualifier| {
+        schema.columns().map(|(column, datatype)| {
+            (name(*qualifier, column), datatype.clone())
+        })
+    });
+    let collations = qualifiers.iter().flat_map(|qualifier| {
+        schema.column_names().map(|column| {
+            (name(*qualifier, column), schema.column_collation(column))
+        })
+    });
+
+    Schema::new(columns.collect::<Vec<_>>()).with_collations(collations)
+}
@@ -1,18 +1,27 @@
 //! Converting a SQL AST to a Logical Plan.
 
+pub mod aggregate;
+pub mod collation;
+pub mod create_sequence;
 pub mod create_table;
+pub mod create_type;
 pub mod datatype;
 pub mod error;
 pub mod explain;
 pub mod explain_table;
 pub mod expr;
+pub mod function;
 pub mod insert;
 pub mod object_name_to_table_name;
 pub mod op;
 pub mod query;
+pub mod set_variable;
 pub mod show_tables;
+pub mod show_variable;
+pub mod transaction;
 pub mod value2data;
 pub mod values2tuples;
+pub mod window;
 
 use crate::{
     catalog::Catalog,
@@ -31,6 +40,12 @@ pub(crate) fn statement_to_logical_plan(
         Statement::CreateTable { .. } => {
             create_table::convert(catalog, statement)
         }
+        Statement::CreateSequence { .. } => {
+            create_sequence::convert(catalog, statement)
+        }
+        Statement::CreateType { .. } => {
+            create_type::convert(catalog, statement)
+        }
         Statement::Explain { .. } => explain::convert(catalog, statement),
         Statement::ShowTables { .. } => {
             show_tables::convert(catalog, statement)
@@ -41,6 +56,17 @@ pub(crate) fn statement_to_logical_plan(
         }
         Statement::Insert { .. } => insert::convert(catalog, statement),
         Statement::Query(_) => query::convert(catalog, statement),
+        Statement::SetTimeZone { .. } | Statement::SetVariable { .. } => {
+            set_variable::convert(catalog, statement)
+        }
+        Statement::ShowVariable { .. } => {
+            show_variable::convert(catalog, statement)
+        }
+        Statement::StartTransaction { .. }
+        | Statement::Commit { .. }
+        | Statement::Rollback { .. }
+        | Statement::Savepoint { .. }
+        | Statement::ReleaseSavepoint { .. } => transaction::convert(statement),
         _ => Err(Error::PlanError(PlanError::Unimplemented(
             UnimplementedFeature::Statement { statement },
         ))),
@@ -1,5 +1,6 @@
 use crate::{
     expr::Operator,
+    pattern::PatternKind,
     plan::error::{PlanError, PlanResult, UnimplementedFeature},
 };
 use sqlparser::ast::BinaryOperator;
@@ -21,3 +22,23 @@ pub(crate) fn convert_op(op: BinaryOperator) -> PlanResult<Operator> {
         })),
     }
 }
+
+/// Convert PostgreSQL pattern matching operators, e.g. `~` and `~~`, return
+/// `None` if `op` is not one of them.
+///
+/// The second element of the returned tuple indicates if it is negated.
+pub(crate) fn convert_pattern_op(
+    op: &BinaryOperator,
+) -> Option<(PatternKind, bool)> {
+    match op {
+        BinaryOperator::PGRegexMatch => Some((PatternKind::Regex, false)),
+        BinaryOperator::PGRegexIMatch => Some((PatternKind::IRegex, false)),
+        BinaryOperator::PGRegexNotMatch => Some((PatternKind::Regex, true)),
+        BinaryOperator::PGRegexNotIMatch => Some((PatternKind::IRegex, true)),
+        BinaryOperator::PGLikeMatch => Some((PatternKind::Like, false)),
+        BinaryOperator::PGILikeMatch => Some((PatternKind::ILike, false)),
+        BinaryOperator::PGNotLikeMatch => Some((PatternKind::Like, true)),
+        BinaryOperator::PGNotILikeMatch => Some((PatternKind::ILike, true)),
+        _ => None,
+    }
+}
@@ -1,22 +1,26 @@
 use super::{
-    error::{PlanError, UnimplementedFeature},
-    expr::convert_expr,
+    aggregate::plan_aggregate,
+    error::{PlanError, PlanResult, UnimplementedFeature},
+    expr::{convert_expr, convert_sort_expr},
+    window::plan_window,
 };
 use crate::{
     catalog::{schema::Schema, Catalog},
     data::types::{Data, DataType},
     error::{Error, Result},
-    expr::Expr,
+    expr::{Expr, SortExpr},
+    function::unsupported_type,
     logical_plan::LogicalPlan,
     plan::object_name_to_table_name::object_name_to_table_name,
 };
 use sqlparser::ast::{
-    Expr as SQLExpr, Query, SelectItem, SetExpr, Statement, TableFactor,
+    Expr as SQLExpr, GroupByExpr, OrderByExpr, Query, SelectItem, SetExpr,
+    Statement, TableAlias, TableFactor, Value,
 };
 use std::{num::NonZeroUsize, ops::Deref};
 
-fn evaluate_limit(expr: SQLExpr) -> Result<usize> {
-    let expr = convert_expr(expr)?;
+fn evaluate_limit(catalog: &Catalog, expr: SQLExpr) -> Result<usize> {
+    let expr = convert_expr(catalog, expr)?;
     assert!(expr.is_constant());
     let data = expr.evaluate_constant_expr().unwrap();
     let Data::Int64(limit) = data else {
@@ -32,8 +36,11 @@ fn evaluate_limit(expr: SQLExpr) -> Result<usize> {
     Ok(limit)
 }
 
-fn evaluate_offset(expr: SQLExpr) -> Result<Option<NonZeroUsize>> {
-    let offset = evaluate_limit(expr)?;
+fn evaluate_offset(
+    catalog: &Catalog,
+    expr: SQLExpr,
+) -> Result<Option<NonZeroUsize>> {
+    let offset = evaluate_limit(catalog, expr)?;
     let offset = match offset {
         0 => None,
         // SAFETY: it won't be 0
@@ -43,7 +50,43 @@ fn evaluate_offset(expr: SQLExpr) -> Result<Option<NonZeroUsize>> {
     Ok(offset)
 }
 
-fn select_without_from(query: Query) -> Result<LogicalPlan> {
+/// Convert the items of `ORDER BY`, an item can also be the name or the
+/// position (starting from 1) of an output column, which is replaced with the
+/// expression of that column in `projection`, whose names are `names`.
+fn convert_order_by(
+    catalog: &Catalog,
+    order_by: Vec<OrderByExpr>,
+    names: &[String],
+    projection: &[Expr],
+) -> Result<Vec<SortExpr>> {
+    order_by
+        .into_iter()
+        .map(|order_by| {
+            let position = match &order_by.expr {
+                SQLExpr::Value(Value::Number(n, _)) => n.parse::<usize>().ok(),
+                _ => None,
+            };
+            let mut sort = convert_sort_expr(catalog, order_by)?;
+            if let Some(position) = position {
+                sort.expr = position
+                    .checked_sub(1)
+                    .and_then(|idx| projection.get(idx))
+                    .cloned()
+                    .ok_or(Error::PlanError(
+                        PlanError::OrderByPositionOutOfRange { position },
+                    ))?;
+            } else if let Expr::Column(name) = &sort.expr {
+                if let Some(idx) = names.iter().position(|n| n == name) {
+                    sort.expr = projection[idx].clone();
+                }
+            }
+
+            Ok(sort)
+        })
+        .collect()
+}
+
+fn select_without_from(catalog: &Catalog, query: Query) -> Result<LogicalPlan> {
     let body = Box::into_inner(query.body);
     let SetExpr::Select(select) = body else {
         unreachable!()
@@ -52,7 +95,7 @@ fn select_without_from(query: Query) -> Result<LogicalPlan> {
     let mut base = LogicalPlan::OneRowPlaceholder;
 
     if let Some(expr) = select.selection {
-        let expr = convert_expr(expr)?;
+        let expr = convert_expr(catalog, expr)?;
         if !expr.is_constant() {
             return Err(Error::PlanError(
                 PlanError::NonConstantExprWithoutFrom { expr },
@@ -67,8 +110,8 @@ fn select_without_from(query: Query) -> Result<LogicalPlan> {
 
     match (query.limit, query.offset) {
         (Some(limit), Some(offset)) => {
-            let limit = Some(evaluate_limit(limit)?);
-            let offset = evaluate_offset(offset.value)?;
+            let limit = Some(evaluate_limit(catalog, limit)?);
+            let offset = evaluate_offset(catalog, offset.value)?;
 
             base = LogicalPlan::Limit {
                 offset,
@@ -77,7 +120,7 @@ fn select_without_from(query: Query) -> Result<LogicalPlan> {
             }
         }
         (Some(limit), None) => {
-            let limit = Some(evaluate_limit(limit)?);
+            let limit = Some(evaluate_limit(catalog, limit)?);
             base = LogicalPlan::Limit {
                 offset: None,
                 limit,
@@ -85,7 +128,7 @@ fn select_without_from(query: Query) -> Result<LogicalPlan> {
             }
         }
         (None, Some(offset)) => {
-            let offset = evaluate_offset(offset.value)?;
+            let offset = evaluate_offset(catalog, offset.value)?;
             if offset.is_some() {
                 base = LogicalPlan::Limit {
                     offset,
@@ -105,7 +148,7 @@ fn select_without_from(query: Query) -> Result<LogicalPlan> {
     for proj in projs {
         match proj {
             SelectItem::UnnamedExpr(expr) => {
-                let expr = convert_expr(expr)?;
+                let expr = convert_expr(catalog, expr)?;
                 if !expr.is_constant() {
                     return Err(Error::PlanError(
                         PlanError::NonConstantExprWithoutFrom { expr },
@@ -118,7 +161,7 @@ fn select_without_from(query: Query) -> Result<LogicalPlan> {
                 exprs.push(expr);
             }
             SelectItem::ExprWithAlias { expr, alias } => {
-                let expr = convert_expr(expr)?;
+                let expr = convert_expr(catalog, expr)?;
                 if !expr.is_constant() {
                     return Err(Error::PlanError(
                         PlanError::NonConstantExprWithoutFrom { expr },
@@ -134,6 +177,20 @@ fn select_without_from(query: Query) -> Result<LogicalPlan> {
         }
     }
 
+    // there is at most 1 row, so there is nothing to sort
+    let names = columns
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+    for sort in convert_order_by(catalog, query.order_by, &names, &exprs)? {
+        if !sort.expr.is_constant() {
+            return Err(Error::PlanError(
+                PlanError::NonConstantExprWithoutFrom { expr: sort.expr },
+            ));
+        }
+        sort.expr.datatype_of_constant_expr()?;
+    }
+
     let schema = Schema::new(columns);
     base = LogicalPlan::Projection {
         expr: exprs,
@@ -162,31 +219,144 @@ fn select_with_from(
             UnimplementedFeature::Statement { statement },
         )));
     }
-    let relation = from.relation;
-    let TableFactor::Table { name, .. } = relation else {
+    let (mut base, schema) = match from.relation {
+        TableFactor::Table { name, .. } => {
+            let table_name = object_name_to_table_name(name)?;
+            // check catalog
+            let table = catalog.get_table(&table_name)?;
+            let schema = table.schema().clone();
+
+            (LogicalPlan::TableScan { name: table_name }, schema)
+        }
+        TableFactor::UNNEST {
+            alias,
+            mut array_exprs,
+            with_offset: false,
+            ..
+        } if array_exprs.len() == 1
+            && alias
+                .as_ref()
+                .map_or(true, |alias| alias.columns.len() <= 1) =>
+        {
+            let array = array_exprs.pop().expect("there is exactly 1 array");
+            plan_unnest(catalog, array, alias)?
+        }
+        _ => {
+            return Err(Error::PlanError(PlanError::Unimplemented(
+                UnimplementedFeature::Statement { statement },
+            )));
+        }
+    };
+
+    if let Some(expr) = select.selection {
+        let expr = convert_expr(catalog, expr)?;
+        if expr.contains_aggregate() {
+            return Err(Error::PlanError(PlanError::MisplacedAggregate {
+                clause: "WHERE",
+            }));
+        }
+        if expr.contains_window() {
+            return Err(Error::PlanError(PlanError::MisplacedWindow {
+                clause: "WHERE",
+            }));
+        }
+        // type-check the predicate so that it won't fail during execution
+        expr.datatype(&schema)?;
+        base = LogicalPlan::Filter {
+            predicate: expr,
+            input: Box::new(base),
+        };
+    }
+
+    let projs = select.projection;
+    assert!(!projs.is_empty());
+
+    let mut exprs: Vec<Expr> = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+    for proj in projs {
+        match proj {
+            SelectItem::UnnamedExpr(expr) => {
+                let expr = convert_expr(catalog, expr)?;
+                names.push(match &expr {
+                    Expr::Column(name) => name.clone(),
+                    expr => expr.to_string(),
+                });
+                exprs.push(expr);
+            }
+            SelectItem::ExprWithAlias { expr, alias } => {
+                let expr = convert_expr(catalog, expr)?;
+                names.push(alias.value);
+                exprs.push(expr);
+            }
+            // treat `QualifiedWildcard` like `Wildcard` because we don't support databases.
+            SelectItem::QualifiedWildcard(_, _) | SelectItem::Wildcard(_) => {
+                exprs.extend(
+                    schema
+                        .column_names()
+                        .map(|name| Expr::Column(name.to_string())),
+                );
+                names.extend(schema.column_names().map(String::from));
+            }
+        }
+    }
+
+    // `ORDER BY` items are planned along with the projection, so that they can
+    // reference aggregate and window function calls
+    let order_by = convert_order_by(catalog, query.order_by, &names, &exprs)?;
+    let n_projected = exprs.len();
+    exprs.extend(order_by.iter().map(|sort| sort.expr.clone()));
+
+    let GroupByExpr::Expressions(group_by) = select.group_by else {
         return Err(Error::PlanError(PlanError::Unimplemented(
             UnimplementedFeature::Statement { statement },
         )));
     };
-    let table_name = object_name_to_table_name(name)?;
-    // check catalog
-    let table = catalog.get_table(&table_name)?;
-    let schema = table.schema();
+    let group_by = group_by
+        .into_iter()
+        .map(|expr| convert_expr(catalog, expr))
+        .collect::<PlanResult<Vec<_>>>()?;
+    let having = select
+        .having
+        .map(|expr| convert_expr(catalog, expr))
+        .transpose()?;
+    if having.as_ref().is_some_and(Expr::contains_window) {
+        return Err(Error::PlanError(PlanError::MisplacedWindow {
+            clause: "HAVING",
+        }));
+    }
 
-    let mut base = LogicalPlan::TableScan { name: table_name };
+    let mut input_schema = schema.clone();
+    if !group_by.is_empty()
+        || having.is_some()
+        || exprs.iter().any(Expr::contains_aggregate)
+    {
+        (base, input_schema, exprs) =
+            plan_aggregate(base, &input_schema, group_by, exprs, having)?;
+    }
+    if exprs.iter().any(Expr::contains_window) {
+        (base, input_schema, exprs) = plan_window(base, &input_schema, exprs)?;
+    }
 
-    if let Some(expr) = select.selection {
-        let expr = convert_expr(expr)?;
-        base = LogicalPlan::Filter {
-            predicate: expr,
+    let sort_exprs = exprs.split_off(n_projected);
+    if !order_by.is_empty() {
+        let order_by = order_by
+            .into_iter()
+            .zip(sort_exprs)
+            .map(|(sort, expr)| SortExpr { expr, ..sort })
+            .collect::<Vec<_>>();
+        for sort in order_by.iter() {
+            sort.expr.datatype(&input_schema)?;
+        }
+        base = LogicalPlan::Sort {
+            order_by,
             input: Box::new(base),
         };
     }
 
     match (query.limit, query.offset) {
         (Some(limit), Some(offset)) => {
-            let limit = Some(evaluate_limit(limit)?);
-            let offset = evaluate_offset(offset.value)?;
+            let limit = Some(evaluate_limit(catalog, limit)?);
+            let offset = evaluate_offset(catalog, offset.value)?;
 
             base = LogicalPlan::Limit {
                 offset,
@@ -195,7 +365,7 @@ fn select_with_from(
             }
         }
         (Some(limit), None) => {
-            let limit = Some(evaluate_limit(limit)?);
+            let limit = Some(evaluate_limit(catalog, limit)?);
             base = LogicalPlan::Limit {
                 offset: None,
                 limit,
@@ -203,7 +373,7 @@ fn select_with_from(
             }
         }
         (None, Some(offset)) => {
-            let offset = evaluate_offset(offset.value)?;
+            let offset = evaluate_offset(catalog, offset.value)?;
             if offset.is_some() {
                 base = LogicalPlan::Limit {
                     offset,
@@ -215,37 +385,9 @@ fn select_with_from(
         (None, None) => { /*do nothing*/ }
     }
 
-    let projs = select.projection;
-    assert!(!projs.is_empty());
-
-    let mut exprs: Vec<Expr> = Vec::new();
-    let mut columns: Vec<(String, DataType)> = Vec::new();
-    for proj in projs {
-        match proj {
-            SelectItem::UnnamedExpr(expr) => {
-                let expr = convert_expr(expr)?;
-                columns.push((expr.to_string(), expr.datatype(schema)?));
-                exprs.push(expr);
-            }
-            SelectItem::ExprWithAlias { expr, alias } => {
-                let expr = convert_expr(expr)?;
-                columns.push((alias.value, expr.datatype(schema)?));
-                exprs.push(expr);
-            }
-            // treat `QualifiedWildcard` like `Wildcard` because we don't support databases.
-            SelectItem::QualifiedWildcard(_, _) | SelectItem::Wildcard(_) => {
-                exprs.extend(
-                    schema
-                        .column_names()
-                        .map(|name| Expr::Column(name.to_string())),
-                );
-                columns.extend(
-                    schema
-                        .columns()
-                        .map(|(name, datatype)| (name.clone(), *datatype)),
-                );
-            }
-        }
+    let mut columns: Vec<(String, DataType)> = Vec::with_capacity(exprs.len());
+    for (name, expr) in names.into_iter().zip(exprs.iter()) {
+        columns.push((name, expr.datatype(&input_schema)?));
     }
 
     let schema = Schema::new(columns);
@@ -258,6 +400,51 @@ fn select_with_from(
     Ok(base)
 }
 
+/// Plan `UNNEST(array)`, which produces a row for every element of `array`,
+/// `array` has to be a constant.
+///
+/// The column is named after the column alias, or the table alias, or
+/// `unnest` if there is no alias.
+fn plan_unnest(
+    catalog: &Catalog,
+    array: SQLExpr,
+    alias: Option<TableAlias>,
+) -> Result<(LogicalPlan, Schema)> {
+    let array = convert_expr(catalog, array)?;
+    if !array.is_constant() {
+        return Err(Error::PlanError(PlanError::NonConstantUnnest {
+            expr: array,
+        }));
+    }
+    let element_type = match array.datatype_of_constant_expr()? {
+        DataType::List(element_type) => Box::into_inner(element_type),
+        datatype => return Err(unsupported_type("unnest", &datatype)),
+    };
+    let Data::List(list) = array.evaluate_constant_expr()? else {
+        unreachable!("type has been checked")
+    };
+    let name = match alias {
+        Some(TableAlias { mut columns, name }) => {
+            columns.pop().unwrap_or(name).value
+        }
+        None => "unnest".to_string(),
+    };
+    let rows = list
+        .into_elements()
+        .into_iter()
+        .map(|element| vec![Expr::Literal(element)])
+        .collect();
+    let schema = Schema::new(vec![(name, element_type)]);
+
+    Ok((
+        LogicalPlan::Values {
+            rows,
+            schema: schema.clone(),
+        },
+        schema,
+    ))
+}
+
 pub(crate) fn convert(
     catalog: &Catalog,
     statement: Statement,
@@ -273,13 +460,11 @@ pub(crate) fn convert(
             let query = Box::into_inner(query);
 
             match select_from_len {
-                0 => Ok(select_without_from(query)?),
+                0 => Ok(select_without_from(catalog, query)?),
                 1 => Ok(select_with_from(catalog, query, statement)?),
-                _ => {
-                    return Err(Error::PlanError(PlanError::Unimplemented(
-                        UnimplementedFeature::Statement { statement },
-                    )));
-                }
+                _ => Err(Error::PlanError(PlanError::Unimplemented(
+                    UnimplementedFeature::Statement { statement },
+                ))),
             }
         }
         // SAFETY:
@@ -0,0 +1,45 @@
+use crate::{
+    catalog::{settings::Settings, Catalog},
+    error::{Error, Result},
+    logical_plan::LogicalPlan,
+    plan::error::{PlanError, UnimplementedFeature},
+};
+use sqlparser::ast::{Expr as SqlExpr, Statement, Value};
+
+pub(crate) fn convert(
+    catalog: &Catalog,
+    statement: Statement,
+) -> Result<LogicalPlan> {
+    let unsupported = |statement: Statement| {
+        Error::PlanError(PlanError::Unimplemented(
+            UnimplementedFeature::Statement { statement },
+        ))
+    };
+
+    let (name, value) = match &statement {
+        // `SET TIME ZONE value`
+        Statement::SetTimeZone {
+            local: false,
+            value,
+        } => (Settings::TIME_ZONE.to_string(), value),
+        // `SET name = value` and `SET name TO value`
+        Statement::SetVariable {
+            local: false,
+            hivevar: false,
+            variable,
+            value,
+        } if value.len() == 1 => (variable.to_string(), &value[0]),
+        _ => return Err(unsupported(statement)),
+    };
+    let value = match value {
+        SqlExpr::Value(Value::SingleQuotedString(value)) => value.clone(),
+        SqlExpr::Identifier(ident) => ident.value.clone(),
+        _ => return Err(unsupported(statement)),
+    };
+
+    // Validate it now so that a bad `SET` fails at planning.
+    let mut settings = *catalog.settings();
+    settings.set(&name, &value)?;
+
+    Ok(LogicalPlan::SetVariable { name, value })
+}
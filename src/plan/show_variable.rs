@@ -0,0 +1,36 @@
+use crate::{
+    catalog::{settings::Settings, Catalog},
+    error::{Error, Result},
+    logical_plan::LogicalPlan,
+    plan::error::{PlanError, UnimplementedFeature},
+};
+use sqlparser::ast::Statement;
+
+pub(crate) fn convert(
+    catalog: &Catalog,
+    statement: Statement,
+) -> Result<LogicalPlan> {
+    let Statement::ShowVariable { variable } = &statement else {
+        // SAFETY:
+        // it has already been checked
+        unsafe { std::hint::unreachable_unchecked() }
+    };
+    let name = match variable.as_slice() {
+        [name] => name.value.clone(),
+        // `SHOW TIME ZONE`
+        [time, zone]
+            if time.value.eq_ignore_ascii_case("TIME")
+                && zone.value.eq_ignore_ascii_case("ZONE") =>
+        {
+            Settings::TIME_ZONE.to_string()
+        }
+        _ => {
+            return Err(Error::PlanError(PlanError::Unimplemented(
+                UnimplementedFeature::Statement { statement },
+            )))
+        }
+    };
+    catalog.settings().get(&name)?;
+
+    Ok(LogicalPlan::ShowVariable { name })
+}
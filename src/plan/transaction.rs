@@ -0,0 +1,45 @@
+//! For converting `BEGIN`, `COMMIT`, `ROLLBACK` and the savepoint statements.
+
+use crate::{
+    error::{Error, Result},
+    logical_plan::LogicalPlan,
+    plan::error::{PlanError, UnimplementedFeature},
+    transaction::TransactionControl,
+};
+use sqlparser::ast::Statement;
+
+pub(crate) fn convert(statement: Statement) -> Result<LogicalPlan> {
+    let control = match &statement {
+        Statement::StartTransaction {
+            modes,
+            modifier: None,
+            ..
+        } if modes.is_empty() => TransactionControl::Begin,
+        Statement::Commit { chain: false } => TransactionControl::Commit,
+        Statement::Rollback {
+            chain: false,
+            savepoint: None,
+        } => TransactionControl::Rollback,
+        Statement::Savepoint { name } => TransactionControl::Savepoint {
+            name: name.value.clone(),
+        },
+        Statement::Rollback {
+            chain: false,
+            savepoint: Some(name),
+        } => TransactionControl::RollbackToSavepoint {
+            name: name.value.clone(),
+        },
+        Statement::ReleaseSavepoint { name } => {
+            TransactionControl::ReleaseSavepoint {
+                name: name.value.clone(),
+            }
+        }
+        _ => {
+            return Err(Error::PlanError(PlanError::Unimplemented(
+                UnimplementedFeature::Statement { statement },
+            )))
+        }
+    };
+
+    Ok(LogicalPlan::Transaction { control })
+}
@@ -1,12 +1,25 @@
 use super::error::{PlanError, PlanResult, UnimplementedFeature};
-use crate::data::types::Data;
-use sqlparser::ast::Value;
+use crate::data::{
+    binary::decode_hex,
+    datetime::parse_interval,
+    decimal::Decimal,
+    types::{Data, DataType},
+};
+use chrono::FixedOffset;
+use sqlparser::ast::{
+    DataType as SQLDataType, ExactNumberInfo, Expr as SqlExpr,
+    Interval as SqlInterval, Value,
+};
 
 pub(crate) fn value_to_data(val: Value) -> PlanResult<Data> {
     match val {
         Value::Number(str, _) => {
+            // Like PostgreSQL, a number with a fraction or an exponent is an
+            // exact decimal, unless it has too many digits.
             if let Ok(num) = str.parse::<i64>() {
                 Ok(Data::Int64(num))
+            } else if let Some(num) = Decimal::parse_literal(&str) {
+                Ok(Data::Decimal(num))
             } else if let Ok(num) = str.parse::<f64>() {
                 Ok(Data::Float64(num))
             } else {
@@ -21,7 +34,14 @@ pub(crate) fn value_to_data(val: Value) -> PlanResult<Data> {
         Value::DoubleQuotedByteStringLiteral(str) => Ok(Data::String(str)),
         Value::RawStringLiteral(str) => Ok(Data::String(str)),
         Value::NationalStringLiteral(str) => Ok(Data::String(str)),
-        Value::HexStringLiteral(str) => Ok(Data::String(str)),
+        // `X'deadbeef'`
+        Value::HexStringLiteral(str) => match decode_hex(&str) {
+            Some(bytes) => Ok(Data::Binary(bytes)),
+            None => Err(PlanError::ConversionError {
+                val: Value::HexStringLiteral(str),
+                to: DataType::Binary,
+            }),
+        },
         Value::DoubleQuotedString(str) => Ok(Data::String(str)),
         Value::Boolean(val) => Ok(Data::Bool(val)),
         Value::Null => {
@@ -31,3 +51,80 @@ pub(crate) fn value_to_data(val: Value) -> PlanResult<Data> {
         Value::UnQuotedString(str) => Ok(Data::String(str)),
     }
 }
+
+/// Convert a typed string literal, e.g., `DATE '2024-01-01'`.
+///
+/// `time_zone` is the time zone of the session.
+pub(crate) fn typed_string_to_data(
+    data_type: SQLDataType,
+    value: String,
+    time_zone: FixedOffset,
+) -> PlanResult<Data> {
+    // Without a precision, a decimal literal keeps all its digits, e.g.,
+    // `DECIMAL '1.50'` has a scale of 2.
+    if let SQLDataType::Decimal(ExactNumberInfo::None)
+    | SQLDataType::Numeric(ExactNumberInfo::None)
+    | SQLDataType::Dec(ExactNumberInfo::None) = data_type
+    {
+        return Decimal::parse(&value).map(Data::Decimal).ok_or_else(|| {
+            PlanError::ConversionError {
+                to: DataType::try_from(data_type)
+                    .expect("DECIMAL is a valid type"),
+                val: Value::SingleQuotedString(value),
+            }
+        });
+    }
+    let datatype = DataType::try_from(data_type)?;
+    string_to_data(value, datatype, time_zone)
+}
+
+/// Convert a string literal to a value of `datatype`.
+///
+/// `time_zone` is the time zone of the session.
+pub(crate) fn string_to_data(
+    value: String,
+    datatype: DataType,
+    time_zone: FixedOffset,
+) -> PlanResult<Data> {
+    Data::parse(&value, &datatype, time_zone).ok_or(
+        PlanError::ConversionError {
+            val: Value::SingleQuotedString(value),
+            to: datatype,
+        },
+    )
+}
+
+/// Convert an interval literal, e.g., `INTERVAL '1 day 2 hours'` or
+/// `INTERVAL '3' MONTH`.
+///
+/// Ranges of fields like `INTERVAL '1-2' YEAR TO MONTH` are not supported.
+pub(crate) fn interval_to_data(interval: SqlInterval) -> PlanResult<Data> {
+    let unsupported = |interval: SqlInterval| {
+        PlanError::Unimplemented(UnimplementedFeature::Expr {
+            expr: SqlExpr::Interval(interval),
+        })
+    };
+
+    let value = match interval.value.as_ref() {
+        SqlExpr::Value(
+            Value::SingleQuotedString(value) | Value::Number(value, _),
+        ) => value.clone(),
+        _ => return Err(unsupported(interval)),
+    };
+    if interval.last_field.is_some()
+        || interval.fractional_seconds_precision.is_some()
+    {
+        return Err(unsupported(interval));
+    }
+    let value = match &interval.leading_field {
+        Some(field) => format!("{value} {field}"),
+        None => value,
+    };
+
+    parse_interval(&value).map(Data::Interval).ok_or(
+        PlanError::ConversionError {
+            val: Value::SingleQuotedString(value),
+            to: DataType::Interval,
+        },
+    )
+}
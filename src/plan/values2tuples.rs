@@ -1,55 +1,323 @@
 use crate::{
-    as_variant,
-    catalog::Catalog,
-    data::tuple::Tuple,
+    catalog::{schema::Schema, Catalog, Table},
+    data::types::{Data, DataType},
     error::{Error, Result},
     expr::Expr,
-    plan::{error::PlanError, expr::convert_expr},
+    plan::{error::PlanError, expr::convert_expr, value2data::string_to_data},
 };
-use sqlparser::ast::Values;
+use sqlparser::ast::{Expr as SqlExpr, Value, Values};
+use std::cmp::Ordering;
 
-/// Convert `values` to a `Vec<Tuple>`, with schema check.
-pub fn values_to_tuples(
+/// Return true if strings are parsed as values of `datatype` when they are
+/// written to a column of it, i.e., date/time, binary, JSON, UUID, enum and
+/// array columns.
+fn is_parsed_from_string(datatype: &DataType) -> bool {
+    datatype.is_datetime()
+        || matches!(
+            datatype,
+            DataType::Binary
+                | DataType::Json
+                | DataType::Uuid
+                | DataType::Enum(_)
+                | DataType::List(_)
+        )
+}
+
+/// Convert `data` to the type of the `idx`th column of `table`, with type
+/// check.
+pub(crate) fn coerce_to_column(
+    catalog: &Catalog,
+    table: &Table,
+    idx: usize,
+    data: Data,
+) -> Result<Data> {
+    let expected_datatype = table
+        .schema()
+        .column_datatypes()
+        .nth(idx)
+        .expect("should be a column");
+    let data = match data {
+        // e.g., '2024-01-01' to a DATE column
+        Data::String(str) if is_parsed_from_string(expected_datatype) => {
+            string_to_data(
+                str,
+                expected_datatype.clone(),
+                catalog.settings().time_zone,
+            )?
+        }
+        // Integers and decimals, e.g., `1` and `1.5`, are converted to floats
+        // for float columns.
+        Data::Int64(int)
+            if expected_datatype.value_type() == DataType::Float64 =>
+        {
+            Data::Float64(int as f64)
+        }
+        Data::Decimal(decimal)
+            if expected_datatype.value_type() == DataType::Float64 =>
+        {
+            Data::Float64(decimal.to_f64())
+        }
+        // Numbers are rounded to the scale of decimal columns.
+        data @ (Data::Int64(_)
+        | Data::Float64(_)
+        | Data::Decimal(_)
+        | Data::String(_)) => match *expected_datatype {
+            DataType::Decimal { precision, scale } => {
+                let decimal = data.to_decimal(precision, scale);
+                decimal.map(Data::Decimal).ok_or_else(|| {
+                    PlanError::ConversionError {
+                        val: Value::SingleQuotedString(data.to_string()),
+                        to: expected_datatype.clone(),
+                    }
+                })?
+            }
+            _ => data,
+        },
+        data => data,
+    };
+    let datatype = data.datatype();
+    let matched = match (&datatype, expected_datatype) {
+        // its precision is checked by the rounding above
+        (
+            DataType::Decimal { scale, .. },
+            DataType::Decimal {
+                scale: expected_scale,
+                ..
+            },
+        ) => scale == expected_scale,
+        (datatype, expected) => datatype == &expected.value_type(),
+    };
+    if !matched {
+        return Err(Error::PlanError(PlanError::MismatchedType {
+            table: table.name().to_string(),
+            column_idx: idx,
+            expected: expected_datatype.clone(),
+            found: datatype,
+        }));
+    };
+    let Some(data) = data.clone().fit(expected_datatype) else {
+        return Err(Error::PlanError(if expected_datatype.is_string() {
+            PlanError::ValueTooLong {
+                datatype: expected_datatype.clone(),
+            }
+        } else {
+            PlanError::ValueOutOfRange {
+                value: data,
+                datatype: expected_datatype.clone(),
+            }
+        }));
+    };
+
+    Ok(data)
+}
+
+/// Check that a value of type `found` can be written to the `idx`th column of
+/// `table`, see [`coerce_to_column()`].
+pub(crate) fn check_column_type(
+    table: &Table,
+    idx: usize,
+    found: DataType,
+) -> Result<()> {
+    let expected = table
+        .schema()
+        .column_datatypes()
+        .nth(idx)
+        .expect("should be a column");
+    let matched = match expected {
+        // parsed from strings
+        expected
+            if found == DataType::String && is_parsed_from_string(expected) =>
+        {
+            true
+        }
+        // rounded to the scale of the column
+        DataType::Decimal { .. } => matches!(
+            found,
+            DataType::Decimal { .. }
+                | DataType::Int64
+                | DataType::Float64
+                | DataType::String
+        ),
+        // converted to a float
+        expected
+            if expected.value_type() == DataType::Float64
+                && matches!(
+                    found,
+                    DataType::Int64 | DataType::Decimal { .. }
+                ) =>
+        {
+            true
+        }
+        expected => found == expected.value_type(),
+    };
+    if !matched {
+        return Err(Error::PlanError(PlanError::MismatchedType {
+            table: table.name().to_string(),
+            column_idx: idx,
+            expected: expected.clone(),
+            found,
+        }));
+    }
+
+    Ok(())
+}
+
+/// Convert the `DEFAULT` expression of the `idx`th column of `table`, it is
+/// evaluated on execution every time a value is needed.
+pub(crate) fn convert_default(
+    catalog: &Catalog,
+    table: &Table,
+    idx: usize,
+) -> Result<Expr> {
+    let column = table
+        .schema()
+        .column_names()
+        .nth(idx)
+        .expect("should be a column");
+    let Some(default) = table.schema().column_default(column) else {
+        return Err(Error::PlanError(PlanError::NoDefaultValue {
+            column: column.to_string(),
+        }));
+    };
+    let expr = convert_expr(catalog, default.clone())?;
+    if !expr.is_constant() {
+        return Err(Error::PlanError(PlanError::NonConstantDefault {
+            column: column.to_string(),
+        }));
+    }
+
+    Ok(expr)
+}
+
+/// Check the `DEFAULT` expression of the `idx`th column of `table`, so that
+/// it won't fail on `INSERT`.
+///
+/// Volatile ones, e.g., `nextval()`, are only type-checked, as evaluating them
+/// has side effects.
+pub(crate) fn check_default(
+    catalog: &Catalog,
+    table: &Table,
+    idx: usize,
+) -> Result<()> {
+    let expr = convert_default(catalog, table, idx)?;
+    if expr.is_volatile() {
+        check_column_type(table, idx, expr.datatype(&Schema::empty())?)
+    } else {
+        coerce_to_column(catalog, table, idx, expr.evaluate_constant_expr()?)
+            .map(|_| ())
+    }
+}
+
+/// Return an error if the `idx`th column of `table` is `GENERATED ALWAYS`, so
+/// that a value can not be inserted into it.
+pub(crate) fn check_not_generated_always(
+    table: &Table,
+    idx: usize,
+) -> Result<()> {
+    let column = table
+        .schema()
+        .column_names()
+        .nth(idx)
+        .expect("should be a column");
+    if table.schema().is_generated_always(column) {
+        return Err(Error::PlanError(PlanError::GeneratedAlwaysColumn {
+            column: column.to_string(),
+        }));
+    }
+
+    Ok(())
+}
+
+/// Return true if `expr` is the `DEFAULT` keyword.
+fn is_default_keyword(expr: &SqlExpr) -> bool {
+    matches!(expr, SqlExpr::Identifier(ident)
+        if ident.quote_style.is_none()
+            && ident.value.eq_ignore_ascii_case("default"))
+}
+
+/// Convert `values` to rows of constant expressions, which are the values of
+/// `columns` of table `table_name`, with schema check.
+///
+/// `columns` are column indexes, or all the columns in order if it is `None`.
+/// A value that is `DEFAULT` is the `DEFAULT` expression of its column.
+///
+/// Values are evaluated and converted to the types of their columns, except
+/// for `DEFAULT`s and volatile ones, e.g., `nextval()`, which are only
+/// type-checked, as evaluating them has side effects. They are evaluated on
+/// execution.
+pub fn values_to_rows(
     catalog: &Catalog,
     table_name: &str,
+    columns: Option<&[usize]>,
     values: Values,
-) -> Result<Vec<Tuple>> {
-    let table = catalog.get_table(table_name)?;
-    let schema = table.schema();
-    let n_columns = table.n_columns();
+) -> Result<Vec<Vec<Expr>>> {
+    let table = &catalog.get_table(table_name)?;
+    let all_columns = (0..table.n_columns()).collect::<Vec<_>>();
     let rows = values.rows;
-    let mut tuples = Vec::new();
+    let mut converted = Vec::with_capacity(rows.len());
     for row in rows {
-        if n_columns != row.len() {
-            return Err(Error::PlanError(PlanError::MismatchedNumberColumns {
-                table: table_name.to_string(),
-                expected: n_columns,
-                found: row.len(),
-            }));
-        }
-
-        let datatypes = schema.column_datatypes();
-        let mut tuple = Vec::with_capacity(n_columns);
-        for (idx, (expected_datatype, expr)) in
-            datatypes.zip(row.into_iter()).enumerate()
-        {
-            let expr = convert_expr(expr)?;
-            let data = as_variant!(Expr::Literal, expr);
-            let datatype = data.datatype();
-            if &datatype != expected_datatype {
-                return Err(Error::PlanError(PlanError::MismatchedType {
-                    table: table_name.to_string(),
-                    column_idx: idx,
-                    expected: *expected_datatype,
-                    found: datatype,
-                }));
-            };
+        check_number_of_values(table, columns, row.len())?;
+        let columns = columns.unwrap_or(&all_columns);
 
-            tuple.push(data);
+        let mut exprs = Vec::with_capacity(row.len());
+        for (&idx, expr) in columns.iter().zip(row.into_iter()) {
+            if is_default_keyword(&expr) {
+                exprs.push(convert_default(catalog, table, idx)?);
+                continue;
+            }
+            check_not_generated_always(table, idx)?;
+            let expr = convert_expr(catalog, expr)?;
+            if !expr.is_constant() {
+                return Err(Error::PlanError(PlanError::NonConstantValue {
+                    expr,
+                }));
+            }
+            if expr.is_volatile() {
+                check_column_type(
+                    table,
+                    idx,
+                    expr.datatype(&Schema::empty())?,
+                )?;
+                exprs.push(expr);
+                continue;
+            }
+            let data = expr.evaluate_constant_expr()?;
+            exprs.push(Expr::Literal(coerce_to_column(
+                catalog, table, idx, data,
+            )?));
         }
 
-        tuples.push(Tuple::new(tuple));
+        converted.push(exprs);
     }
 
-    Ok(tuples)
+    Ok(converted)
+}
+
+/// Check that `found` values are supplied for `columns` of `table`, see
+/// [`values_to_rows()`].
+pub(crate) fn check_number_of_values(
+    table: &Table,
+    columns: Option<&[usize]>,
+    found: usize,
+) -> Result<()> {
+    let Some(columns) = columns else {
+        if table.n_columns() == found {
+            return Ok(());
+        }
+        return Err(Error::PlanError(PlanError::MismatchedNumberColumns {
+            table: table.name().to_string(),
+            expected: table.n_columns(),
+            found,
+        }));
+    };
+
+    match found.cmp(&columns.len()) {
+        Ordering::Equal => Ok(()),
+        Ordering::Greater => {
+            Err(Error::PlanError(PlanError::MoreExpressionsThanColumns))
+        }
+        Ordering::Less => {
+            Err(Error::PlanError(PlanError::MoreColumnsThanExpressions))
+        }
+    }
 }
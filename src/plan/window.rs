@@ -0,0 +1,65 @@
+//! Planning window functions.
+
+use crate::{
+    catalog::schema::Schema, error::Result, expr::Expr,
+    function::window::WindowExpr, logical_plan::LogicalPlan,
+};
+
+/// Build a window operator on top of `input`, whose schema is `schema`.
+///
+/// The window operator appends a column for every window function call, and
+/// `projection` will be rewritten to reference these columns.
+///
+/// Return the new plan, its schema and the rewritten `projection`.
+pub(crate) fn plan_window(
+    input: LogicalPlan,
+    schema: &Schema,
+    projection: Vec<Expr>,
+) -> Result<(LogicalPlan, Schema, Vec<Expr>)> {
+    let mut window = Vec::new();
+    let projection = projection
+        .into_iter()
+        .map(|expr| rewrite(expr, &mut window))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut columns = schema
+        .columns()
+        .map(|(name, datatype)| (name.clone(), datatype.clone()))
+        .collect::<Vec<_>>();
+    for window_expr in window.iter() {
+        for expr in window_expr.partition_by.iter() {
+            expr.datatype(schema)?;
+        }
+        for sort in window_expr.order_by.iter() {
+            sort.expr.datatype(schema)?;
+        }
+        columns.push((window_expr.to_string(), window_expr.datatype(schema)?));
+    }
+    let collations = schema
+        .column_names()
+        .map(|name| (name.to_string(), schema.column_collation(name)));
+    let output_schema = Schema::new(columns).with_collations(collations);
+
+    let plan = LogicalPlan::Window {
+        window,
+        schema: output_schema.clone(),
+        input: Box::new(input),
+    };
+
+    Ok((plan, output_schema, projection))
+}
+
+/// Replace window function calls in `expr` with references to the columns
+/// appended by the window operator, they are collected into `window`.
+fn rewrite(expr: Expr, window: &mut Vec<WindowExpr>) -> Result<Expr> {
+    match expr {
+        Expr::Window(window_expr) => {
+            let name = window_expr.to_string();
+            if !window.iter().any(|w| w.to_string() == name) {
+                window.push(Box::into_inner(window_expr));
+            }
+            Ok(Expr::Column(name))
+        }
+        expr => expr.map_children(|child| rewrite(child, window)),
+    }
+}
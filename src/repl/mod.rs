@@ -47,7 +47,14 @@ pub fn run_repl(
     let result = ctx.collect(physical_plan.deref())?;
     let time = now.elapsed().unwrap();
 
-    println!("{}", display(&physical_plan.schema(), result));
+    println!(
+        "{}",
+        display(
+            &physical_plan.schema(),
+            result,
+            ctx.catalog.settings().time_zone
+        )
+    );
 
     if ctx.config.timer {
         println!("Took {:?}", time);
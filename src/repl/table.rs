@@ -1,4 +1,5 @@
 use crate::{catalog::schema::Schema, data::tuple::Tuple};
+use chrono::FixedOffset;
 use comfy_table::{
     Table,
     TableComponent::{
@@ -43,8 +44,13 @@ fn new_table() -> Table {
     table
 }
 
-/// Return a displayable return.
-pub fn display(schema: &Schema, result: Vec<Tuple>) -> impl Display {
+/// Return a displayable return, `TIMESTAMPTZ` values are displayed in
+/// `time_zone`.
+pub fn display(
+    schema: &Schema,
+    result: Vec<Tuple>,
+    time_zone: FixedOffset,
+) -> impl Display {
     let mut table = new_table();
 
     table.set_header(
@@ -57,7 +63,7 @@ pub fn display(schema: &Schema, result: Vec<Tuple>) -> impl Display {
         table.add_row(
             tuple
                 .iter()
-                .map(|data| format!("{data}"))
+                .map(|data| data.to_string_in(time_zone))
                 .collect::<Vec<_>>(),
         );
     }
@@ -0,0 +1,499 @@
+//! Sequences, which generate integers for `nextval()`, `SERIAL` and identity
+//! columns.
+//!
+//! The state of every sequence is stored in the `vinyl_sequence` tree, keyed
+//! by its name. Like PostgreSQL, sequences are not transactional, a value
+//! returned by `nextval()` is never returned again even if the transaction
+//! that got it is rolled back, so the tree is written and flushed directly on
+//! every change.
+//!
+//! To not flush on every `nextval()`, the state stored is [`LOG_VALS`] values
+//! ahead of the one returned, and the values in between are handed out from
+//! memory. Like PostgreSQL, those values are skipped after a crash or restart.
+
+use crate::error::Result;
+use derive_more::{Display, Error};
+use sled::Tree;
+use std::{collections::HashMap, sync::Mutex};
+
+/// The name of the tree that stores the sequences.
+pub const TREE_NAME: &str = "vinyl_sequence";
+
+/// The number of values fetched by `nextval()` every time it writes the tree.
+pub const LOG_VALS: usize = 32;
+
+#[derive(Error, Display, Debug)]
+pub enum SequenceError {
+    #[display(fmt = "Sequence with name '{}' already exists", name)]
+    SequenceExists { name: String },
+    #[display(fmt = "Sequence with name '{}' does not exist", name)]
+    SequenceDoesNotExist { name: String },
+    #[display(fmt = "INCREMENT must not be zero")]
+    ZeroIncrement,
+    #[display(fmt = "MINVALUE ({min}) must be less than MAXVALUE ({max})")]
+    InvalidBounds { min: i64, max: i64 },
+    #[display(
+        fmt = "START value ({start}) must be between MINVALUE ({min}) and MAXVALUE ({max})"
+    )]
+    StartOutOfBounds { start: i64, min: i64, max: i64 },
+    #[display(
+        fmt = "nextval: reached maximum value of sequence '{name}' ({max})"
+    )]
+    ReachedMaxValue { name: String, max: i64 },
+    #[display(
+        fmt = "nextval: reached minimum value of sequence '{name}' ({min})"
+    )]
+    ReachedMinValue { name: String, min: i64 },
+    #[display(
+        fmt = "setval: value {value} is out of bounds for sequence '{name}' ({min}..{max})"
+    )]
+    ValueOutOfBounds {
+        name: String,
+        value: i64,
+        min: i64,
+        max: i64,
+    },
+    #[display(
+        fmt = "currval of sequence '{name}' is not yet defined in this session"
+    )]
+    CurrvalNotDefined { name: String },
+}
+
+/// The definition of a sequence.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SequenceOptions {
+    pub increment: i64,
+    pub min: i64,
+    pub max: i64,
+    pub start: i64,
+    /// Whether it wraps around when a bound is reached.
+    pub cycle: bool,
+}
+
+impl SequenceOptions {
+    /// Return the default options of a sequence that counts by `increment`
+    /// in `[min, max]`, which starts from `min` if it is ascending, `max`
+    /// otherwise.
+    pub fn new(increment: i64, min: i64, max: i64) -> Self {
+        Self {
+            increment,
+            min,
+            max,
+            start: if increment > 0 { min } else { max },
+            cycle: false,
+        }
+    }
+
+    /// Check that the options are consistent.
+    pub fn check(&self) -> std::result::Result<(), SequenceError> {
+        if self.increment == 0 {
+            return Err(SequenceError::ZeroIncrement);
+        }
+        if self.min >= self.max {
+            return Err(SequenceError::InvalidBounds {
+                min: self.min,
+                max: self.max,
+            });
+        }
+        if !(self.min..=self.max).contains(&self.start) {
+            return Err(SequenceError::StartOutOfBounds {
+                start: self.start,
+                min: self.min,
+                max: self.max,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// The stored state of a sequence.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct SequenceState {
+    options: SequenceOptions,
+    /// The value returned by the latest `nextval()`, or the one that the
+    /// next `nextval()` returns if `is_called` is false.
+    last: i64,
+    is_called: bool,
+}
+
+impl SequenceState {
+    const ENCODED_LEN: usize = 5 * std::mem::size_of::<i64>() + 2;
+
+    fn encode(&self) -> Vec<u8> {
+        let options = &self.options;
+        let mut buf = Vec::with_capacity(Self::ENCODED_LEN);
+        for n in [
+            options.increment,
+            options.min,
+            options.max,
+            options.start,
+            self.last,
+        ] {
+            buf.extend_from_slice(&n.to_be_bytes());
+        }
+        buf.push(options.cycle as u8);
+        buf.push(self.is_called as u8);
+
+        buf
+    }
+
+    fn decode(buf: &[u8]) -> Self {
+        assert_eq!(buf.len(), Self::ENCODED_LEN, "corrupted sequence");
+        let int = |idx: usize| {
+            let bytes = &buf[idx * 8..(idx + 1) * 8];
+            i64::from_be_bytes(bytes.try_into().expect("should be an i64"))
+        };
+
+        Self {
+            options: SequenceOptions {
+                increment: int(0),
+                min: int(1),
+                max: int(2),
+                start: int(3),
+                cycle: buf[40] != 0,
+            },
+            last: int(4),
+            is_called: buf[41] != 0,
+        }
+    }
+
+    /// Advance the sequence, return the new state.
+    fn next(self, name: &str) -> std::result::Result<Self, SequenceError> {
+        if !self.is_called {
+            return Ok(Self {
+                is_called: true,
+                ..self
+            });
+        }
+        let options = &self.options;
+        let next = self
+            .last
+            .checked_add(options.increment)
+            .filter(|next| (options.min..=options.max).contains(next));
+        let last = match next {
+            Some(next) => next,
+            None if options.cycle => {
+                if options.increment > 0 {
+                    options.min
+                } else {
+                    options.max
+                }
+            }
+            None if options.increment > 0 => {
+                return Err(SequenceError::ReachedMaxValue {
+                    name: name.to_string(),
+                    max: options.max,
+                })
+            }
+            None => {
+                return Err(SequenceError::ReachedMinValue {
+                    name: name.to_string(),
+                    min: options.min,
+                })
+            }
+        };
+
+        Ok(Self { last, ..self })
+    }
+}
+
+/// The state of a sequence in memory, which is behind the stored one.
+#[derive(Debug, Copy, Clone)]
+struct CachedState {
+    state: SequenceState,
+    /// The number of values that can be fetched before the stored state is
+    /// reached.
+    remaining: usize,
+}
+
+/// The values returned by the latest `nextval()` of every sequence in a
+/// session, for `currval()`.
+#[derive(Debug, Default)]
+pub struct Currvals(Mutex<HashMap<String, i64>>);
+
+impl Currvals {
+    fn insert(&self, name: &str, value: i64) {
+        self.0.lock().unwrap().insert(name.to_string(), value);
+    }
+
+    fn get(&self, name: &str) -> Option<i64> {
+        self.0.lock().unwrap().get(name).copied()
+    }
+}
+
+/// All the sequences, shared by the sessions and the functions that operate
+/// on them.
+#[derive(Debug)]
+pub struct Sequences {
+    tree: Tree,
+    /// The sequences that have values fetched but not returned yet.
+    cache: Mutex<HashMap<String, CachedState>>,
+}
+
+impl Sequences {
+    /// Create a new [`Sequences`] stored in `tree`.
+    pub fn new(tree: Tree) -> Self {
+        Self {
+            tree,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Return true if sequence `name` exists.
+    pub fn contains(&self, name: &str) -> Result<bool> {
+        Ok(self.tree.contains_key(name)?)
+    }
+
+    /// Create sequence `name` with `options`, which should have been checked.
+    pub fn create(&self, name: &str, options: SequenceOptions) -> Result<()> {
+        let state = SequenceState {
+            options,
+            last: options.start,
+            is_called: false,
+        };
+        if self
+            .tree
+            .compare_and_swap(
+                name,
+                None as Option<&[u8]>,
+                Some(state.encode()),
+            )?
+            .is_err()
+        {
+            return Err(SequenceError::SequenceExists {
+                name: name.to_string(),
+            }
+            .into());
+        }
+        self.tree.flush()?;
+
+        Ok(())
+    }
+
+    /// Drop sequence `name`, nothing happens if it does not exist.
+    pub fn remove(&self, name: &str) -> Result<()> {
+        let mut cache = self.cache.lock().unwrap();
+        self.tree.remove(name)?;
+        cache.remove(name);
+        self.tree.flush()?;
+
+        Ok(())
+    }
+
+    /// Read the state of sequence `name`.
+    fn get(&self, name: &str) -> Result<(sled::IVec, SequenceState)> {
+        let Some(encoded) = self.tree.get(name)? else {
+            return Err(SequenceError::SequenceDoesNotExist {
+                name: name.to_string(),
+            }
+            .into());
+        };
+        let state = SequenceState::decode(&encoded);
+
+        Ok((encoded, state))
+    }
+
+    /// Replace the state of sequence `name` with the one returned by `f`,
+    /// retrying if it is changed concurrently.
+    fn update<T, F>(&self, name: &str, f: F) -> Result<T>
+    where
+        F: Fn(
+            SequenceState,
+        )
+            -> std::result::Result<(T, SequenceState), SequenceError>,
+    {
+        loop {
+            let (encoded, state) = self.get(name)?;
+            let (ret, new) = f(state)?;
+            if self
+                .tree
+                .compare_and_swap(name, Some(encoded), Some(new.encode()))?
+                .is_ok()
+            {
+                // a value must not be returned again after a crash
+                self.tree.flush()?;
+                return Ok(ret);
+            }
+        }
+    }
+
+    /// Advance sequence `name` and return its new value, which is recorded in
+    /// `currvals`.
+    pub fn nextval(&self, name: &str, currvals: &Currvals) -> Result<i64> {
+        let mut cache = self.cache.lock().unwrap();
+        let cached = match cache.get(name) {
+            Some(cached) if cached.remaining > 0 => CachedState {
+                state: cached.state.next(name)?,
+                remaining: cached.remaining - 1,
+            },
+            // fetch the next values and store the state after them
+            _ => self.update(name, |state| {
+                let state = state.next(name)?;
+                let mut logged = state;
+                let mut remaining = 0;
+                while remaining < LOG_VALS - 1 {
+                    let Ok(next) = logged.next(name) else {
+                        break;
+                    };
+                    logged = next;
+                    remaining += 1;
+                }
+
+                Ok((CachedState { state, remaining }, logged))
+            })?,
+        };
+        cache.insert(name.to_string(), cached);
+        let value = cached.state.last;
+        currvals.insert(name, value);
+
+        Ok(value)
+    }
+
+    /// Return the value returned by the latest `nextval()` of sequence `name`
+    /// recorded in `currvals`.
+    pub fn currval(&self, name: &str, currvals: &Currvals) -> Result<i64> {
+        // it could have been dropped
+        self.get(name)?;
+        currvals.get(name).ok_or_else(|| {
+            SequenceError::CurrvalNotDefined {
+                name: name.to_string(),
+            }
+            .into()
+        })
+    }
+
+    /// Set the value of sequence `name`, the next `nextval()` returns the
+    /// value after it if `is_called`, or itself otherwise. The value is
+    /// recorded in `currvals` if `is_called`.
+    pub fn setval(
+        &self,
+        name: &str,
+        value: i64,
+        is_called: bool,
+        currvals: &Currvals,
+    ) -> Result<i64> {
+        let mut cache = self.cache.lock().unwrap();
+        self.update(name, |state| {
+            let options = &state.options;
+            if !(options.min..=options.max).contains(&value) {
+                return Err(SequenceError::ValueOutOfBounds {
+                    name: name.to_string(),
+                    value,
+                    min: options.min,
+                    max: options.max,
+                });
+            }
+
+            let state = SequenceState {
+                last: value,
+                is_called,
+                ..state
+            };
+            Ok(((), state))
+        })?;
+        cache.remove(name);
+        if is_called {
+            currvals.insert(name, value);
+        }
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use pretty_assertions::assert_eq;
+
+    fn sequences() -> Sequences {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        Sequences::new(db.open_tree(TREE_NAME).unwrap())
+    }
+
+    #[test]
+    fn nextval_and_bounds() {
+        let sequences = sequences();
+        let currvals = Currvals::default();
+        sequences
+            .create("s", SequenceOptions::new(2, 1, 5))
+            .unwrap();
+        assert!(matches!(
+            sequences.currval("s", &currvals),
+            Err(Error::SequenceError(
+                SequenceError::CurrvalNotDefined { .. }
+            ))
+        ));
+        let values = (0..3)
+            .map(|_| sequences.nextval("s", &currvals).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(values, [1, 3, 5]);
+        assert_eq!(sequences.currval("s", &currvals).unwrap(), 5);
+        assert!(matches!(
+            sequences.nextval("s", &currvals),
+            Err(Error::SequenceError(SequenceError::ReachedMaxValue {
+                max: 5,
+                ..
+            }))
+        ));
+
+        assert_eq!(sequences.setval("s", 2, false, &currvals).unwrap(), 2);
+        assert_eq!(sequences.nextval("s", &currvals).unwrap(), 2);
+        assert_eq!(sequences.setval("s", 2, true, &currvals).unwrap(), 2);
+        assert_eq!(sequences.nextval("s", &currvals).unwrap(), 4);
+        assert!(sequences.setval("s", 6, true, &currvals).is_err());
+
+        // the state is stored in the tree, currval() is per session
+        sequences.setval("s", 1, true, &currvals).unwrap();
+        let reopened = Sequences::new(sequences.tree.clone());
+        let currvals = Currvals::default();
+        assert!(reopened.currval("s", &currvals).is_err());
+        assert_eq!(reopened.nextval("s", &currvals).unwrap(), 3);
+    }
+
+    #[test]
+    fn nextval_in_cached_blocks() {
+        let sequences = sequences();
+        let currvals = Currvals::default();
+        sequences
+            .create("s", SequenceOptions::new(1, 1, 100))
+            .unwrap();
+        assert_eq!(sequences.nextval("s", &currvals).unwrap(), 1);
+        let (_, stored) = sequences.get("s").unwrap();
+        assert_eq!(stored.last, LOG_VALS as i64);
+        for value in 2..=LOG_VALS as i64 {
+            assert_eq!(sequences.nextval("s", &currvals).unwrap(), value);
+        }
+        let (_, stored) = sequences.get("s").unwrap();
+        assert_eq!(stored.last, LOG_VALS as i64);
+        assert_eq!(
+            sequences.nextval("s", &currvals).unwrap(),
+            LOG_VALS as i64 + 1
+        );
+
+        // the values fetched are skipped after a restart
+        let reopened = Sequences::new(sequences.tree.clone());
+        assert_eq!(
+            reopened.nextval("s", &currvals).unwrap(),
+            2 * LOG_VALS as i64 + 1
+        );
+    }
+
+    #[test]
+    fn descending_and_cycle() {
+        let sequences = sequences();
+        let currvals = Currvals::default();
+        let options = SequenceOptions {
+            cycle: true,
+            ..SequenceOptions::new(-1, 1, 3)
+        };
+        sequences.create("s", options).unwrap();
+        let values = (0..5)
+            .map(|_| sequences.nextval("s", &currvals).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(values, [3, 2, 1, 3, 2]);
+        assert!(sequences.create("s", options).is_err());
+        assert!(sequences.nextval("t", &currvals).is_err());
+    }
+}
@@ -24,10 +24,18 @@ impl ColumnType for DataType {
     fn to_char(&self) -> char {
         match self {
             Self::Bool => 'B',
-            Self::String => 'T',
-            Self::Int64 => 'I',
-            Self::Float64 => 'F',
-            Self::Timestamp => todo!(),
+            Self::String | Self::Char { .. } | Self::Varchar { .. } => 'T',
+            // compared in the hex format
+            Self::Binary => 'T',
+            Self::Json | Self::Uuid | Self::List(_) | Self::Enum(_) => 'T',
+            Self::Int16 | Self::Int32 | Self::Int64 => 'I',
+            Self::Float32 | Self::Float64 | Self::Decimal { .. } => 'F',
+            // date/time values are compared in their text form
+            Self::Timestamp
+            | Self::TimestampTz
+            | Self::Date
+            | Self::Time
+            | Self::Interval => 'T',
         }
     }
 }
@@ -50,13 +58,14 @@ impl DB for VinylDB {
         }
 
         let types = result.first().unwrap().datatypes();
+        let time_zone = self.catalog.settings().time_zone;
 
         let rows = result
             .into_iter()
             .map(|tuple| {
                 tuple
                     .iter()
-                    .map(|data| data.to_string())
+                    .map(|data| data.to_string_in(time_zone))
                     .collect::<Vec<String>>()
             })
             .collect::<Vec<_>>();
@@ -1,11 +1,33 @@
-use crate::{catalog::error::CatalogError, config::Config, error::Result};
+use crate::{
+    catalog::error::CatalogError,
+    config::Config,
+    error::Result,
+    mvcc::{Mvcc, Snapshot, Timestamp, TIMESTAMP_KEY},
+    sequence::{self, Sequences},
+};
 use sled::{Db, Tree};
-use std::collections::{hash_map::Entry, HashMap};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::{Arc, RwLock},
+};
 
-#[derive(Debug)]
+/// The trees used internally other than the catalog, tables can not be named
+/// after them.
+pub const INTERNAL_TREES: [&str; 3] = [
+    crate::catalog::vinyl_type::TABLE_NAME,
+    sequence::TREE_NAME,
+    "__sled__default",
+];
+
+/// The storage of all the data, a clone of it shares the same database,
+/// trees, timestamps and sequences, e.g., for another session.
+#[derive(Debug, Clone)]
 pub struct StorageEngine {
     db: Db,
-    trees: HashMap<String, Tree>,
+    trees: Arc<RwLock<HashMap<String, Tree>>>,
+    /// Timestamps of the versions stored in the tables, see [`crate::mvcc`].
+    mvcc: Arc<Mvcc>,
+    sequences: Arc<Sequences>,
 }
 
 impl StorageEngine {
@@ -18,6 +40,9 @@ impl StorageEngine {
         std::fs::create_dir_all(sled_dir.as_path())?;
         let db = sled::open(sled_dir.as_path())?;
         db.open_tree(crate::catalog::vinyl_table::TABLE_NAME)?;
+        db.open_tree(crate::catalog::vinyl_type::TABLE_NAME)?;
+        let sequences =
+            Arc::new(Sequences::new(db.open_tree(sequence::TREE_NAME)?));
 
         let mut trees = HashMap::new();
         for tree_name in db.tree_names() {
@@ -26,30 +51,78 @@ impl StorageEngine {
             let tree = db.open_tree(&tree_name)?;
             assert!(trees.insert(name, tree).is_none());
         }
+        let latest: Timestamp = match db.get(TIMESTAMP_KEY)? {
+            Some(bytes) => Timestamp::from_be_bytes(
+                bytes.as_ref().try_into().expect("should be a u64"),
+            ),
+            None => 0,
+        };
+        let mvcc = Arc::new(Mvcc::new(latest));
 
-        Ok(Self { db, trees })
+        Ok(Self {
+            db,
+            trees: Arc::new(RwLock::new(trees)),
+            mvcc,
+            sequences,
+        })
     }
 
-    pub fn add_table(&mut self, name: String) -> Result<()> {
-        match self.trees.entry(name.clone()) {
+    pub fn add_table(&self, name: String) -> Result<()> {
+        match self.trees.write().unwrap().entry(name.clone()) {
             Entry::Vacant(v) => {
                 let tree = self.db.open_tree(name)?;
                 v.insert(tree);
             }
-            Entry::Occupied(_) => unreachable!(
-                "should be unreachable as we will check catalog first"
-            ),
+            Entry::Occupied(o) => {
+                return Err(CatalogError::TableExists {
+                    name: o.key().clone(),
+                }
+                .into())
+            }
         }
 
         Ok(())
     }
 
-    pub fn get_tree_of_table(&self, name: &str) -> Result<&Tree> {
-        let tree = self.trees.get(name).ok_or_else(|| {
-            CatalogError::TableDoesNotExist {
-                name: name.to_string(),
-            }
-        })?;
+    /// Drop the tree of table `name`, nothing happens if it does not exist.
+    pub fn remove_table(&self, name: &str) -> Result<()> {
+        if self.trees.write().unwrap().remove(name).is_some() {
+            self.db.drop_tree(name)?;
+        }
+
+        Ok(())
+    }
+
+    /// Return the underlying sled database.
+    pub fn db(&self) -> &Db {
+        &self.db
+    }
+
+    /// Return the timestamps of the versions stored in the tables.
+    pub fn mvcc(&self) -> &Mvcc {
+        &self.mvcc
+    }
+
+    /// Return the sequences, which are shared by the sequence functions.
+    pub fn sequences(&self) -> &Arc<Sequences> {
+        &self.sequences
+    }
+
+    /// Take a snapshot of the latest committed data.
+    pub fn snapshot(&self) -> Snapshot {
+        self.mvcc.snapshot()
+    }
+
+    pub fn get_tree_of_table(&self, name: &str) -> Result<Tree> {
+        let tree =
+            self.trees
+                .read()
+                .unwrap()
+                .get(name)
+                .cloned()
+                .ok_or_else(|| CatalogError::TableDoesNotExist {
+                    name: name.to_string(),
+                })?;
 
         Ok(tree)
     }
@@ -0,0 +1,472 @@
+//! Transactions, i.e., `BEGIN`, `COMMIT` and `ROLLBACK`, and savepoints in
+//! them.
+//!
+//! Writes of a transaction are buffered in memory, reads in it see these
+//! writes on top of the committed data. They are written to all the affected
+//! trees in a sled transaction on commit, and simply discarded on rollback.
+//! A savepoint remembers the writes made before it, rolling back to it
+//! discards the ones made after it.
+//!
+//! A statement executed outside of a transaction runs in its own one, which is
+//! committed once it finishes.
+//!
+//! Transactions are isolated by snapshots, see [`crate::mvcc`]: a transaction
+//! reads the data committed before it started, and fails to commit if a row
+//! it writes has been committed by another transaction since then.
+
+use crate::{
+    error::{Error, Result},
+    mvcc::{self, Snapshot, Timestamp, TIMESTAMP_KEY},
+    storage_engine::StorageEngine,
+};
+use derive_more::{Display, Error};
+use indexmap::IndexMap;
+use sled::{
+    transaction::TransactionError as SledTransactionError, Batch, IVec,
+    Transactional, Tree,
+};
+use std::{cmp::Ordering, collections::BTreeMap, iter::Peekable};
+
+#[derive(Error, Display, Debug, Clone)]
+pub enum TransactionError {
+    #[display(fmt = "there is already a transaction in progress")]
+    AlreadyInProgress,
+    #[display(fmt = "there is no transaction in progress")]
+    NotInProgress,
+    #[display(fmt = "{statement} cannot run inside a transaction block")]
+    NotAllowedInTransaction { statement: &'static str },
+    #[display(fmt = "{statement} can only be used in transaction blocks")]
+    OnlyInTransaction { statement: &'static str },
+    #[display(fmt = "savepoint \"{name}\" does not exist")]
+    SavepointDoesNotExist { name: String },
+    #[display(fmt = "could not serialize access due to concurrent update")]
+    SerializationFailure,
+}
+
+/// Statements controlling transactions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionControl {
+    /// `BEGIN` or `START TRANSACTION`
+    Begin,
+    /// `COMMIT`
+    Commit,
+    /// `ROLLBACK`
+    Rollback,
+    /// `SAVEPOINT name`
+    Savepoint { name: String },
+    /// `ROLLBACK TO SAVEPOINT name`
+    RollbackToSavepoint { name: String },
+    /// `RELEASE SAVEPOINT name`
+    ReleaseSavepoint { name: String },
+}
+
+/// The writes of a transaction, table name => (key => value), keys are ordered
+/// in the same way as sled.
+type Writes = IndexMap<String, BTreeMap<IVec, IVec>>;
+
+/// An iterator over the `(key, value)` pairs of a table.
+pub type TableIter = Box<dyn Iterator<Item = sled::Result<(IVec, IVec)>>>;
+
+/// The uncommitted writes of a transaction.
+#[derive(Debug)]
+pub struct Transaction {
+    /// The data this transaction reads.
+    snapshot: Snapshot,
+    writes: Writes,
+    /// Savepoints and the writes made before them, from the oldest to the
+    /// newest.
+    savepoints: Vec<(String, Writes)>,
+}
+
+impl Transaction {
+    /// Start a transaction on the latest committed data of `storage`.
+    pub fn new(storage: &StorageEngine) -> Self {
+        Self {
+            snapshot: storage.snapshot(),
+            writes: IndexMap::new(),
+            savepoints: Vec::new(),
+        }
+    }
+
+    /// Define a savepoint `name`, an existing one with the same name is
+    /// shadowed until this one is released.
+    pub fn savepoint(&mut self, name: String) {
+        self.savepoints.push((name, self.writes.clone()));
+    }
+
+    /// Return the index of the newest savepoint `name`.
+    fn find_savepoint(&self, name: &str) -> Result<usize> {
+        self.savepoints
+            .iter()
+            .rposition(|(savepoint, _)| savepoint == name)
+            .ok_or_else(|| {
+                Error::TransactionError(
+                    TransactionError::SavepointDoesNotExist {
+                        name: name.to_string(),
+                    },
+                )
+            })
+    }
+
+    /// Discard the writes made after savepoint `name`, the savepoints defined
+    /// after it are destroyed, and it is kept.
+    pub fn rollback_to_savepoint(&mut self, name: &str) -> Result<()> {
+        let idx = self.find_savepoint(name)?;
+        self.savepoints.truncate(idx + 1);
+        self.writes = self.savepoints[idx].1.clone();
+
+        Ok(())
+    }
+
+    /// Destroy savepoint `name` and the ones defined after it, writes are
+    /// kept.
+    pub fn release_savepoint(&mut self, name: &str) -> Result<()> {
+        let idx = self.find_savepoint(name)?;
+        self.savepoints.truncate(idx);
+
+        Ok(())
+    }
+
+    /// Buffer the write of `value` to `key` of `table`.
+    pub fn insert(&mut self, table: &str, key: IVec, value: IVec) {
+        self.writes
+            .entry(table.to_string())
+            .or_default()
+            .insert(key, value);
+    }
+
+    /// Return the value of `key` of `table`, whose committed data are stored
+    /// in `tree`.
+    pub fn get(
+        &self,
+        table: &str,
+        tree: &Tree,
+        key: &[u8],
+    ) -> Result<Option<IVec>> {
+        match self.writes.get(table).and_then(|writes| writes.get(key)) {
+            Some(value) => Ok(Some(value.clone())),
+            None => Ok(mvcc::get_version(tree, key, self.snapshot.ts())?
+                .map(|(_, value)| value)),
+        }
+    }
+
+    /// Scan `table`, whose committed data are stored in `tree`, the writes of
+    /// this transaction are applied.
+    pub fn scan(&self, table: &str, tree: &Tree) -> TableIter {
+        let writes = self
+            .writes
+            .get(table)
+            .map(|writes| {
+                writes
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        Box::new(MergedIter {
+            committed: self.snapshot.clone().scan(tree).peekable(),
+            writes: writes.into_iter().peekable(),
+        })
+    }
+
+    /// Write all the buffered writes atomically, as new versions committed at
+    /// a new timestamp.
+    ///
+    /// [`TransactionError::SerializationFailure`] will be returned if any of
+    /// the written rows has a version committed after this transaction
+    /// started.
+    pub fn commit(self, storage: &StorageEngine) -> Result<()> {
+        if self.writes.is_empty() {
+            return Ok(());
+        }
+
+        let default_tree: &Tree = storage.db();
+        let mut trees = Vec::with_capacity(self.writes.len() + 1);
+        trees.push(default_tree.clone());
+        for table in self.writes.keys() {
+            trees.push(storage.get_tree_of_table(table)?);
+        }
+
+        let (guard, commit_ts) = storage.mvcc().start_commit();
+        for (tree, writes) in trees[1..].iter().zip(self.writes.values()) {
+            for key in writes.keys() {
+                if let Some((ts, _)) =
+                    mvcc::get_version(tree, key, Timestamp::MAX)?
+                {
+                    if ts > self.snapshot.ts() {
+                        return Err(Error::TransactionError(
+                            TransactionError::SerializationFailure,
+                        ));
+                    }
+                }
+            }
+        }
+
+        // the writes to a table are applied in a batch
+        let batches = self
+            .writes
+            .values()
+            .map(|writes| {
+                let mut batch = Batch::default();
+                for (key, value) in writes {
+                    batch.insert(mvcc::version_key(key, commit_ts), value);
+                }
+                batch
+            })
+            .collect::<Vec<_>>();
+        let res: std::result::Result<(), SledTransactionError<()>> =
+            trees.as_slice().transaction(|tx_trees| {
+                for (tx_tree, batch) in tx_trees[1..].iter().zip(&batches) {
+                    tx_tree.apply_batch(batch)?;
+                }
+                tx_trees[0]
+                    .insert(TIMESTAMP_KEY, &commit_ts.to_be_bytes()[..])?;
+
+                Ok(())
+            });
+        res.map_err(|err| match err {
+            SledTransactionError::Storage(err) => Error::SledError(err),
+            SledTransactionError::Abort(()) => unreachable!("never aborted"),
+        })?;
+        storage.mvcc().publish(guard, commit_ts);
+
+        Ok(())
+    }
+}
+
+/// Committed data merged with the writes of a transaction, both are ordered
+/// by keys, a written key overrides the committed one.
+struct MergedIter {
+    committed: Peekable<mvcc::SnapshotIter>,
+    writes: Peekable<std::vec::IntoIter<(IVec, IVec)>>,
+}
+
+impl Iterator for MergedIter {
+    type Item = sled::Result<(IVec, IVec)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ordering = match (self.committed.peek(), self.writes.peek()) {
+            (None, None) => return None,
+            (Some(Ok((committed, _))), Some((written, _))) => {
+                committed.cmp(written)
+            }
+            (Some(_), _) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+        };
+
+        match ordering {
+            Ordering::Less => self.committed.next(),
+            Ordering::Equal => {
+                self.committed.next();
+                self.writes.next().map(Ok)
+            }
+            Ordering::Greater => self.writes.next().map(Ok),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        catalog::error::CatalogError,
+        data::{
+            tuple::Tuple,
+            types::{Data, DataType},
+        },
+        VinylDB,
+    };
+    use camino_tempfile::Utf8TempDir;
+    use pretty_assertions::assert_eq;
+    use std::ops::Deref;
+
+    /// Return the `(key, value)` of row `(id)`.
+    fn row(id: i64) -> (IVec, IVec) {
+        let key = IVec::from(Data::Int64(id).encode().as_ref());
+        (key, Tuple::new([Data::Int64(id)]).encode().into())
+    }
+
+    #[test]
+    fn writes_are_buffered_until_commit() {
+        let dir = Utf8TempDir::new().unwrap();
+        let mut db = VinylDB::new(dir.path());
+        db.sql("CREATE TABLE t (id INT64)").unwrap();
+        db.sql("CREATE TABLE u (id INT64)").unwrap();
+        let len = |db: &VinylDB, table| {
+            db.storage.get_tree_of_table(table).unwrap().len()
+        };
+
+        db.sql("BEGIN").unwrap();
+        db.sql("INSERT INTO t VALUES (1), (2)").unwrap();
+        db.sql("INSERT INTO u VALUES (1)").unwrap();
+        assert_eq!((len(&db, "t"), len(&db, "u")), (0, 0));
+        db.sql("COMMIT").unwrap();
+        assert_eq!((len(&db, "t"), len(&db, "u")), (2, 1));
+
+        db.sql("BEGIN").unwrap();
+        db.sql("INSERT INTO t VALUES (3)").unwrap();
+        db.sql("ROLLBACK").unwrap();
+        assert_eq!(len(&db, "t"), 2);
+
+        // autocommit
+        db.sql("INSERT INTO t VALUES (3)").unwrap();
+        assert_eq!(len(&db, "t"), 3);
+    }
+
+    #[test]
+    fn concurrent_writes_to_the_same_row_conflict() {
+        let dir = Utf8TempDir::new().unwrap();
+        let mut db = VinylDB::new(dir.path());
+        db.sql("CREATE TABLE t (id INT64)").unwrap();
+        let (key, value) = row(1);
+
+        let mut t1 = Transaction::new(&db.storage);
+        let mut t2 = Transaction::new(&db.storage);
+        let mut t3 = Transaction::new(&db.storage);
+        t1.insert("t", key.clone(), value.clone());
+        t2.insert("t", key, value);
+        let (key, value) = row(2);
+        t3.insert("t", key, value);
+        t1.commit(&db.storage).unwrap();
+        assert!(matches!(
+            t2.commit(&db.storage),
+            Err(Error::TransactionError(
+                TransactionError::SerializationFailure
+            ))
+        ));
+        t3.commit(&db.storage).unwrap();
+
+        assert_eq!(
+            db.sql("SELECT id FROM t").unwrap(),
+            vec![Tuple::new([Data::Int64(1)]), Tuple::new([Data::Int64(2)])]
+        );
+    }
+
+    #[test]
+    fn concurrent_sessions_conflict() {
+        let dir = Utf8TempDir::new().unwrap();
+        let mut s1 = VinylDB::new(dir.path());
+        s1.sql("CREATE TABLE t (id INT64, v STRING)").unwrap();
+        s1.sql("INSERT INTO t VALUES (1, 'a')").unwrap();
+        let mut s2 = s1.session();
+        let select = |db: &mut VinylDB| db.sql("SELECT v FROM t").unwrap();
+        let v = |str: &str| vec![Tuple::new([Data::String(str.to_string())])];
+
+        s1.sql("BEGIN").unwrap();
+        s2.sql("BEGIN").unwrap();
+        s1.sql("INSERT OR REPLACE INTO t VALUES (1, 'b')").unwrap();
+        s2.sql("INSERT OR REPLACE INTO t VALUES (1, 'c')").unwrap();
+        assert_eq!(select(&mut s1), v("b"));
+        assert_eq!(select(&mut s2), v("c"));
+        s1.sql("COMMIT").unwrap();
+        // s2 still reads its snapshot
+        assert_eq!(select(&mut s2), v("c"));
+        assert!(matches!(
+            s2.sql("COMMIT"),
+            Err(Error::TransactionError(
+                TransactionError::SerializationFailure
+            ))
+        ));
+        assert!(!s2.in_transaction());
+        assert_eq!(select(&mut s2), v("b"));
+
+        // writes to different rows do not conflict
+        s1.sql("BEGIN").unwrap();
+        s2.sql("BEGIN").unwrap();
+        s1.sql("INSERT INTO t VALUES (2, 'x')").unwrap();
+        s2.sql("INSERT INTO t VALUES (3, 'y')").unwrap();
+        s2.sql("COMMIT").unwrap();
+        s1.sql("COMMIT").unwrap();
+        assert_eq!(s1.sql("SELECT id FROM t").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn sessions_share_the_catalog() {
+        let dir = Utf8TempDir::new().unwrap();
+        let mut s1 = VinylDB::new(dir.path());
+        let mut s2 = s1.session();
+
+        s1.sql("CREATE TYPE mood AS ENUM ('sad', 'happy')").unwrap();
+        s1.sql("CREATE TABLE t (id SERIAL, m mood)").unwrap();
+        s2.sql("INSERT INTO t (m) VALUES ('happy')").unwrap();
+        let rows = s1.sql("SELECT id FROM t").unwrap();
+        assert_eq!(rows, vec![Tuple::new([Data::Int64(1)])]);
+
+        assert!(matches!(
+            s2.sql("CREATE TABLE t (id SERIAL)"),
+            Err(Error::CatalogError(CatalogError::TableExists { .. }))
+        ));
+        assert!(matches!(
+            s2.sql("CREATE TYPE mood AS ENUM ('angry')"),
+            Err(Error::CatalogError(CatalogError::TypeExists { .. }))
+        ));
+        assert_eq!(s2.sql("SHOW TABLES").unwrap().len(), 1);
+        assert_eq!(s1.sql("SELECT id FROM t").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn scans_read_a_snapshot() {
+        let dir = Utf8TempDir::new().unwrap();
+        let mut db = VinylDB::new(dir.path());
+        db.sql("CREATE TABLE t (id INT64)").unwrap();
+        db.sql("INSERT INTO t VALUES (1)").unwrap();
+
+        let plan = db.create_logical_plan("SELECT id FROM t").unwrap();
+        let plan = db.create_physical_plan(&plan).unwrap();
+        let stream = db.execute(plan.deref()).unwrap();
+        db.sql("INSERT INTO t VALUES (0), (2)").unwrap();
+        assert_eq!(
+            stream.collect::<Result<Vec<_>>>().unwrap(),
+            vec![Tuple::new([Data::Int64(1)])]
+        );
+
+        // a transaction does not see the commits made after it started
+        db.sql("BEGIN").unwrap();
+        let mut other = Transaction::new(&db.storage);
+        let (key, value) = row(3);
+        other.insert("t", key, value);
+        other.commit(&db.storage).unwrap();
+        assert_eq!(db.sql("SELECT id FROM t").unwrap().len(), 3);
+        db.sql("COMMIT").unwrap();
+        assert_eq!(db.sql("SELECT id FROM t").unwrap().len(), 4);
+    }
+
+    #[test]
+    fn obsolete_versions_are_collected() {
+        let dir = Utf8TempDir::new().unwrap();
+        let mut db = VinylDB::new(dir.path());
+        db.sql("CREATE TABLE t (id INT64)").unwrap();
+        let write = |db: &VinylDB, id| {
+            let mut transaction = Transaction::new(&db.storage);
+            let (key, value) = row(id);
+            transaction.insert("t", key, value);
+            transaction.commit(&db.storage).unwrap();
+        };
+        write(&db, 1);
+        write(&db, 2);
+
+        // the version read by this snapshot is kept
+        let snapshot = db.storage.snapshot();
+        write(&db, 1);
+        write(&db, 1);
+        assert_eq!(db.collect_garbage().unwrap(), 1);
+        let tree = db.storage.get_tree_of_table("t").unwrap();
+        assert_eq!(tree.len(), 3);
+        let schema = crate::catalog::schema::Schema::new([(
+            "id".to_string(),
+            DataType::Int64,
+        )]);
+        assert_eq!(
+            snapshot
+                .scan(&tree)
+                .map(|res| Tuple::decode(&res.unwrap().1, &schema))
+                .collect::<Vec<_>>(),
+            vec![Tuple::new([Data::Int64(1)]), Tuple::new([Data::Int64(2)])]
+        );
+
+        assert_eq!(db.collect_garbage().unwrap(), 1);
+        assert_eq!(tree.len(), 2);
+        assert_eq!(db.sql("SELECT id FROM t").unwrap().len(), 2);
+    }
+}
@@ -41,3 +41,103 @@ fn insert() {
 fn query() {
     run("tests/query.slt");
 }
+
+#[test]
+fn aggregate() {
+    run("tests/aggregate.slt");
+}
+
+#[test]
+fn window() {
+    run("tests/window.slt");
+}
+
+#[test]
+fn pattern() {
+    run("tests/pattern.slt");
+}
+
+#[test]
+fn conditional() {
+    run("tests/conditional.slt");
+}
+
+#[test]
+fn simplify() {
+    run("tests/simplify.slt");
+}
+
+#[test]
+fn datetime() {
+    run("tests/datetime.slt");
+}
+
+#[test]
+fn decimal() {
+    run("tests/decimal.slt");
+}
+
+#[test]
+fn types() {
+    run("tests/types.slt");
+}
+
+#[test]
+fn binary() {
+    run("tests/binary.slt");
+}
+
+#[test]
+fn json() {
+    run("tests/json.slt");
+}
+
+#[test]
+fn uuid() {
+    run("tests/uuid.slt");
+}
+
+#[test]
+fn array() {
+    run("tests/array.slt");
+}
+
+#[test]
+fn enum_type() {
+    run("tests/enum.slt");
+}
+
+#[test]
+fn collation() {
+    run("tests/collation.slt");
+}
+
+#[test]
+fn transaction() {
+    run("tests/transaction.slt");
+}
+
+#[test]
+fn savepoint() {
+    run("tests/savepoint.slt");
+}
+
+#[test]
+fn upsert() {
+    run("tests/upsert.slt");
+}
+
+#[test]
+fn returning() {
+    run("tests/returning.slt");
+}
+
+#[test]
+fn insert_columns() {
+    run("tests/insert_columns.slt");
+}
+
+#[test]
+fn sequence() {
+    run("tests/sequence.slt");
+}